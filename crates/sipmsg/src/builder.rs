@@ -0,0 +1,584 @@
+use crate::{
+    NameAddr, OwnedSipMessage, SipHeader, SipMethod, SipRFCHeader, SipRequest,
+    SipResponseStatusCode, SipUri, ViaHeader,
+};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt, str};
+
+/// Default value of Max-Forwards header
+/// ([rfc3261 section-8.1.1.6](https://tools.ietf.org/html/rfc3261#section-8.1.1.6))
+const DEFAULT_MAX_FORWARDS: &str = "70";
+
+/// Headers that must be present in every request
+/// ([rfc3261 section-8.1.1](https://tools.ietf.org/html/rfc3261#section-8.1.1)).
+/// CSeq and Max-Forwards are filled by builder if they are not set.
+static REQUEST_MANDATORY_HEADERS: &[SipRFCHeader] = &[
+    SipRFCHeader::To,
+    SipRFCHeader::From,
+    SipRFCHeader::CallID,
+    SipRFCHeader::Via,
+];
+
+/// Headers that must be present in every response
+/// ([rfc3261 section-8.2.6.2](https://tools.ietf.org/html/rfc3261#section-8.2.6.2))
+static RESPONSE_MANDATORY_HEADERS: &[SipRFCHeader] = &[
+    SipRFCHeader::Via,
+    SipRFCHeader::From,
+    SipRFCHeader::To,
+    SipRFCHeader::CallID,
+    SipRFCHeader::CSeq,
+];
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum BuildError {
+    /// Mandatory header was not set
+    MissingHeader(SipRFCHeader),
+    /// Built message can't be parsed back.
+    /// Usually it means that a header name or value contains forbidden characters.
+    InvalidMessage,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildError::MissingHeader(hdr) => {
+                write!(f, "missing mandatory header {}", hdr.as_str())
+            }
+            BuildError::InvalidMessage => f.write_str("invalid SIP message"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BuildError {}
+
+/// Headers and body of message under construction
+#[derive(Default)]
+struct MessageParts {
+    headers: Vec<(String, String)>,
+    /// Content type and body
+    body: Option<(String, Vec<u8>)>,
+}
+
+impl MessageParts {
+    fn push(&mut self, name: &str, value: &str) {
+        self.headers.push((name.to_string(), value.to_string()));
+    }
+
+    fn contains(&self, hdr: SipRFCHeader) -> bool {
+        self.headers
+            .iter()
+            .any(|(name, _)| SipRFCHeader::from_str(name) == Some(hdr))
+    }
+
+    fn check_mandatory(&self, mandatory: &[SipRFCHeader]) -> Result<(), BuildError> {
+        match mandatory.iter().find(|hdr| !self.contains(**hdr)) {
+            Some(hdr) => Err(BuildError::MissingHeader(*hdr)),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes start line, headers and body. Parses result to make sure that it is valid message.
+    fn build(self, start_line: &str) -> Result<OwnedSipMessage, BuildError> {
+        let mut raw = String::from(start_line);
+        raw.push_str("\r\n");
+        for (name, value) in self.headers.iter() {
+            if name.contains(['\r', '\n']) || value.contains(['\r', '\n']) {
+                return Err(BuildError::InvalidMessage);
+            }
+            match SipRFCHeader::from_str(name) {
+                // Content-Length is always computed from the body
+                Some(SipRFCHeader::ContentLength) => continue,
+                Some(SipRFCHeader::ContentType) if self.body.is_some() => continue,
+                _ => {}
+            }
+            raw.push_str(&format!("{}: {}\r\n", name, value));
+        }
+
+        let raw = match self.body {
+            Some((content_type, body)) => {
+                raw.push_str(&format!(
+                    "Content-Type: {}\r\nContent-Length: {}\r\n\r\n",
+                    content_type,
+                    body.len()
+                ));
+                let mut raw = raw.into_bytes();
+                raw.extend_from_slice(&body);
+                raw
+            }
+            None => {
+                raw.push_str("Content-Length: 0\r\n\r\n");
+                raw.into_bytes()
+            }
+        };
+
+        match OwnedSipMessage::parse(&raw) {
+            Ok((&[], msg)) => Ok(msg),
+            _ => Err(BuildError::InvalidMessage),
+        }
+    }
+}
+
+/// Builder of SIP requests.
+///
+/// Max-Forwards and CSeq headers are added if they are not set,
+/// Content-Length is always computed from the body.
+///
+/// ```rust
+/// use sipmsg::{SipMethod, SipRFCHeader, SipRequestBuilder, SipUri};
+///
+/// let (_, uri) = SipUri::parse(b"sip:bob@biloxi.com").unwrap();
+/// let msg = SipRequestBuilder::new(SipMethod::MESSAGE, &uri)
+///     .header(SipRFCHeader::Via, "SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK776asdhds")
+///     .header(SipRFCHeader::To, "Bob <sip:bob@biloxi.com>")
+///     .header(SipRFCHeader::From, "Alice <sip:alice@atlanta.com>;tag=1928301774")
+///     .header(SipRFCHeader::CallID, "a84b4c76e66710@pc33.atlanta.com")
+///     .cseq(2)
+///     .body("text/plain", b"Hello")
+///     .build()
+///     .unwrap();
+///
+/// let msg = msg.message();
+/// let request = msg.request().unwrap();
+/// assert_eq!(request.rl.method, SipMethod::MESSAGE);
+/// assert_eq!(
+///     request.headers.get_rfc_s(SipRFCHeader::CSeq).unwrap().value.vstr,
+///     "2 MESSAGE"
+/// );
+/// assert_eq!(request.body.unwrap(), b"Hello");
+/// ```
+pub struct RequestBuilder {
+    method: String,
+    uri: String,
+    cseq: u32,
+    parts: MessageParts,
+}
+
+impl RequestBuilder {
+    pub fn new(method: SipMethod, uri: &SipUri) -> RequestBuilder {
+        RequestBuilder {
+            method: method.to_string(),
+            uri: uri.to_string(),
+            cseq: 1,
+            parts: MessageParts::default(),
+        }
+    }
+
+    /// Adds header defined in rfc3261. Header may be added several times.
+    pub fn header(mut self, hdr: SipRFCHeader, value: &str) -> RequestBuilder {
+        self.parts.push(hdr.as_str(), value);
+        self
+    }
+
+    /// Adds extension header
+    pub fn ext_header(mut self, name: &str, value: &str) -> RequestBuilder {
+        self.parts.push(name, value);
+        self
+    }
+
+    /// Adds Via header. Header may be added several times.
+    pub fn via(mut self, via: &ViaHeader) -> RequestBuilder {
+        self.parts.push(SipRFCHeader::Via.as_str(), &via.to_string());
+        self
+    }
+
+    /// Adds From header
+    pub fn from(mut self, from: &NameAddr) -> RequestBuilder {
+        self.parts.push(SipRFCHeader::From.as_str(), &from.to_string());
+        self
+    }
+
+    /// Adds To header
+    pub fn to(mut self, to: &NameAddr) -> RequestBuilder {
+        self.parts.push(SipRFCHeader::To.as_str(), &to.to_string());
+        self
+    }
+
+    /// Adds Call-ID header
+    pub fn call_id(mut self, call_id: &str) -> RequestBuilder {
+        self.parts.push(SipRFCHeader::CallID.as_str(), call_id);
+        self
+    }
+
+    /// Sequence number of CSeq header. Used only if CSeq header is not set explicitly.
+    /// Default is 1
+    pub fn cseq(mut self, seq: u32) -> RequestBuilder {
+        self.cseq = seq;
+        self
+    }
+
+    /// Sets body and its Content-Type
+    pub fn body(mut self, content_type: &str, body: &[u8]) -> RequestBuilder {
+        self.parts.body = Some((content_type.to_string(), body.to_vec()));
+        self
+    }
+
+    pub fn build(mut self) -> Result<OwnedSipMessage, BuildError> {
+        self.parts.check_mandatory(REQUEST_MANDATORY_HEADERS)?;
+        if !self.parts.contains(SipRFCHeader::MaxForwards) {
+            self.parts
+                .push(SipRFCHeader::MaxForwards.as_str(), DEFAULT_MAX_FORWARDS);
+        }
+        if !self.parts.contains(SipRFCHeader::CSeq) {
+            let cseq = format!("{} {}", self.cseq, self.method);
+            self.parts.push(SipRFCHeader::CSeq.as_str(), &cseq);
+        }
+        let request_line = format!("{} {} SIP/2.0", self.method, self.uri);
+        self.parts.build(&request_line)
+    }
+
+    /// Builds request in wire format
+    pub fn to_bytes(self) -> Result<Vec<u8>, BuildError> {
+        Ok(self.build()?.into_bytes())
+    }
+}
+
+/// Builder of SIP responses.
+///
+/// ```rust
+/// use sipmsg::{SipMessage, SipRFCHeader, SipResponseBuilder, SipResponseStatusCode};
+///
+/// let (_, invite) = SipMessage::parse(b"INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+///     Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK776asdhds\r\n\
+///     To: Bob <sip:bob@biloxi.com>\r\n\
+///     From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+///     Call-ID: a84b4c76e66710@pc33.atlanta.com\r\n\
+///     CSeq: 314159 INVITE\r\n\
+///     Content-Length: 0\r\n\r\n").unwrap();
+///
+/// let ringing = SipResponseBuilder::from_request(invite.request().unwrap(), SipResponseStatusCode::Ringing)
+///     .to_tag("a6c85cf")
+///     .header(SipRFCHeader::Contact, "<sip:bob@192.0.2.4>")
+///     .to_bytes()
+///     .unwrap();
+///
+/// assert!(ringing.starts_with(b"SIP/2.0 180 Ringing\r\n"));
+/// ```
+pub struct ResponseBuilder {
+    status_code: SipResponseStatusCode,
+    reason_phrase: Option<String>,
+    to_tag: Option<String>,
+    parts: MessageParts,
+}
+
+impl ResponseBuilder {
+    pub fn new(status_code: SipResponseStatusCode) -> ResponseBuilder {
+        ResponseBuilder {
+            status_code,
+            reason_phrase: None,
+            to_tag: None,
+            parts: MessageParts::default(),
+        }
+    }
+
+    /// Creates response to the request.
+    /// Via, From, To, Call-ID and CSeq headers are copied from the request
+    /// ([rfc3261 section-8.2.6.2](https://tools.ietf.org/html/rfc3261#section-8.2.6.2)).
+    pub fn from_request(req: &SipRequest, status_code: SipResponseStatusCode) -> ResponseBuilder {
+        let mut builder = ResponseBuilder::new(status_code);
+        for hdr in RESPONSE_MANDATORY_HEADERS {
+            let hdrs = match req.headers.get_rfc(*hdr) {
+                Some(hdrs) => hdrs,
+                None => continue,
+            };
+            for header in hdrs {
+                // Parsed header value is always valid utf-8
+                let value = str::from_utf8(header.raw_value_param).unwrap_or_default();
+                builder.parts.push(hdr.as_str(), value.trim_end());
+            }
+        }
+        builder
+    }
+
+    /// Reason phrase of status line. By default it is taken from status code
+    pub fn reason_phrase(mut self, reason_phrase: &str) -> ResponseBuilder {
+        self.reason_phrase = Some(reason_phrase.to_string());
+        self
+    }
+
+    /// Tag that is added to To header if it has not a tag yet
+    pub fn to_tag(mut self, tag: &str) -> ResponseBuilder {
+        self.to_tag = Some(tag.to_string());
+        self
+    }
+
+    /// Adds header defined in rfc3261. Header may be added several times.
+    pub fn header(mut self, hdr: SipRFCHeader, value: &str) -> ResponseBuilder {
+        self.parts.push(hdr.as_str(), value);
+        self
+    }
+
+    /// Adds extension header
+    pub fn ext_header(mut self, name: &str, value: &str) -> ResponseBuilder {
+        self.parts.push(name, value);
+        self
+    }
+
+    /// Adds Via header. Header may be added several times.
+    pub fn via(mut self, via: &ViaHeader) -> ResponseBuilder {
+        self.parts.push(SipRFCHeader::Via.as_str(), &via.to_string());
+        self
+    }
+
+    /// Adds From header
+    pub fn from(mut self, from: &NameAddr) -> ResponseBuilder {
+        self.parts.push(SipRFCHeader::From.as_str(), &from.to_string());
+        self
+    }
+
+    /// Adds To header
+    pub fn to(mut self, to: &NameAddr) -> ResponseBuilder {
+        self.parts.push(SipRFCHeader::To.as_str(), &to.to_string());
+        self
+    }
+
+    /// Adds Call-ID header
+    pub fn call_id(mut self, call_id: &str) -> ResponseBuilder {
+        self.parts.push(SipRFCHeader::CallID.as_str(), call_id);
+        self
+    }
+
+    /// Adds CSeq header
+    pub fn cseq(mut self, seq: u32, method: SipMethod) -> ResponseBuilder {
+        let cseq = format!("{} {}", seq, method);
+        self.parts.push(SipRFCHeader::CSeq.as_str(), &cseq);
+        self
+    }
+
+    /// Sets body and its Content-Type
+    pub fn body(mut self, content_type: &str, body: &[u8]) -> ResponseBuilder {
+        self.parts.body = Some((content_type.to_string(), body.to_vec()));
+        self
+    }
+
+    pub fn build(mut self) -> Result<OwnedSipMessage, BuildError> {
+        self.parts.check_mandatory(RESPONSE_MANDATORY_HEADERS)?;
+        if let Some(tag) = &self.to_tag {
+            let to = self
+                .parts
+                .headers
+                .iter_mut()
+                .find(|(name, _)| SipRFCHeader::from_str(name) == Some(SipRFCHeader::To));
+            if let Some((_, value)) = to {
+                if !ResponseBuilder::has_tag(value)? {
+                    value.push_str(";tag=");
+                    value.push_str(tag);
+                }
+            }
+        }
+        let reason_phrase = match &self.reason_phrase {
+            Some(reason_phrase) => reason_phrase.as_str(),
+            None => self.status_code.reason_phrase(),
+        };
+        let status_line = format!("SIP/2.0 {} {}", self.status_code.code(), reason_phrase);
+        self.parts.build(&status_line)
+    }
+
+    /// Builds response in wire format
+    pub fn to_bytes(self) -> Result<Vec<u8>, BuildError> {
+        Ok(self.build()?.into_bytes())
+    }
+
+    fn has_tag(to_value: &str) -> Result<bool, BuildError> {
+        let line = format!("To: {}\r\n", to_value);
+        match SipHeader::parse(line.as_bytes()) {
+            Ok((_, (_, headers))) => Ok(headers
+                .iter()
+                .any(|hdr| hdr.params().is_some_and(|p| p.get("tag").is_some()))),
+            Err(_) => Err(BuildError::InvalidMessage),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SipMessage, SipRFCHeader};
+
+    fn uri() -> SipUri<'static> {
+        SipUri::parse(b"sip:bob@biloxi.com;transport=tcp")
+            .unwrap()
+            .1
+    }
+
+    fn invite_builder() -> RequestBuilder {
+        RequestBuilder::new(SipMethod::INVITE, &uri())
+            .header(
+                SipRFCHeader::Via,
+                "SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8",
+            )
+            .header(SipRFCHeader::To, "Bob <sip:bob@biloxi.com>")
+            .header(
+                SipRFCHeader::From,
+                "Alice <sip:alice@atlanta.com>;tag=1928301774",
+            )
+            .header(SipRFCHeader::CallID, "a84b4c76e66710")
+    }
+
+    #[test]
+    fn build_request() {
+        let owned = invite_builder()
+            .ext_header("X-Custom", "custom value")
+            .header(SipRFCHeader::ContentLength, "1000")
+            .body("application/sdp", b"v=0\r\n")
+            .build()
+            .unwrap();
+        let msg = owned.message();
+        let req = msg.request().unwrap();
+        assert_eq!(
+            req.rl.raw,
+            b"INVITE sip:bob@biloxi.com;transport=tcp SIP/2.0\r\n"
+        );
+        let value = |hdr| req.headers.get_rfc_s(hdr).unwrap().value.vstr;
+        assert_eq!(value(SipRFCHeader::MaxForwards), "70");
+        assert_eq!(value(SipRFCHeader::CSeq), "1 INVITE");
+        assert_eq!(value(SipRFCHeader::ContentType), "application/sdp");
+        assert_eq!(value(SipRFCHeader::ContentLength), "5");
+        assert_eq!(
+            req.headers
+                .get_rfc(SipRFCHeader::ContentLength)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            req.headers.get_ext_s("x-custom").unwrap().value.vstr,
+            "custom value"
+        );
+        assert_eq!(req.body.unwrap(), b"v=0\r\n");
+    }
+
+    #[test]
+    fn build_request_errors() {
+        let res = RequestBuilder::new(SipMethod::OPTIONS, &uri())
+            .header(SipRFCHeader::To, "<sip:bob@biloxi.com>")
+            .build();
+        assert_eq!(res, Err(BuildError::MissingHeader(SipRFCHeader::From)));
+
+        let res = invite_builder()
+            .ext_header("X-Injection", "value\r\nContent-Length: 10")
+            .to_bytes();
+        assert_eq!(res, Err(BuildError::InvalidMessage));
+
+        let res = invite_builder().ext_header("Bad Name", "value").to_bytes();
+        assert_eq!(res, Err(BuildError::InvalidMessage));
+    }
+
+    #[test]
+    fn build_response_from_request() {
+        let owned = invite_builder()
+            .header(SipRFCHeader::Via, "SIP/2.0/UDP 192.168.1.111")
+            .cseq(314159)
+            .build()
+            .unwrap();
+        let msg = owned.message();
+        let req = msg.request().unwrap();
+
+        let resp = ResponseBuilder::from_request(req, SipResponseStatusCode::BusyHere)
+            .to_tag("a6c85cf")
+            .reason_phrase("Busy")
+            .to_bytes()
+            .unwrap();
+        let (_, msg) = SipMessage::parse(&resp).unwrap();
+        let resp = msg.response().unwrap();
+        assert_eq!(resp.sl.raw, b"SIP/2.0 486 Busy\r\n");
+        let vias = resp.headers.get_rfc(SipRFCHeader::Via).unwrap();
+        assert_eq!(vias.len(), 2);
+        assert_eq!(vias[1].value.vstr, "SIP/2.0/UDP 192.168.1.111");
+        let to = resp.headers.get_rfc_s(SipRFCHeader::To).unwrap();
+        assert_eq!(to.params().unwrap().get(&"tag"), Some(&Some("a6c85cf")));
+        assert_eq!(
+            resp.headers
+                .get_rfc_s(SipRFCHeader::CSeq)
+                .unwrap()
+                .value
+                .vstr,
+            "314159 INVITE"
+        );
+        assert!(resp.headers.get_rfc(SipRFCHeader::MaxForwards).is_none());
+
+        // To header of in-dialog request already has a tag
+        let owned = RequestBuilder::new(SipMethod::BYE, &uri())
+            .header(
+                SipRFCHeader::Via,
+                "SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds9",
+            )
+            .header(SipRFCHeader::To, "Bob <sip:bob@biloxi.com>;tag=a6c85cf")
+            .header(
+                SipRFCHeader::From,
+                "Alice <sip:alice@atlanta.com>;tag=1928301774",
+            )
+            .header(SipRFCHeader::CallID, "a84b4c76e66710")
+            .build()
+            .unwrap();
+        let msg = owned.message();
+        let resp = ResponseBuilder::from_request(msg.request().unwrap(), SipResponseStatusCode::OK)
+            .to_tag("other")
+            .build()
+            .unwrap();
+        let msg = resp.message();
+        let to = msg.headers().get_rfc_s(SipRFCHeader::To).unwrap();
+        assert_eq!(to.raw_value_param, b"Bob <sip:bob@biloxi.com>;tag=a6c85cf");
+    }
+
+    #[test]
+    fn build_response_errors() {
+        let res = ResponseBuilder::new(SipResponseStatusCode::OK)
+            .header(SipRFCHeader::Via, "SIP/2.0/UDP pc33.atlanta.com")
+            .build();
+        assert_eq!(res, Err(BuildError::MissingHeader(SipRFCHeader::From)));
+    }
+
+    #[test]
+    fn build_typed_headers() {
+        let (_, via) =
+            ViaHeader::parse(b"SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n").unwrap();
+        let (_, from) =
+            NameAddr::parse(b"Alice <sip:alice@atlanta.com>;tag=1928301774\r\n").unwrap();
+        let (_, to) = NameAddr::parse(b"Bob <sip:bob@biloxi.com>\r\n").unwrap();
+        let owned = RequestBuilder::new(SipMethod::OPTIONS, &uri())
+            .via(&via)
+            .from(&from)
+            .to(&to)
+            .call_id("a84b4c76e66710")
+            .build()
+            .unwrap();
+        let msg = owned.message();
+        let req = msg.request().unwrap();
+        let value = |hdr| req.headers.get_rfc_s(hdr).unwrap().raw_value_param;
+        assert_eq!(
+            value(SipRFCHeader::Via),
+            b"SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8"
+        );
+        assert_eq!(
+            value(SipRFCHeader::From),
+            b"\"Alice\" <sip:alice@atlanta.com>;tag=1928301774"
+        );
+        assert_eq!(value(SipRFCHeader::To), b"\"Bob\" <sip:bob@biloxi.com>");
+        assert_eq!(value(SipRFCHeader::CallID), b"a84b4c76e66710");
+
+        let resp = ResponseBuilder::new(SipResponseStatusCode::OK)
+            .via(&via)
+            .from(&from)
+            .to(&to)
+            .call_id("a84b4c76e66710")
+            .cseq(2, SipMethod::OPTIONS)
+            .build()
+            .unwrap();
+        let msg = resp.message();
+        let cseq = msg.headers().get_rfc_s(SipRFCHeader::CSeq).unwrap();
+        assert_eq!(cseq.value.vstr, "2 OPTIONS");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn build_error_is_std_error() {
+        let err: std::boxed::Box<dyn std::error::Error> =
+            std::boxed::Box::new(BuildError::MissingHeader(SipRFCHeader::To));
+        assert_eq!(err.to_string(), "missing mandatory header To");
+    }
+}
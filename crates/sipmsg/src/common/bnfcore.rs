@@ -263,3 +263,27 @@ pub fn is_user_unreserved_char(c: u8) -> bool {
 pub fn is_password_char(c: u8) -> bool {
     is_unreserved(c) || c == b'&' || c == b'=' || c == b'+' || c == b'$' || c == b','
 }
+
+/// hnv-unreserved  =  "[" / "]" / "/" / "?" / ":" / "+" / "$"
+#[inline]
+pub fn is_hnv_unreserved_char(c: u8) -> bool {
+    c == b'[' || c == b']' || c == b'/' || c == b'?' || c == b':' || c == b'+' || c == b'$'
+}
+
+/// Characters of hname and hvalue of SIP URI header except escaped
+#[inline]
+pub fn is_hnv_char(c: u8) -> bool {
+    is_unreserved(c) || is_hnv_unreserved_char(c)
+}
+
+/// param-unreserved  =  "[" / "]" / "/" / ":" / "&" / "+" / "$"
+#[inline]
+pub fn is_param_unreserved_char(c: u8) -> bool {
+    c == b'[' || c == b']' || c == b'/' || c == b':' || c == b'&' || c == b'+' || c == b'$'
+}
+
+/// paramchar  =  param-unreserved / unreserved / escaped
+#[inline]
+pub fn is_paramchar(c: u8) -> bool {
+    is_param_unreserved_char(c) || is_unreserved(c)
+}
@@ -1,36 +1,110 @@
 use core::convert::From;
-use core::str;
+use core::fmt;
 use nom;
 use nom::error::{ErrorKind, ParseError};
 
-#[derive(Debug)]
-pub struct SipParseError<'a> {
+/// Element of message that failed to parse, variant of [`SipParseError`] without details
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum SipParseErrorKind {
+    /// Input ended before the message was complete
+    Incomplete,
+    /// Request line or status line
+    StartLine,
+    HeaderName,
+    HeaderValue,
+    Uri,
+    /// Parameter of URI or header
+    Parameter,
+    Body,
+    /// Element is not known
+    Other,
+}
+
+impl SipParseErrorKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SipParseErrorKind::Incomplete => "incomplete message",
+            SipParseErrorKind::StartLine => "invalid start line",
+            SipParseErrorKind::HeaderName => "invalid header name",
+            SipParseErrorKind::HeaderValue => "invalid header value",
+            SipParseErrorKind::Uri => "invalid URI",
+            SipParseErrorKind::Parameter => "invalid parameter",
+            SipParseErrorKind::Body => "invalid body",
+            SipParseErrorKind::Other => "parse error",
+        }
+    }
+}
+
+/// Position of error in message
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SipParseErrorPosition {
+    /// Offset in bytes from the beginning of message
+    pub offset: usize,
+    /// Starts from 1
+    pub line: usize,
+    /// Starts from 1
+    pub column: usize,
+}
+
+/// Details of error in the element that failed to parse
+#[derive(Clone, PartialEq, Debug)]
+pub struct SipParseErrorDetails<'a> {
+    /// Code of error defined by the element parser.
+    /// 0 means that input doesn't match the grammar of element
     pub code: u32,
     pub message: Option<&'a str>,
+    /// Name of header that failed to parse
+    pub header: Option<&'a str>,
+    /// Position of error. It is set by message parsers
+    /// ([`SipMessage::parse`](crate::SipMessage::parse) and others)
+    pub position: Option<SipParseErrorPosition>,
+    /// Remaining input at the moment of error
+    input: Option<&'a [u8]>,
+}
+
+/// Error of parsing SIP message or its element
+///
+/// ```rust
+/// use sipmsg::{SipMessage, SipParseError};
+///
+/// let err = match SipMessage::parse(b"INVITE sip:bob@biloxi.com SIP/2.0\r\nVia SIP/2.0/UDP h\r\n\r\n") {
+///     Err(err) => SipParseError::from(err),
+///     Ok(_) => panic!("message is invalid"),
+/// };
+/// match err {
+///     SipParseError::HeaderName(details) => assert_eq!(details.position.unwrap().line, 2),
+///     _ => panic!("unexpected error {}", err),
+/// }
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub enum SipParseError<'a> {
+    /// Input ended before the message was complete
+    Incomplete,
+    /// Request line or status line
+    StartLine(SipParseErrorDetails<'a>),
+    HeaderName(SipParseErrorDetails<'a>),
+    HeaderValue(SipParseErrorDetails<'a>),
+    Uri(SipParseErrorDetails<'a>),
+    /// Parameter of URI or header
+    Parameter(SipParseErrorDetails<'a>),
+    Body(SipParseErrorDetails<'a>),
+    /// Element is not known
+    Other(SipParseErrorDetails<'a>),
 }
 
 impl<'a> From<(&'a str, ErrorKind)> for SipParseError<'a> {
     fn from(error: (&'a str, ErrorKind)) -> Self {
-        SipParseError {
-            code: error.1 as u32,
-            message: Some(error.0),
-        }
+        SipParseError::from_error_kind(error.0.as_bytes(), error.1)
     }
 }
 
 impl<'a> ParseError<&'a str> for SipParseError<'a> {
     fn from_error_kind(error: &'a str, kind: ErrorKind) -> Self {
-        SipParseError {
-            code: kind as u32,
-            message: Some(error),
-        }
+        SipParseError::from_error_kind(error.as_bytes(), kind)
     }
 
-    fn append(error: &'a str, kind: ErrorKind, _other: SipParseError) -> Self {
-        SipParseError {
-            code: kind as u32,
-            message: Some(error),
-        }
+    fn append(_error: &'a str, _kind: ErrorKind, other: SipParseError<'a>) -> Self {
+        other
     }
 }
 
@@ -51,44 +125,196 @@ macro_rules! sip_parse_error {
 }
 
 impl<'a> SipParseError<'a> {
-    pub fn new(code: u32, message: Option<&'a str>) -> SipParseError {
-        SipParseError {
-            code: code,
-            message: message,
+    /// Error of unknown element. Its kind is set by the parser of enclosing element
+    pub fn new(code: u32, message: Option<&'a str>) -> SipParseError<'a> {
+        SipParseError::Other(SipParseErrorDetails {
+            code,
+            message,
+            header: None,
+            position: None,
+            input: None,
+        })
+    }
+
+    /// Error of incomplete input
+    pub fn incomplete() -> SipParseError<'a> {
+        SipParseError::Incomplete
+    }
+
+    pub fn is_incomplete(&self) -> bool {
+        *self == SipParseError::Incomplete
+    }
+
+    pub fn kind(&self) -> SipParseErrorKind {
+        match self {
+            SipParseError::Incomplete => SipParseErrorKind::Incomplete,
+            SipParseError::StartLine(_) => SipParseErrorKind::StartLine,
+            SipParseError::HeaderName(_) => SipParseErrorKind::HeaderName,
+            SipParseError::HeaderValue(_) => SipParseErrorKind::HeaderValue,
+            SipParseError::Uri(_) => SipParseErrorKind::Uri,
+            SipParseError::Parameter(_) => SipParseErrorKind::Parameter,
+            SipParseError::Body(_) => SipParseErrorKind::Body,
+            SipParseError::Other(_) => SipParseErrorKind::Other,
         }
     }
-}
 
-impl<'a> ParseError<&'a [u8]> for SipParseError<'a> {
-    fn from_error_kind(error: &'a [u8], kind: ErrorKind) -> Self {
-        let error_str: &str;
-        match str::from_utf8(error) {
-            Ok(err_str) => {
-                error_str = err_str;
-            }
-            Err(_) => {
-                error_str = "Internal error of parser. Can't cast error string to to utf8";
-            }
+    /// `None` for incomplete input
+    pub fn details(&self) -> Option<&SipParseErrorDetails<'a>> {
+        match self {
+            SipParseError::Incomplete => None,
+            SipParseError::StartLine(details)
+            | SipParseError::HeaderName(details)
+            | SipParseError::HeaderValue(details)
+            | SipParseError::Uri(details)
+            | SipParseError::Parameter(details)
+            | SipParseError::Body(details)
+            | SipParseError::Other(details) => Some(details),
         }
-        SipParseError {
-            code: kind as u32,
-            message: Some(error_str),
+    }
+
+    fn details_mut(&mut self) -> Option<&mut SipParseErrorDetails<'a>> {
+        match self {
+            SipParseError::Incomplete => None,
+            SipParseError::StartLine(details)
+            | SipParseError::HeaderName(details)
+            | SipParseError::HeaderValue(details)
+            | SipParseError::Uri(details)
+            | SipParseError::Parameter(details)
+            | SipParseError::Body(details)
+            | SipParseError::Other(details) => Some(details),
         }
     }
 
-    fn append(error: &'a [u8], kind: ErrorKind, _other: SipParseError) -> Self {
-        let error_str: &str;
-        match str::from_utf8(error) {
-            Ok(err_str) => {
-                error_str = err_str;
+    /// Sets kind of error if it was not set by nested parser.
+    /// `element` is the input of element parser,
+    /// it is used as position of error if the exact position is unknown.
+    pub(crate) fn with_kind(self, kind: SipParseErrorKind, element: &'a [u8]) -> Self {
+        let mut err = match self {
+            SipParseError::Other(details) => match kind {
+                SipParseErrorKind::Incomplete => SipParseError::Incomplete,
+                SipParseErrorKind::StartLine => SipParseError::StartLine(details),
+                SipParseErrorKind::HeaderName => SipParseError::HeaderName(details),
+                SipParseErrorKind::HeaderValue => SipParseError::HeaderValue(details),
+                SipParseErrorKind::Uri => SipParseError::Uri(details),
+                SipParseErrorKind::Parameter => SipParseError::Parameter(details),
+                SipParseErrorKind::Body => SipParseError::Body(details),
+                SipParseErrorKind::Other => SipParseError::Other(details),
+            },
+            err => err,
+        };
+        if let Some(details) = err.details_mut() {
+            if details.input.is_none() {
+                details.input = Some(element);
             }
-            Err(_) => {
-                error_str = "Internal error of parser. Can't cast error string to to utf8";
+        }
+        err
+    }
+
+    /// Sets name of header where error occurred
+    pub(crate) fn in_header(mut self, name: &'a str) -> Self {
+        if let Some(details) = self.details_mut() {
+            if details.header.is_none() {
+                details.header = Some(name);
             }
         }
-        SipParseError {
-            code: kind as u32,
-            message: Some(error_str),
+        self
+    }
+
+    /// Calculates position of error in the message
+    pub(crate) fn locate(mut self, message: &[u8]) -> Self {
+        self.set_position(message);
+        self
+    }
+
+    pub(crate) fn set_position(&mut self, message: &[u8]) {
+        let details = match self.details_mut() {
+            Some(details) if details.position.is_none() => details,
+            _ => return,
+        };
+        let input = match details.input {
+            Some(input) => input,
+            None => return,
+        };
+        let start = message.as_ptr() as usize;
+        let error_start = input.as_ptr() as usize;
+        if error_start < start || error_start > start + message.len() {
+            return;
+        }
+        let offset = error_start - start;
+        let before = &message[..offset];
+        let line_start = match before.iter().rposition(|c| *c == b'\n') {
+            Some(idx) => idx + 1,
+            None => 0,
+        };
+        details.position = Some(SipParseErrorPosition {
+            offset,
+            line: before.iter().filter(|c| **c == b'\n').count() + 1,
+            column: offset - line_start + 1,
+        });
+    }
+}
+
+/// Applies `f` to error, leaves `nom::Err::Incomplete` as is
+pub(crate) fn map_sip_err<'a, F>(
+    err: nom::Err<SipParseError<'a>>,
+    f: F,
+) -> nom::Err<SipParseError<'a>>
+where
+    F: FnOnce(SipParseError<'a>) -> SipParseError<'a>,
+{
+    match err {
+        nom::Err::Error(e) => nom::Err::Error(f(e)),
+        nom::Err::Failure(e) => nom::Err::Failure(f(e)),
+        nom::Err::Incomplete(needed) => nom::Err::Incomplete(needed),
+    }
+}
+
+impl<'a> From<nom::Err<SipParseError<'a>>> for SipParseError<'a> {
+    fn from(err: nom::Err<SipParseError<'a>>) -> Self {
+        match err {
+            nom::Err::Error(e) | nom::Err::Failure(e) => e,
+            nom::Err::Incomplete(_) => SipParseError::incomplete(),
+        }
+    }
+}
+
+impl<'a> fmt::Display for SipParseError<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.kind().as_str())?;
+        let details = match self.details() {
+            Some(details) => details,
+            None => return Ok(()),
+        };
+        if let Some(header) = details.header {
+            write!(f, " in header {}", header)?;
         }
+        if let Some(position) = &details.position {
+            write!(f, " at line {}, column {}", position.line, position.column)?;
+        }
+        match details.message {
+            Some(message) => write!(f, ": {}", message),
+            None if details.code == 0 => Ok(()),
+            None => write!(f, " (code {})", details.code),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> std::error::Error for SipParseError<'a> {}
+
+impl<'a> ParseError<&'a [u8]> for SipParseError<'a> {
+    /// Kind of nom parser is internal detail, so error gets code 0
+    fn from_error_kind(error: &'a [u8], _kind: ErrorKind) -> Self {
+        SipParseError::Other(SipParseErrorDetails {
+            code: 0,
+            message: None,
+            header: None,
+            position: None,
+            input: Some(error),
+        })
+    }
+
+    fn append(_error: &'a [u8], _kind: ErrorKind, other: SipParseError<'a>) -> Self {
+        other
     }
 }
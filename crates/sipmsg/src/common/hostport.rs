@@ -1,5 +1,5 @@
 use crate::common::{bnfcore::*, errorparse::SipParseError, nom_wrappers::from_utf8_nom};
-use core::str;
+use core::{fmt, str};
 use nom::bytes::complete::{take, take_until, take_while1};
 
 // domainlabel      =  alphanum / alphanum *( alphanum / "-" ) alphanum
@@ -85,6 +85,21 @@ impl<'a> HostPort<'a> {
     }
 }
 
+impl<'a> fmt::Display for HostPort<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.host.contains(':') {
+            // IPv6reference
+            write!(f, "[{}]", self.host)?;
+        } else {
+            f.write_str(self.host)?;
+        }
+        match self.port {
+            Some(port) => write!(f, ":{}", port),
+            None => Ok(()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,6 +127,14 @@ mod tests {
         host_port_test_case("[2001:db8::10]:8080", "2001:db8::10", Some(8080), "");
     }
 
+    #[test]
+    fn host_port_display() {
+        for hp in ["127.0.0.1", "google.com:5060", "[2001:db8::10]", "[2001:db8::10]:8080"].iter() {
+            let (_, hostport) = HostPort::parse(hp.as_bytes()).unwrap();
+            assert_eq!(alloc::format!("{}", hostport), *hp);
+        }
+    }
+
     #[test]
     fn host_parse_with_rest() {
         host_port_test_case(
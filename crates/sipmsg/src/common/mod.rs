@@ -5,6 +5,9 @@ pub mod hostport;
 pub mod nom_wrappers;
 
 pub mod bnfcore;
+pub mod percent;
 pub mod take_sws_token;
 
+pub mod sip_date;
 pub mod sip_method;
+pub mod transport;
@@ -0,0 +1,148 @@
+//! Decoding and encoding of escaped characters (`%XX`) of URI components
+//! ([rfc3261 section-19.1.2](https://tools.ietf.org/html/rfc3261#section-19.1.2)).
+//!
+//! Parsers keep components as they are written in the message,
+//! these functions convert them to the plain form and back.
+//!
+//! ```rust
+//! use sipmsg::common::percent;
+//!
+//! assert_eq!(percent::decode("alice%40atlanta.com"), "alice@atlanta.com");
+//! assert_eq!(percent::encode_header_value("alice@atlanta.com"), "alice%40atlanta.com");
+//! assert_eq!(percent::encode_user("alice smith"), "alice%20smith");
+//! ```
+use crate::common::bnfcore::{
+    is_escaped, is_hnv_char, is_paramchar, is_password_char, is_unreserved, is_user_unreserved_char,
+};
+use alloc::{borrow::Cow, string::String, vec::Vec};
+
+/// Bytes of string with escaped characters decoded
+pub(crate) fn decoded_bytes(s: &str) -> impl Iterator<Item = u8> + '_ {
+    let bytes = s.as_bytes();
+    let mut idx = 0;
+    core::iter::from_fn(move || {
+        if idx >= bytes.len() {
+            return None;
+        }
+        if is_escaped(&bytes[idx..]) {
+            let c = u8::from_str_radix(&s[idx + 1..idx + 3], 16).ok();
+            idx += 3;
+            c
+        } else {
+            idx += 1;
+            Some(bytes[idx - 1])
+        }
+    })
+}
+
+/// Bytes of string with only escaped unreserved characters decoded, so `%61` is `a`
+/// but `%40` is not `@`. The flag is true for bytes that stay escaped
+pub(crate) fn unreserved_decoded_bytes(s: &str) -> impl Iterator<Item = (u8, bool)> + '_ {
+    let bytes = s.as_bytes();
+    let mut idx = 0;
+    core::iter::from_fn(move || {
+        if idx >= bytes.len() {
+            return None;
+        }
+        if is_escaped(&bytes[idx..]) {
+            let c = u8::from_str_radix(&s[idx + 1..idx + 3], 16).ok()?;
+            idx += 3;
+            Some((c, !is_unreserved(c)))
+        } else {
+            idx += 1;
+            Some((bytes[idx - 1], false))
+        }
+    })
+}
+
+/// Decodes escaped characters. `%` that is not followed by two hex digits is kept as is.
+/// Decoded bytes that are not valid UTF-8 are replaced with `U+FFFD`.
+/// Returns borrowed string if there is nothing to decode.
+pub fn decode(s: &str) -> Cow<'_, str> {
+    if !s.contains('%') {
+        return Cow::Borrowed(s);
+    }
+    let bytes: Vec<u8> = decoded_bytes(s).collect();
+    match String::from_utf8(bytes) {
+        Ok(decoded) => Cow::Owned(decoded),
+        Err(e) => Cow::Owned(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+    }
+}
+
+/// Escapes every byte of `s` for which `is_allowed` returns false.
+/// Returns borrowed string if there is nothing to escape.
+pub fn encode(s: &str, is_allowed: fn(u8) -> bool) -> Cow<'_, str> {
+    if s.bytes().all(is_allowed) {
+        return Cow::Borrowed(s);
+    }
+    let mut encoded = String::with_capacity(s.len() + 8);
+    for c in s.bytes() {
+        if is_allowed(c) {
+            encoded.push(char::from(c));
+        } else {
+            encoded.push_str(&alloc::format!("%{:02X}", c));
+        }
+    }
+    Cow::Owned(encoded)
+}
+
+/// user  =  1*( unreserved / escaped / user-unreserved )
+#[inline]
+fn is_user_char(c: u8) -> bool {
+    is_unreserved(c) || is_user_unreserved_char(c)
+}
+
+/// Encodes user part of SIP URI
+pub fn encode_user(s: &str) -> Cow<'_, str> {
+    encode(s, is_user_char)
+}
+
+/// Encodes password of SIP URI
+pub fn encode_password(s: &str) -> Cow<'_, str> {
+    encode(s, is_password_char)
+}
+
+/// Encodes name or value of SIP URI parameter
+pub fn encode_param(s: &str) -> Cow<'_, str> {
+    encode(s, is_paramchar)
+}
+
+/// Encodes name of SIP URI header
+pub fn encode_header_name(s: &str) -> Cow<'_, str> {
+    encode(s, is_hnv_char)
+}
+
+/// Encodes value of SIP URI header
+pub fn encode_header_value(s: &str) -> Cow<'_, str> {
+    encode(s, is_hnv_char)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        assert!(matches!(decode("alice"), Cow::Borrowed("alice")));
+        assert_eq!(decode("%61lice%2Bbob%2b"), "alice+bob+");
+        assert_eq!(decode("100%"), "100%");
+        assert_eq!(decode("%zz%4"), "%zz%4");
+        assert_eq!(decode("%D0%B0"), "а");
+        assert_eq!(decode("%FF"), "\u{FFFD}");
+    }
+
+    #[test]
+    fn test_encode() {
+        assert!(matches!(encode_user("+1-212;x=1"), Cow::Borrowed(_)));
+        assert_eq!(encode_user("alice@home"), "alice%40home");
+        assert_eq!(encode_password("pa:ss;"), "pa%3Ass%3B");
+        assert_eq!(encode_param("a b;c"), "a%20b%3Bc");
+        assert_eq!(encode_header_name("sub&ject"), "sub%26ject");
+        assert_eq!(
+            encode_header_value("sip:bob@biloxi.com"),
+            "sip:bob%40biloxi.com"
+        );
+        assert_eq!(encode_header_value("а"), "%D0%B0");
+        assert_eq!(decode(&encode_header_value("a=b&c d%")), "a=b&c d%");
+    }
+}
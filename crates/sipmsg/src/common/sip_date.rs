@@ -0,0 +1,290 @@
+use crate::common::errorparse::SipParseError;
+use core::fmt;
+use nom::{
+    bytes::complete::{tag, take, take_while_m_n},
+    character::complete::char,
+    character::is_digit,
+};
+
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// SIP-date  =  rfc1123-date
+/// ([rfc3261 section-20.17](https://tools.ietf.org/html/rfc3261#section-20.17)).
+/// Date and time in GMT, e.g. `Sat, 13 Nov 2010 23:29:00 GMT`
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct SipDate {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+fn is_leap_year(year: u16) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of proleptic Gregorian calendar date
+fn days_from_civil(year: u16, month: u8, day: u8) -> i64 {
+    // Year starts at March, so leap day is the last day of year
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Calendar date of days since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+impl SipDate {
+    /// Returns `None` if date or time is invalid. `month` is 1-12, year is 4DIGIT
+    pub fn new(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Option<SipDate> {
+        if year > 9999
+            || !(1..=12).contains(&month)
+            || day == 0
+            || day > days_in_month(year, month)
+            || hour > 23
+            || minute > 59
+            || second > 59
+        {
+            return None;
+        }
+        Some(SipDate {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    /// Date of seconds since the UNIX epoch.
+    /// Returns `None` if year is out of 0-9999 range
+    pub fn from_unix_seconds(seconds: i64) -> Option<SipDate> {
+        let days = seconds.div_euclid(SECONDS_PER_DAY);
+        let time = seconds.rem_euclid(SECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+        if !(0..=9999).contains(&year) {
+            return None;
+        }
+        SipDate::new(
+            year as u16,
+            month,
+            day,
+            (time / 3600) as u8,
+            (time % 3600 / 60) as u8,
+            (time % 60) as u8,
+        )
+    }
+
+    /// Seconds since the UNIX epoch, negative before 1970
+    pub fn unix_seconds(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day) * SECONDS_PER_DAY
+            + i64::from(self.hour) * 3600
+            + i64::from(self.minute) * 60
+            + i64::from(self.second)
+    }
+
+    /// Date after `seconds`, e.g. the end of Expires or Retry-After interval.
+    /// Returns `None` if year overflows 9999
+    pub fn checked_add_seconds(&self, seconds: u32) -> Option<SipDate> {
+        SipDate::from_unix_seconds(self.unix_seconds() + i64::from(seconds))
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    /// Month of year 1-12
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// Day of week, 0 is Sunday
+    pub fn weekday(&self) -> u8 {
+        // 1970-01-01 is Thursday
+        (days_from_civil(self.year, self.month, self.day) + 4).rem_euclid(7) as u8
+    }
+
+    // rfc1123-date  =  wkday "," SP date1 SP time SP "GMT"
+    // date1         =  2DIGIT SP month SP 4DIGIT
+    // time          =  2DIGIT ":" 2DIGIT ":" 2DIGIT
+    /// Parses date with validation of calendar, time and day of week
+    pub fn parse(input: &[u8]) -> nom::IResult<&[u8], SipDate, SipParseError<'_>> {
+        let (input, wkday) = take(3usize)(input)?;
+        let wkday = match WEEKDAYS.iter().position(|d| d.as_bytes() == wkday) {
+            Some(wkday) => wkday as u8,
+            None => return sip_parse_error!(1, "Invalid wday value in Date header"),
+        };
+        let (input, _) = tag(", ")(input)?;
+        let (input, day) = take_number(input, 1, 2)?;
+        let (input, _) = char(' ')(input)?;
+        let (input, month) = take(3usize)(input)?;
+        let month = match MONTHS.iter().position(|m| m.as_bytes() == month) {
+            Some(month) => month as u8 + 1,
+            None => return sip_parse_error!(2, "Invalid month value in Date header"),
+        };
+        let (input, _) = char(' ')(input)?;
+        let (input, year) = take_number(input, 4, 4)?;
+        let (input, _) = char(' ')(input)?;
+        let (input, hour) = take_number(input, 2, 2)?;
+        let (input, _) = char(':')(input)?;
+        let (input, minute) = take_number(input, 2, 2)?;
+        let (input, _) = char(':')(input)?;
+        let (input, second) = take_number(input, 2, 2)?;
+        let (input, _) = tag(" GMT")(input)?;
+
+        let date = match SipDate::new(
+            year,
+            month,
+            day as u8,
+            hour as u8,
+            minute as u8,
+            second as u8,
+        ) {
+            Some(date) => date,
+            None => return sip_parse_error!(3, "Invalid date or time in Date header"),
+        };
+        if date.weekday() != wkday {
+            return sip_parse_error!(4, "Day of week doesn't match date in Date header");
+        }
+        Ok((input, date))
+    }
+}
+
+fn take_number(
+    input: &[u8],
+    min: usize,
+    max: usize,
+) -> nom::IResult<&[u8], u16, SipParseError<'_>> {
+    let (input, digits) = take_while_m_n(min, max, is_digit)(input)?;
+    let number = digits
+        .iter()
+        .fold(0u16, |acc, d| acc * 10 + u16::from(d - b'0'));
+    Ok((input, number))
+}
+
+impl fmt::Display for SipDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+            WEEKDAYS[self.weekday() as usize],
+            self.day,
+            MONTHS[self.month as usize - 1],
+            self.year,
+            self.hour,
+            self.minute,
+            self.second
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_sip_date_parse() {
+        let (input, date) = SipDate::parse(b"Sat, 13 Nov 2010 23:29:00 GMT\r\n").unwrap();
+        assert_eq!(input, b"\r\n");
+        assert_eq!(date, SipDate::new(2010, 11, 13, 23, 29, 0).unwrap());
+        assert_eq!(date.weekday(), 6);
+        assert_eq!(date.unix_seconds(), 1_289_690_940);
+        assert_eq!(date.to_string(), "Sat, 13 Nov 2010 23:29:00 GMT");
+
+        let (_, date) = SipDate::parse(b"Thu, 1 Jan 1970 00:00:00 GMT").unwrap();
+        assert_eq!(date.unix_seconds(), 0);
+        assert_eq!(date.to_string(), "Thu, 01 Jan 1970 00:00:00 GMT");
+
+        for invalid in [
+            "Sun, 13 Nov 2010 23:29:00 GMT",
+            "Sat, 31 Nov 2010 23:29:00 GMT",
+            "Sat, 13 Nov 2010 24:00:00 GMT",
+            "Sat, 13 Nov 2010 23:60:00 GMT",
+            "Sat, 13 Nov 2010 23:29:00 UTC",
+            "Mon, 29 Feb 2100 00:00:00 GMT",
+            "Sat, 13 Nov 10 23:29:00 GMT",
+        ]
+        .iter()
+        {
+            assert!(SipDate::parse(invalid.as_bytes()).is_err(), "{}", invalid);
+        }
+        assert!(SipDate::parse(b"Tue, 29 Feb 2000 12:00:00 GMT").is_ok());
+    }
+
+    #[test]
+    fn test_sip_date_unix_seconds() {
+        for seconds in [
+            0,
+            951_782_400, // 2000-02-29
+            1_289_690_940,
+            -86_401,
+            253_402_300_799, // 9999-12-31 23:59:59
+            -62_167_219_200, // 0000-01-01
+        ]
+        .iter()
+        {
+            let date = SipDate::from_unix_seconds(*seconds).unwrap();
+            assert_eq!(date.unix_seconds(), *seconds);
+        }
+        let date = SipDate::from_unix_seconds(-86_401).unwrap();
+        assert_eq!(date.to_string(), "Tue, 30 Dec 1969 23:59:59 GMT");
+        assert_eq!(SipDate::from_unix_seconds(253_402_300_800), None);
+
+        let date = SipDate::new(2004, 2, 28, 23, 0, 0).unwrap();
+        assert_eq!(
+            date.checked_add_seconds(7200).unwrap().to_string(),
+            "Sun, 29 Feb 2004 01:00:00 GMT"
+        );
+        assert_eq!(SipDate::new(2001, 2, 29, 0, 0, 0), None);
+    }
+}
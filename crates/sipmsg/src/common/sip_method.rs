@@ -1,6 +1,7 @@
-use unicase::Ascii;
+use crate::common::bnfcore::is_token_char;
+use core::fmt;
 #[derive(Copy, Clone, PartialEq, Debug)]
-pub enum SipMethod {
+pub enum SipMethod<'a> {
     ACK,
     BYE,
     CANCEL,
@@ -15,9 +16,12 @@ pub enum SipMethod {
     REGISTER,
     SUBSCRIBE,
     UPDATE,
+    /// extension-method  =  token
+    /// ([rfc3261 section-25.1](https://tools.ietf.org/html/rfc3261#section-25.1))
+    Extension(&'a str),
 }
 
-impl SipMethod {
+impl<'a> SipMethod<'a> {
     pub fn as_str(&self) -> &str {
         match self {
             &SipMethod::ACK => "ACK",
@@ -34,11 +38,16 @@ impl SipMethod {
             &SipMethod::REGISTER => "REGISTER",
             &SipMethod::SUBSCRIBE => "SUBSCRIBE",
             &SipMethod::UPDATE => "UPDATE",
+            &SipMethod::Extension(s) => s,
         }
     }
 
-    pub fn from_str(s: &str) -> Option<SipMethod> {
-        let s = Ascii::new(s);
+    /// Returns `SipMethod::Extension` if `s` is not a method defined in rfc,
+    /// or `None` if `s` is not a token.
+    /// Method names are case-sensitive
+    /// ([rfc3261 section-7.1](https://tools.ietf.org/html/rfc3261#section-7.1)),
+    /// so `invite` is an extension method
+    pub fn from_str(s: &'a str) -> Option<SipMethod<'a>> {
         macro_rules! match_str {
             ($input_str:expr, $enum_result:expr) => {
                 if s == $input_str {
@@ -60,6 +69,15 @@ impl SipMethod {
         match_str!("REGISTER", SipMethod::REGISTER);
         match_str!("SUBSCRIBE", SipMethod::SUBSCRIBE);
         match_str!("UPDATE", SipMethod::UPDATE);
+        if !s.is_empty() && s.bytes().all(is_token_char) {
+            return Some(SipMethod::Extension(s));
+        }
         None
     }
 }
+
+impl<'a> fmt::Display for SipMethod<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
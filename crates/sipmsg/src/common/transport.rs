@@ -0,0 +1,89 @@
+use crate::common::bnfcore::is_token_char;
+use core::fmt;
+use unicase::Ascii;
+
+/// transport  =  "UDP" / "TCP" / "TLS" / "SCTP" / "TLS-SCTP" / other-transport
+/// ([rfc3261 section-25.1](https://tools.ietf.org/html/rfc3261#section-25.1)),
+/// "WS" / "WSS" ([rfc7118](https://tools.ietf.org/html/rfc7118))
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Transport<'a> {
+    UDP,
+    TCP,
+    TLS,
+    SCTP,
+    TLSSCTP,
+    WS,
+    WSS,
+    /// other-transport  =  token
+    Other(&'a str),
+}
+
+impl<'a> Transport<'a> {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Transport::UDP => "UDP",
+            Transport::TCP => "TCP",
+            Transport::TLS => "TLS",
+            Transport::SCTP => "SCTP",
+            Transport::TLSSCTP => "TLS-SCTP",
+            Transport::WS => "WS",
+            Transport::WSS => "WSS",
+            Transport::Other(s) => s,
+        }
+    }
+
+    /// Transport is case-insensitive.
+    /// Returns `Transport::Other` for unknown transport or `None` if `s` is not a token
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &'a str) -> Option<Transport<'a>> {
+        let s = Ascii::new(s);
+        let transport = if s == "UDP" {
+            Transport::UDP
+        } else if s == "TCP" {
+            Transport::TCP
+        } else if s == "TLS" {
+            Transport::TLS
+        } else if s == "SCTP" {
+            Transport::SCTP
+        } else if s == "TLS-SCTP" {
+            Transport::TLSSCTP
+        } else if s == "WS" {
+            Transport::WS
+        } else if s == "WSS" {
+            Transport::WSS
+        } else if !s.is_empty() && s.bytes().all(is_token_char) {
+            Transport::Other(s.into_inner())
+        } else {
+            return None;
+        };
+        Some(transport)
+    }
+
+    /// Transport that requires TLS
+    pub fn is_secure(&self) -> bool {
+        matches!(self, Transport::TLS | Transport::TLSSCTP | Transport::WSS)
+    }
+}
+
+impl<'a> fmt::Display for Transport<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transport_from_str() {
+        assert_eq!(Transport::from_str("udp"), Some(Transport::UDP));
+        assert_eq!(Transport::from_str("Tls-Sctp"), Some(Transport::TLSSCTP));
+        assert_eq!(Transport::from_str("wss"), Some(Transport::WSS));
+        assert_eq!(Transport::from_str("quic"), Some(Transport::Other("quic")));
+        assert_eq!(Transport::from_str("a b"), None);
+        assert_eq!(Transport::from_str(""), None);
+        assert!(Transport::WSS.is_secure());
+        assert!(!Transport::TCP.is_secure());
+    }
+}
@@ -0,0 +1,1220 @@
+//! HTTP Digest authentication of SIP requests: credentials of client
+//! and challenges with verification of server
+//! ([rfc3261 section-22.4](https://tools.ietf.org/html/rfc3261#section-22.4),
+//! [rfc7616](https://tools.ietf.org/html/rfc7616),
+//! [rfc8760](https://tools.ietf.org/html/rfc8760)).
+//!
+//! ```rust
+//! use sipmsg::{SipDigestChallenge, SipDigestClient, SipMethod, SipRFCHeader, SipResponse};
+//!
+//! let (_, resp) = SipResponse::parse(
+//!     b"SIP/2.0 401 Unauthorized\r\n\
+//!     WWW-Authenticate: Digest realm=\"atlanta.com\", qop=\"auth\",\r\n \
+//!       nonce=\"84a4cc6f3082121f32b42a2187831a9e\"\r\n\r\n",
+//! )
+//! .unwrap();
+//! let challenge = SipDigestChallenge::from_response(&resp).unwrap();
+//!
+//! let mut client = SipDigestClient::new("alice", "secret");
+//! let (name, value) = client
+//!     .authorize(&challenge, &SipMethod::INVITE, "sip:bob@biloxi.com", None, "0a4f113b")
+//!     .unwrap();
+//! assert_eq!(name, SipRFCHeader::Authorization);
+//! assert!(value.starts_with("Digest username=\"alice\", realm=\"atlanta.com\""));
+//! assert!(value.contains("nc=00000001"));
+//! ```
+use crate::{
+    headers::{name_addr_header::unescape_quoted, SipHeader, SipHeaderTagType as HeaderTagType},
+    SipMethod, SipParseError, SipParseErrorKind, SipRFCHeader, SipRequest, SipResponse, Uri,
+};
+use alloc::{
+    borrow::Cow,
+    collections::btree_map::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{fmt, str};
+use md5::{Digest, Md5};
+use sha2::{Sha256, Sha512_256};
+use unicase::Ascii;
+
+/// Hash algorithm of digest
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DigestAlgorithm {
+    MD5,
+    MD5Sess,
+    SHA256,
+    SHA256Sess,
+    SHA512_256,
+    SHA512_256Sess,
+}
+
+impl DigestAlgorithm {
+    pub fn as_str(&self) -> &str {
+        match self {
+            DigestAlgorithm::MD5 => "MD5",
+            DigestAlgorithm::MD5Sess => "MD5-sess",
+            DigestAlgorithm::SHA256 => "SHA-256",
+            DigestAlgorithm::SHA256Sess => "SHA-256-sess",
+            DigestAlgorithm::SHA512_256 => "SHA-512-256",
+            DigestAlgorithm::SHA512_256Sess => "SHA-512-256-sess",
+        }
+    }
+
+    /// Algorithm is case-insensitive. Returns `None` for unsupported algorithm
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<DigestAlgorithm> {
+        let s = Ascii::new(s);
+        [
+            DigestAlgorithm::MD5,
+            DigestAlgorithm::MD5Sess,
+            DigestAlgorithm::SHA256,
+            DigestAlgorithm::SHA256Sess,
+            DigestAlgorithm::SHA512_256,
+            DigestAlgorithm::SHA512_256Sess,
+        ]
+        .iter()
+        .find(|alg| s == alg.as_str())
+        .copied()
+    }
+
+    /// `-sess` variant where HA1 includes nonce and cnonce
+    pub fn is_session(&self) -> bool {
+        matches!(
+            self,
+            DigestAlgorithm::MD5Sess
+                | DigestAlgorithm::SHA256Sess
+                | DigestAlgorithm::SHA512_256Sess
+        )
+    }
+
+    /// Hash of data as lowercase hex string
+    pub fn hash(&self, data: &[u8]) -> String {
+        match self {
+            DigestAlgorithm::MD5 | DigestAlgorithm::MD5Sess => to_hex(&Md5::digest(data)),
+            DigestAlgorithm::SHA256 | DigestAlgorithm::SHA256Sess => to_hex(&Sha256::digest(data)),
+            DigestAlgorithm::SHA512_256 | DigestAlgorithm::SHA512_256Sess => {
+                to_hex(&Sha512_256::digest(data))
+            }
+        }
+    }
+}
+
+impl fmt::Display for DigestAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Quality of protection
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Qop {
+    Auth,
+    /// Authentication with integrity protection of the body
+    AuthInt,
+}
+
+impl Qop {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Qop::Auth => "auth",
+            Qop::AuthInt => "auth-int",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Qop> {
+        match s {
+            "auth" => Some(Qop::Auth),
+            "auth-int" => Some(Qop::AuthInt),
+            _ => None,
+        }
+    }
+}
+
+/// Digest challenge of WWW-Authenticate or Proxy-Authenticate header.
+/// Quoted values are unescaped
+#[derive(PartialEq, Debug)]
+pub struct DigestChallenge<'a> {
+    pub realm: Cow<'a, str>,
+    pub nonce: Cow<'a, str>,
+    pub opaque: Option<Cow<'a, str>>,
+    /// MD5 if algorithm is absent
+    pub algorithm: DigestAlgorithm,
+    /// Supported qop options. Empty if server is compatible with rfc2069
+    pub qop: Vec<Qop>,
+    /// Previous request was rejected because of stale nonce
+    pub stale: bool,
+    /// Challenge came in Proxy-Authenticate header
+    pub proxy: bool,
+}
+
+impl<'a> DigestChallenge<'a> {
+    /// Challenge of WWW-Authenticate or Proxy-Authenticate header
+    pub fn from_header(hdr: &SipHeader<'a>) -> Result<DigestChallenge<'a>, SipParseError<'a>> {
+        let invalid = |code, message| {
+            SipParseError::new(code, Some(message))
+                .with_kind(SipParseErrorKind::HeaderValue, hdr.value.vstr.as_bytes())
+                .in_header(hdr.name.into_inner())
+        };
+        let tags = match hdr.value.tags() {
+            Some(tags) => tags,
+            None => return Err(invalid(1, "Header is not a challenge")),
+        };
+        let tag = |tag_type| tags.get(&tag_type).and_then(|v| str::from_utf8(v).ok());
+        if !tag(HeaderTagType::AuthSchema).is_some_and(|s| s.eq_ignore_ascii_case("Digest")) {
+            return Err(invalid(2, "Authentication scheme is not Digest"));
+        }
+        let (realm, nonce) = match (tag(HeaderTagType::Realm), tag(HeaderTagType::Nonce)) {
+            (Some(realm), Some(nonce)) => (realm, nonce),
+            _ => return Err(invalid(3, "Realm and nonce are mandatory")),
+        };
+        let algorithm = match tag(HeaderTagType::Algorithm) {
+            Some(algorithm) => match DigestAlgorithm::from_str(algorithm) {
+                Some(algorithm) => algorithm,
+                None => return Err(invalid(4, "Unsupported digest algorithm")),
+            },
+            None => DigestAlgorithm::MD5,
+        };
+        let qop: Vec<Qop> = match tag(HeaderTagType::QopValue) {
+            Some(qop) => qop
+                .split(',')
+                .filter_map(|q| Qop::from_str(q.trim()))
+                .collect(),
+            None => Vec::new(),
+        };
+        if qop.is_empty() && tags.contains_key(&HeaderTagType::QopValue) {
+            return Err(invalid(5, "Unsupported qop"));
+        }
+        Ok(DigestChallenge {
+            realm: unescape_quoted(realm),
+            nonce: unescape_quoted(nonce),
+            opaque: tag(HeaderTagType::Opaque).map(unescape_quoted),
+            algorithm,
+            qop,
+            stale: tag(HeaderTagType::Stale).is_some_and(|s| s.eq_ignore_ascii_case("true")),
+            proxy: hdr.name == Ascii::new(SipRFCHeader::ProxyAuthenticate.as_str()),
+        })
+    }
+
+    /// The first supported Digest challenge of 401 or 407 response.
+    /// Server may offer several challenges in order of preference
+    /// ([rfc8760 section-2.4](https://tools.ietf.org/html/rfc8760#section-2.4)),
+    /// challenges with other schemes or unsupported algorithms are skipped.
+    /// If none is supported, the error of the first challenge is returned
+    pub fn from_response(resp: &SipResponse<'a>) -> Result<DigestChallenge<'a>, SipParseError<'a>> {
+        let mut first_error = None;
+        let headers = [
+            SipRFCHeader::WWWAuthenticate,
+            SipRFCHeader::ProxyAuthenticate,
+        ]
+        .iter()
+        .filter_map(|hdr| resp.headers.get_rfc(*hdr))
+        .flat_map(|hdrs| hdrs.iter());
+        for hdr in headers {
+            match DigestChallenge::from_header(hdr) {
+                Ok(challenge) => return Ok(challenge),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        Err(first_error.unwrap_or_else(|| SipParseError::new(6, Some("Challenge is absent"))))
+    }
+
+    /// Header that carries credentials for this challenge
+    pub fn authorization_header(&self) -> SipRFCHeader {
+        if self.proxy {
+            SipRFCHeader::ProxyAuthorization
+        } else {
+            SipRFCHeader::Authorization
+        }
+    }
+}
+
+/// Parameters of digest response computation
+pub(crate) struct DigestInput<'s> {
+    pub algorithm: DigestAlgorithm,
+    pub nonce: &'s str,
+    pub method: &'s str,
+    pub uri: &'s str,
+    /// qop, nonce-count and cnonce
+    pub qop: Option<(Qop, &'s str, &'s str)>,
+    pub body: &'s [u8],
+}
+
+impl<'s> DigestInput<'s> {
+    /// request-digest of
+    /// [rfc2617 section-3.2.2.1](https://tools.ietf.org/html/rfc2617#section-3.2.2.1).
+    /// `ha1` is H(username:realm:password)
+    pub fn response(&self, ha1: &str) -> String {
+        let alg = self.algorithm;
+        let ha1 = match self.qop {
+            Some((_, _, cnonce)) if alg.is_session() => {
+                alg.hash(format!("{}:{}:{}", ha1, self.nonce, cnonce).as_bytes())
+            }
+            _ => ha1.to_string(),
+        };
+        let ha2 = match self.qop {
+            Some((Qop::AuthInt, _, _)) => {
+                let body_hash = alg.hash(self.body);
+                alg.hash(format!("{}:{}:{}", self.method, self.uri, body_hash).as_bytes())
+            }
+            _ => alg.hash(format!("{}:{}", self.method, self.uri).as_bytes()),
+        };
+        let data = match self.qop {
+            Some((qop, nc, cnonce)) => format!(
+                "{}:{}:{}:{}:{}:{}",
+                ha1,
+                self.nonce,
+                nc,
+                cnonce,
+                qop.as_str(),
+                ha2
+            ),
+            None => format!("{}:{}:{}", ha1, self.nonce, ha2),
+        };
+        alg.hash(data.as_bytes())
+    }
+}
+
+/// H(username:realm:password)
+pub(crate) fn hash_credentials(
+    algorithm: DigestAlgorithm,
+    username: &str,
+    realm: &str,
+    password: &str,
+) -> String {
+    algorithm.hash(format!("{}:{}:{}", username, realm, password).as_bytes())
+}
+
+/// Writes quoted-string escaping `"` and `\`
+fn push_quoted(value: &mut String, s: &str) {
+    value.push('"');
+    for c in s.chars() {
+        if c == '"' || c == '\\' {
+            value.push('\\');
+        }
+        value.push(c);
+    }
+    value.push('"');
+}
+
+/// Error of [`DigestClient::authorize`]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DigestError {
+    /// Server requires qop, but cnonce is empty
+    MissingCnonce,
+}
+
+impl fmt::Display for DigestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DigestError::MissingCnonce => f.write_str("cnonce is required by qop"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DigestError {}
+
+/// Computes credentials for challenges and tracks nonce-count of the last nonce
+pub struct DigestClient {
+    username: String,
+    password: String,
+    nonce: String,
+    nonce_count: u32,
+}
+
+/// Password is not printed
+impl fmt::Debug for DigestClient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DigestClient")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .field("nonce", &self.nonce)
+            .field("nonce_count", &self.nonce_count)
+            .finish()
+    }
+}
+
+impl DigestClient {
+    pub fn new(username: &str, password: &str) -> DigestClient {
+        DigestClient {
+            username: username.to_string(),
+            password: password.to_string(),
+            nonce: String::new(),
+            nonce_count: 0,
+        }
+    }
+
+    /// Count of requests sent with the last nonce
+    pub fn nonce_count(&self) -> u32 {
+        self.nonce_count
+    }
+
+    /// Returns name and value of Authorization or Proxy-Authorization header.
+    /// `uri` is the Request-URI, `body` is used by qop=auth-int,
+    /// which is chosen only if server doesn't offer qop=auth.
+    /// `cnonce` is a random client nonce, it is not used if server doesn't support qop.
+    /// Nonce-count is incremented for each call with the same nonce.
+    pub fn authorize(
+        &mut self,
+        challenge: &DigestChallenge,
+        method: &SipMethod,
+        uri: &str,
+        body: Option<&[u8]>,
+        cnonce: &str,
+    ) -> Result<(SipRFCHeader, String), DigestError> {
+        let qop = if challenge.qop.is_empty() || challenge.qop.contains(&Qop::Auth) {
+            challenge.qop.first().map(|_| Qop::Auth)
+        } else {
+            Some(Qop::AuthInt)
+        };
+        if qop.is_some() && cnonce.is_empty() {
+            return Err(DigestError::MissingCnonce);
+        }
+        if self.nonce != challenge.nonce {
+            self.nonce = challenge.nonce.to_string();
+            self.nonce_count = 0;
+        }
+        self.nonce_count += 1;
+        let nc = format!("{:08x}", self.nonce_count);
+
+        let input = DigestInput {
+            algorithm: challenge.algorithm,
+            nonce: &challenge.nonce,
+            method: method.as_str(),
+            uri,
+            qop: qop.map(|qop| (qop, nc.as_str(), cnonce)),
+            body: body.unwrap_or(&[]),
+        };
+        let ha1 = hash_credentials(
+            challenge.algorithm,
+            &self.username,
+            &challenge.realm,
+            &self.password,
+        );
+        let response = input.response(&ha1);
+
+        let mut value = String::from("Digest username=");
+        push_quoted(&mut value, &self.username);
+        value.push_str(", realm=");
+        push_quoted(&mut value, &challenge.realm);
+        value.push_str(", nonce=");
+        push_quoted(&mut value, &challenge.nonce);
+        value.push_str(", uri=");
+        push_quoted(&mut value, uri);
+        value.push_str(&format!(
+            ", response=\"{}\", algorithm={}",
+            response, challenge.algorithm
+        ));
+        if let Some(qop) = qop {
+            value.push_str(", cnonce=");
+            push_quoted(&mut value, cnonce);
+            value.push_str(&format!(", qop={}, nc={}", qop.as_str(), nc));
+        }
+        if let Some(opaque) = &challenge.opaque {
+            value.push_str(", opaque=");
+            push_quoted(&mut value, opaque);
+        }
+        Ok((challenge.authorization_header(), value))
+    }
+}
+
+/// Password of user or precomputed H(username:realm:password)
+#[derive(Clone, PartialEq, Debug)]
+pub enum Credential {
+    Password(String),
+    HA1(String),
+}
+
+/// Storage of user credentials used by [`DigestServer`]
+pub trait CredentialStore {
+    /// Credential of user in realm. HA1 must be computed by `algorithm`.
+    /// Returns `None` if user is unknown
+    fn credential(
+        &self,
+        username: &str,
+        realm: &str,
+        algorithm: DigestAlgorithm,
+    ) -> Option<Credential>;
+}
+
+/// Reason of rejected credentials
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DigestRejection {
+    /// Request has no credentials for the realm
+    Missing,
+    /// Credentials are incomplete or don't match the challenge
+    Malformed,
+    UnknownUser,
+    /// Nonce was not issued by this server
+    InvalidNonce,
+    /// Response is valid but nonce has expired, challenge with `stale=true`
+    Stale,
+    /// Nonce-count was already used with this nonce
+    Replay,
+    InvalidResponse,
+}
+
+/// Credentials accepted by [`DigestServer::verify`]
+#[derive(PartialEq)]
+pub struct DigestAuthorized {
+    pub username: String,
+    pub nonce: String,
+    /// qop, nonce-count and cnonce of request
+    pub qop: Option<(Qop, u32, String)>,
+    /// digest-uri of request
+    uri: String,
+    /// H(username:realm:password), `rspauth` of Authentication-Info is computed with it
+    ha1: String,
+}
+
+/// HA1 is not printed, it allows to authenticate as the user
+impl fmt::Debug for DigestAuthorized {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DigestAuthorized")
+            .field("username", &self.username)
+            .field("nonce", &self.nonce)
+            .field("qop", &self.qop)
+            .field("uri", &self.uri)
+            .field("ha1", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Issues challenges and verifies credentials of requests.
+/// Nonce contains creation time and keyed hash, so issued nonces are not stored.
+/// Nonce-counts are tracked until nonce expires to detect replays.
+/// Time is passed by caller in seconds, e.g. since the UNIX epoch.
+pub struct DigestServer {
+    realm: String,
+    secret: Vec<u8>,
+    algorithm: DigestAlgorithm,
+    qop: Vec<Qop>,
+    nonce_lifetime: u64,
+    proxy: bool,
+    sequence: u32,
+    nonce_counts: BTreeMap<String, u32>,
+}
+
+/// Secret of nonces is not printed
+impl fmt::Debug for DigestServer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DigestServer")
+            .field("realm", &self.realm)
+            .field("secret", &"<redacted>")
+            .field("algorithm", &self.algorithm)
+            .field("qop", &self.qop)
+            .field("nonce_lifetime", &self.nonce_lifetime)
+            .field("proxy", &self.proxy)
+            .field("sequence", &self.sequence)
+            .field("nonce_counts", &self.nonce_counts)
+            .finish()
+    }
+}
+
+/// Length of hex timestamp and sequence at start of nonce
+const NONCE_PREFIX_LEN: usize = 24;
+
+impl DigestServer {
+    /// Server with MD5 algorithm, qop=auth and nonce lifetime of 300 seconds
+    pub fn new(realm: &str, secret: &[u8]) -> DigestServer {
+        DigestServer {
+            realm: realm.to_string(),
+            secret: secret.to_vec(),
+            algorithm: DigestAlgorithm::MD5,
+            qop: vec![Qop::Auth],
+            nonce_lifetime: 300,
+            proxy: false,
+            sequence: 0,
+            nonce_counts: BTreeMap::new(),
+        }
+    }
+
+    pub fn algorithm(mut self, algorithm: DigestAlgorithm) -> DigestServer {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Offered qop options. Empty for rfc2069 compatibility
+    pub fn qop(mut self, qop: &[Qop]) -> DigestServer {
+        self.qop = qop.to_vec();
+        self
+    }
+
+    pub fn nonce_lifetime(mut self, seconds: u64) -> DigestServer {
+        self.nonce_lifetime = seconds;
+        self
+    }
+
+    /// Use Proxy-Authenticate and Proxy-Authorization headers
+    pub fn proxy(mut self, proxy: bool) -> DigestServer {
+        self.proxy = proxy;
+        self
+    }
+
+    pub fn realm(&self) -> &str {
+        &self.realm
+    }
+
+    fn nonce_mac(&self, prefix: &str) -> String {
+        let mut data = Vec::with_capacity(prefix.len() + self.realm.len() + self.secret.len() + 2);
+        data.extend_from_slice(prefix.as_bytes());
+        data.push(b':');
+        data.extend_from_slice(self.realm.as_bytes());
+        data.push(b':');
+        data.extend_from_slice(&self.secret);
+        DigestAlgorithm::SHA256.hash(&data)
+    }
+
+    /// New nonce created at `now`
+    pub fn nonce(&mut self, now: u64) -> String {
+        self.sequence = self.sequence.wrapping_add(1);
+        let prefix = format!("{:016x}{:08x}", now, self.sequence);
+        let mac = self.nonce_mac(&prefix);
+        prefix + &mac
+    }
+
+    /// Creation time of nonce issued by this server
+    fn nonce_time(&self, nonce: &str) -> Option<u64> {
+        if nonce.len() <= NONCE_PREFIX_LEN || !nonce.is_char_boundary(NONCE_PREFIX_LEN) {
+            return None;
+        }
+        let (prefix, mac) = nonce.split_at(NONCE_PREFIX_LEN);
+        if !constant_time_eq(mac.as_bytes(), self.nonce_mac(prefix).as_bytes()) {
+            return None;
+        }
+        u64::from_str_radix(&prefix[..16], 16).ok()
+    }
+
+    fn is_expired(&self, created: u64, now: u64) -> bool {
+        created > now || now - created > self.nonce_lifetime
+    }
+
+    /// Name and value of WWW-Authenticate or Proxy-Authenticate header with new nonce.
+    /// `stale` is set when credentials were rejected with [`DigestRejection::Stale`]
+    pub fn challenge(&mut self, now: u64, stale: bool) -> (SipRFCHeader, String) {
+        let nonce = self.nonce(now);
+        let mut value = String::from("Digest realm=");
+        push_quoted(&mut value, &self.realm);
+        value.push_str(", nonce=");
+        push_quoted(&mut value, &nonce);
+        value.push_str(&format!(", algorithm={}", self.algorithm));
+        if !self.qop.is_empty() {
+            let qop: Vec<&str> = self.qop.iter().map(|qop| qop.as_str()).collect();
+            value.push_str(&format!(", qop=\"{}\"", qop.join(",")));
+        }
+        if stale {
+            value.push_str(", stale=true");
+        }
+        let name = if self.proxy {
+            SipRFCHeader::ProxyAuthenticate
+        } else {
+            SipRFCHeader::WWWAuthenticate
+        };
+        (name, value)
+    }
+
+    /// Verifies Authorization or Proxy-Authorization header of request for realm of server
+    pub fn verify<S: CredentialStore + ?Sized>(
+        &mut self,
+        request: &SipRequest,
+        store: &S,
+        now: u64,
+    ) -> Result<DigestAuthorized, DigestRejection> {
+        let name = if self.proxy {
+            SipRFCHeader::ProxyAuthorization
+        } else {
+            SipRFCHeader::Authorization
+        };
+        let realm = self.realm.as_str();
+        let tags = request
+            .headers
+            .get_rfc(name)
+            .into_iter()
+            .flatten()
+            .filter_map(|hdr| hdr.value.tags())
+            .find(|tags| {
+                tags.get(&HeaderTagType::Realm)
+                    .map(|r| unescape_quoted_bytes(r))
+                    == Some(realm.into())
+            })
+            .ok_or(DigestRejection::Missing)?;
+        let tag = |tag_type| tags.get(&tag_type).and_then(|v| str::from_utf8(v).ok());
+
+        if !tag(HeaderTagType::AuthSchema).is_some_and(|s| s.eq_ignore_ascii_case("Digest")) {
+            return Err(DigestRejection::Malformed);
+        }
+        let (username, nonce, uri, response) = match (
+            tag(HeaderTagType::Username),
+            tag(HeaderTagType::Nonce),
+            tag(HeaderTagType::DigestUri),
+            tag(HeaderTagType::Dresponse),
+        ) {
+            (Some(username), Some(nonce), Some(uri), Some(response)) => (
+                unescape_quoted(username),
+                unescape_quoted(nonce),
+                unescape_quoted(uri),
+                unescape_quoted(response),
+            ),
+            _ => return Err(DigestRejection::Malformed),
+        };
+        let algorithm = tag(HeaderTagType::Algorithm)
+            .map_or(Some(DigestAlgorithm::MD5), DigestAlgorithm::from_str);
+        if algorithm != Some(self.algorithm) || !self.is_request_uri(request, &uri) {
+            return Err(DigestRejection::Malformed);
+        }
+        let qop = match tag(HeaderTagType::QopValue) {
+            Some(qop) => {
+                let qop = Qop::from_str(qop).filter(|qop| self.qop.contains(qop));
+                let nc =
+                    tag(HeaderTagType::NonceCount).and_then(|nc| u32::from_str_radix(nc, 16).ok());
+                match (qop, nc, tag(HeaderTagType::Cnonce)) {
+                    (Some(qop), Some(nc), Some(cnonce)) => Some((qop, nc, unescape_quoted(cnonce))),
+                    _ => return Err(DigestRejection::Malformed),
+                }
+            }
+            None if self.qop.is_empty() => None,
+            None => return Err(DigestRejection::Malformed),
+        };
+
+        let created = self
+            .nonce_time(&nonce)
+            .ok_or(DigestRejection::InvalidNonce)?;
+        let ha1 = match store.credential(&username, realm, self.algorithm) {
+            Some(Credential::Password(password)) => {
+                hash_credentials(self.algorithm, &username, realm, &password)
+            }
+            Some(Credential::HA1(ha1)) => ha1.to_ascii_lowercase(),
+            None => return Err(DigestRejection::UnknownUser),
+        };
+        let nc = qop.as_ref().map(|(_, nc, _)| format!("{:08x}", nc));
+        let input = DigestInput {
+            algorithm: self.algorithm,
+            nonce: &nonce,
+            method: request.rl.method.as_str(),
+            uri: &uri,
+            qop: qop
+                .as_ref()
+                .map(|(qop, _, cnonce)| (*qop, nc.as_deref().unwrap_or_default(), &**cnonce)),
+            body: request.body.unwrap_or(&[]),
+        };
+        let expected = input.response(&ha1);
+        if !constant_time_eq(
+            expected.as_bytes(),
+            response.to_ascii_lowercase().as_bytes(),
+        ) {
+            return Err(DigestRejection::InvalidResponse);
+        }
+        if self.is_expired(created, now) {
+            return Err(DigestRejection::Stale);
+        }
+
+        let lifetime = self.nonce_lifetime;
+        self.nonce_counts.retain(|nonce, _| {
+            u64::from_str_radix(&nonce[..16], 16).is_ok_and(|t| t <= now && now - t <= lifetime)
+        });
+        if let Some((_, nc, _)) = qop {
+            let last_nc = self.nonce_counts.entry(nonce.to_string()).or_insert(0);
+            if nc <= *last_nc {
+                return Err(DigestRejection::Replay);
+            }
+            *last_nc = nc;
+        }
+
+        Ok(DigestAuthorized {
+            username: username.into_owned(),
+            nonce: nonce.into_owned(),
+            qop: qop.map(|(qop, nc, cnonce)| (qop, nc, cnonce.into_owned())),
+            uri: uri.into_owned(),
+            ha1,
+        })
+    }
+
+    /// Digest-uri must be the Request-URI
+    /// ([rfc3261 section-22.4](https://tools.ietf.org/html/rfc3261#section-22.4))
+    fn is_request_uri(&self, request: &SipRequest, uri: &str) -> bool {
+        let digest_uri = match Uri::parse(uri.as_bytes()) {
+            Ok(([], digest_uri)) => digest_uri,
+            _ => return false,
+        };
+        match (digest_uri.sip(), request.rl.uri.sip()) {
+            (Some(digest_uri), Some(request_uri)) => digest_uri.equivalent(request_uri),
+            _ => digest_uri.to_string() == request.rl.uri.to_string(),
+        }
+    }
+
+    /// Value of Authentication-Info header for accepted credentials.
+    /// `response_body` is the body of response that carries the header,
+    /// it is a part of `rspauth` for `qop=auth-int`.
+    /// `nextnonce` is created if `now` is given.
+    /// Returns `None` if there is nothing to send: rfc2069 credentials without nextnonce
+    pub fn authentication_info(
+        &mut self,
+        authorized: &DigestAuthorized,
+        response_body: &[u8],
+        now: Option<u64>,
+    ) -> Option<String> {
+        let mut values = Vec::new();
+        if let Some(now) = now {
+            let mut nextnonce = String::from("nextnonce=");
+            push_quoted(&mut nextnonce, &self.nonce(now));
+            values.push(nextnonce);
+        }
+        if let Some((qop, nc, cnonce)) = &authorized.qop {
+            let nc = format!("{:08x}", nc);
+            // rspauth is computed as response with empty method
+            let rspauth = DigestInput {
+                algorithm: self.algorithm,
+                nonce: &authorized.nonce,
+                method: "",
+                uri: &authorized.uri,
+                qop: Some((*qop, &nc, cnonce)),
+                body: response_body,
+            }
+            .response(&authorized.ha1);
+            values.push(format!("qop={}", qop.as_str()));
+            values.push(format!("rspauth=\"{}\"", rspauth));
+            let mut cnonce_value = String::from("cnonce=");
+            push_quoted(&mut cnonce_value, cnonce);
+            values.push(cnonce_value);
+            values.push(format!("nc={}", nc));
+        }
+        if values.is_empty() {
+            None
+        } else {
+            Some(values.join(", "))
+        }
+    }
+}
+
+fn unescape_quoted_bytes(value: &[u8]) -> Cow<'_, str> {
+    match str::from_utf8(value) {
+        Ok(value) => unescape_quoted(value),
+        Err(_) => Cow::Borrowed(""),
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SipHeaders;
+
+    fn challenge(hdr: &str) -> (SipHeaders<'_>, SipRFCHeader) {
+        let (_, headers) = SipHeaders::parse(hdr.as_bytes()).unwrap();
+        let name = *headers.get_rfc_headers_keys().next().unwrap();
+        (headers, name)
+    }
+
+    #[test]
+    fn test_digest_rfc2617_md5() {
+        let (headers, name) = challenge(
+            "WWW-Authenticate: Digest realm=\"testrealm@host.com\", qop=\"auth,auth-int\", \
+            nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", \
+            opaque=\"5ccc069c403ebaf9f0171e9517f40e41\"\r\n\r\n",
+        );
+        let challenge = DigestChallenge::from_header(headers.get_rfc_s(name).unwrap()).unwrap();
+        assert_eq!(challenge.algorithm, DigestAlgorithm::MD5);
+        assert_eq!(challenge.qop, [Qop::Auth, Qop::AuthInt]);
+        assert!(!challenge.proxy);
+
+        let mut client = DigestClient::new("Mufasa", "Circle Of Life");
+        let method = SipMethod::Extension("GET");
+        let (name, value) = client
+            .authorize(&challenge, &method, "/dir/index.html", None, "0a4f113b")
+            .unwrap();
+        assert_eq!(name, SipRFCHeader::Authorization);
+        assert_eq!(
+            value,
+            "Digest username=\"Mufasa\", realm=\"testrealm@host.com\", \
+            nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", uri=\"/dir/index.html\", \
+            response=\"6629fae49393a05397450978507c4ef1\", algorithm=MD5, \
+            cnonce=\"0a4f113b\", qop=auth, nc=00000001, \
+            opaque=\"5ccc069c403ebaf9f0171e9517f40e41\""
+        );
+
+        let (_, value) = client
+            .authorize(&challenge, &method, "/dir/index.html", None, "0a4f113b")
+            .unwrap();
+        assert!(value.contains("nc=00000002"));
+        assert_eq!(client.nonce_count(), 2);
+    }
+
+    #[test]
+    fn test_digest_rfc7616_sha256() {
+        let (headers, name) = challenge(
+            "Proxy-Authenticate: Digest realm=\"http-auth@example.org\", qop=\"auth\", \
+            algorithm=SHA-256, nonce=\"7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v\", \
+            opaque=\"FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS\"\r\n\r\n",
+        );
+        let challenge = DigestChallenge::from_header(headers.get_rfc_s(name).unwrap()).unwrap();
+        assert!(challenge.proxy);
+        let mut client = DigestClient::new("Mufasa", "Circle of Life");
+        let (name, value) = client
+            .authorize(
+                &challenge,
+                &SipMethod::Extension("GET"),
+                "/dir/index.html",
+                None,
+                "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ",
+            )
+            .unwrap();
+        assert_eq!(name, SipRFCHeader::ProxyAuthorization);
+        assert!(value.contains(
+            "response=\"753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1\""
+        ));
+        assert!(value.contains("algorithm=SHA-256"));
+    }
+
+    #[test]
+    fn test_digest_algorithms() {
+        assert_eq!(
+            DigestAlgorithm::from_str("sha-512-256-SESS"),
+            Some(DigestAlgorithm::SHA512_256Sess)
+        );
+        assert_eq!(DigestAlgorithm::from_str("SHA-1"), None);
+        assert_eq!(
+            DigestAlgorithm::SHA512_256.hash(b"abc"),
+            "53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23"
+        );
+
+        let input = DigestInput {
+            algorithm: DigestAlgorithm::MD5Sess,
+            nonce: "abc",
+            method: "INVITE",
+            uri: "sip:bob@biloxi.com",
+            qop: Some((Qop::AuthInt, "00000001", "xyz")),
+            body: b"v=0\r\n",
+        };
+        let alg = DigestAlgorithm::MD5;
+        let ha1 = alg.hash(b"bob:biloxi.com:zanzibar");
+        let ha1 = alg.hash(format!("{}:abc:xyz", ha1).as_bytes());
+        let ha2 =
+            alg.hash(format!("INVITE:sip:bob@biloxi.com:{}", alg.hash(b"v=0\r\n")).as_bytes());
+        let expected = alg.hash(format!("{}:abc:00000001:xyz:auth-int:{}", ha1, ha2).as_bytes());
+        let credentials = hash_credentials(input.algorithm, "bob", "biloxi.com", "zanzibar");
+        assert_eq!(input.response(&credentials), expected);
+    }
+
+    #[test]
+    fn test_digest_challenge_errors() {
+        for hdr in [
+            "WWW-Authenticate: Basic realm=\"a\"\r\n\r\n",
+            "WWW-Authenticate: Digest realm=\"a\"\r\n\r\n",
+            "WWW-Authenticate: Digest realm=\"a\", nonce=\"b\", algorithm=SHA-1\r\n\r\n",
+            "WWW-Authenticate: Digest realm=\"a\", nonce=\"b\", qop=\"auth-conf\"\r\n\r\n",
+        ]
+        .iter()
+        {
+            let (headers, name) = challenge(hdr);
+            let err = DigestChallenge::from_header(headers.get_rfc_s(name).unwrap()).unwrap_err();
+            assert_eq!(err.details().unwrap().header, Some("WWW-Authenticate"));
+        }
+
+        // rfc2069 compatibility: no qop, no cnonce
+        let (headers, name) =
+            challenge("WWW-Authenticate: Digest realm=\"a\", nonce=\"b\"\r\n\r\n");
+        let rfc2069 = DigestChallenge::from_header(headers.get_rfc_s(name).unwrap()).unwrap();
+        let (_, value) = DigestClient::new("u", "p")
+            .authorize(&rfc2069, &SipMethod::REGISTER, "sip:a", None, "")
+            .unwrap();
+        assert!(!value.contains("cnonce") && !value.contains("nc="));
+
+        let (headers, name) =
+            challenge("WWW-Authenticate: Digest realm=\"a\", nonce=\"b\", qop=auth\r\n\r\n");
+        let challenge = DigestChallenge::from_header(headers.get_rfc_s(name).unwrap()).unwrap();
+        assert_eq!(
+            DigestClient::new("u", "p").authorize(
+                &challenge,
+                &SipMethod::REGISTER,
+                "sip:a",
+                None,
+                ""
+            ),
+            Err(DigestError::MissingCnonce)
+        );
+    }
+
+    #[test]
+    fn test_digest_challenge_escaped() {
+        let (headers, name) = challenge(
+            "WWW-Authenticate: Digest realm=\"a\\\"b\", nonce=\"c\\\\d\", qop=\"auth\"\r\n\r\n",
+        );
+        let challenge = DigestChallenge::from_header(headers.get_rfc_s(name).unwrap()).unwrap();
+        assert_eq!(challenge.realm, "a\"b");
+        assert_eq!(challenge.nonce, "c\\d");
+        // Values are escaped once again in credentials
+        let (_, value) = DigestClient::new("u", "p")
+            .authorize(&challenge, &SipMethod::REGISTER, "sip:a", None, "x")
+            .unwrap();
+        assert!(value.contains("realm=\"a\\\"b\", nonce=\"c\\\\d\""));
+    }
+
+    #[test]
+    fn test_digest_challenge_preference() {
+        let buf = b"SIP/2.0 401 Unauthorized\r\n\
+            WWW-Authenticate: Bearer realm=\"a\"\r\n\
+            WWW-Authenticate: Digest realm=\"a\", nonce=\"n1\", algorithm=SHA-512\r\n\
+            WWW-Authenticate: Digest realm=\"a\", nonce=\"n2\", algorithm=SHA-256\r\n\
+            WWW-Authenticate: Digest realm=\"a\", nonce=\"n3\"\r\n\
+            Content-Length: 0\r\n\r\n";
+        let (_, resp) = SipResponse::parse(buf).unwrap();
+        let challenge = DigestChallenge::from_response(&resp).unwrap();
+        assert_eq!(challenge.nonce, "n2");
+        assert_eq!(challenge.algorithm, DigestAlgorithm::SHA256);
+
+        let buf = b"SIP/2.0 401 Unauthorized\r\n\
+            WWW-Authenticate: Digest realm=\"a\", nonce=\"n1\", algorithm=SHA-512\r\n\
+            Content-Length: 0\r\n\r\n";
+        let (_, resp) = SipResponse::parse(buf).unwrap();
+        let err = DigestChallenge::from_response(&resp).unwrap_err();
+        assert_eq!(err.details().unwrap().code, 4);
+    }
+
+    #[test]
+    fn test_digest_client_debug() {
+        let client = DigestClient::new("alice", "secret-password");
+        let debug = format!("{:?}", client);
+        assert!(debug.contains("alice"));
+        assert!(!debug.contains("secret-password"));
+    }
+
+    struct Users;
+
+    impl CredentialStore for Users {
+        fn credential(
+            &self,
+            username: &str,
+            realm: &str,
+            alg: DigestAlgorithm,
+        ) -> Option<Credential> {
+            match username {
+                "alice" => Some(Credential::Password("secret".to_string())),
+                "bob" => Some(Credential::HA1(hash_credentials(
+                    alg, "bob", realm, "zanzibar",
+                ))),
+                _ => None,
+            }
+        }
+    }
+
+    fn authorize(client: &mut DigestClient, name: SipRFCHeader, value: &str) -> String {
+        let hdr = format!("{}: {}\r\n\r\n", name.as_str(), value);
+        let (headers, name) = challenge(&hdr);
+        let challenge = DigestChallenge::from_header(headers.get_rfc_s(name).unwrap()).unwrap();
+        let (name, value) = client
+            .authorize(
+                &challenge,
+                &SipMethod::REGISTER,
+                "sip:atlanta.com",
+                None,
+                "8d3f",
+            )
+            .unwrap();
+        format!(
+            "REGISTER sip:atlanta.com SIP/2.0\r\n{}: {}\r\nContent-Length: 0\r\n\r\n",
+            name.as_str(),
+            value
+        )
+    }
+
+    fn verify(
+        server: &mut DigestServer,
+        request: &str,
+        now: u64,
+    ) -> Result<DigestAuthorized, DigestRejection> {
+        let (_, request) = SipRequest::parse(request.as_bytes()).unwrap();
+        server.verify(&request, &Users, now)
+    }
+
+    #[test]
+    fn test_digest_server_verify() {
+        let mut server =
+            DigestServer::new("atlanta.com", b"key").algorithm(DigestAlgorithm::SHA256);
+        let (name, value) = server.challenge(1000, false);
+        assert_eq!(name, SipRFCHeader::WWWAuthenticate);
+        assert!(value.ends_with("algorithm=SHA-256, qop=\"auth\""));
+
+        let mut client = DigestClient::new("alice", "secret");
+        let request = authorize(&mut client, name, &value);
+        let authorized = verify(&mut server, &request, 1010).unwrap();
+        assert_eq!(authorized.username, "alice");
+        assert_eq!(authorized.qop, Some((Qop::Auth, 1, "8d3f".to_string())));
+        assert_eq!(
+            verify(&mut server, &request, 1010),
+            Err(DigestRejection::Replay)
+        );
+
+        // Authentication-Info is parsed back by Authentication-Info parser
+        let info = server
+            .authentication_info(&authorized, b"", Some(1010))
+            .unwrap();
+        assert!(info.starts_with("nextnonce=\""));
+        let hdr = format!("Authentication-Info: {}\r\n\r\n", info);
+        let (headers, _) = challenge(&hdr);
+        let values = headers.get_rfc(SipRFCHeader::AuthenticationInfo).unwrap();
+        assert_eq!(values.len(), 5);
+        let rspauth = DigestInput {
+            algorithm: DigestAlgorithm::SHA256,
+            nonce: &authorized.nonce,
+            method: "",
+            uri: "sip:atlanta.com",
+            qop: Some((Qop::Auth, "00000001", "8d3f")),
+            body: b"",
+        }
+        .response(&hash_credentials(
+            DigestAlgorithm::SHA256,
+            "alice",
+            "atlanta.com",
+            "secret",
+        ));
+        assert_eq!(
+            values[2].value.tags().unwrap()[&HeaderTagType::AinfoValue],
+            rspauth.as_bytes()
+        );
+
+        // Next request with the same nonce
+        let request = authorize(&mut client, name, &value);
+        assert!(request.contains("nc=00000002"));
+        assert!(verify(&mut server, &request, 1020).is_ok());
+        assert_eq!(
+            verify(&mut server, &request, 1400),
+            Err(DigestRejection::Stale)
+        );
+        let (_, value) = server.challenge(1400, true);
+        assert!(value.ends_with(", stale=true"));
+
+        let mut client = DigestClient::new("bob", "zanzibar");
+        let request = authorize(&mut client, name, &value);
+        assert!(verify(&mut server, &request, 1400).is_ok());
+        let mut client = DigestClient::new("bob", "secret");
+        let request = authorize(&mut client, name, &value);
+        assert_eq!(
+            verify(&mut server, &request, 1400),
+            Err(DigestRejection::InvalidResponse)
+        );
+        let mut client = DigestClient::new("carol", "secret");
+        let request = authorize(&mut client, name, &value);
+        assert_eq!(
+            verify(&mut server, &request, 1400),
+            Err(DigestRejection::UnknownUser)
+        );
+
+        let forged = value.replacen("nonce=\"0", "nonce=\"1", 1);
+        let request = authorize(&mut client, name, &forged);
+        assert_eq!(
+            verify(&mut server, &request, 1400),
+            Err(DigestRejection::InvalidNonce)
+        );
+        let request = request.replace("REGISTER sip:atlanta.com", "REGISTER sip:biloxi.com");
+        assert_eq!(
+            verify(&mut server, &request, 1400),
+            Err(DigestRejection::Malformed)
+        );
+        let request = "REGISTER sip:atlanta.com SIP/2.0\r\nContent-Length: 0\r\n\r\n";
+        assert_eq!(
+            verify(&mut server, request, 1400),
+            Err(DigestRejection::Missing)
+        );
+    }
+
+    #[test]
+    fn test_digest_server_escaped_values() {
+        let mut server = DigestServer::new("atlanta.com", b"key");
+        let (name, value) = server.challenge(0, false);
+        let request = authorize(&mut DigestClient::new("alice", "secret"), name, &value);
+        // quoted-pairs are allowed in every quoted-string of credentials
+        let escape = |request: &str, param: &str| {
+            let start = request.find(&format!("{}=\"", param)).unwrap() + param.len() + 2;
+            let mut escaped = request[..start].to_string();
+            escaped.push('\\');
+            escaped.push_str(&request[start..]);
+            escaped
+        };
+        let mut escaped = request.clone();
+        for param in &["nonce", "uri", "cnonce", "response"] {
+            escaped = escape(&escaped, param);
+        }
+        let authorized = verify(&mut server, &escaped, 5).unwrap();
+        assert_eq!(authorized.qop, Some((Qop::Auth, 1, "8d3f".to_string())));
+        assert_eq!(
+            verify(&mut server, &request, 5),
+            Err(DigestRejection::Replay)
+        );
+    }
+
+    #[test]
+    fn test_digest_server_debug() {
+        let mut server = DigestServer::new("atlanta.com", b"nonce-key");
+        let debug = format!("{:?}", server);
+        assert!(debug.contains("atlanta.com"));
+        assert!(!debug.contains("110, 111, 110, 99, 101"));
+
+        let (name, value) = server.challenge(0, false);
+        let request = authorize(&mut DigestClient::new("alice", "secret"), name, &value);
+        let authorized = verify(&mut server, &request, 5).unwrap();
+        let ha1 = hash_credentials(DigestAlgorithm::MD5, "alice", "atlanta.com", "secret");
+        let debug = format!("{:?}", authorized);
+        assert!(debug.contains("alice"));
+        assert!(!debug.contains(&ha1));
+    }
+
+    #[test]
+    fn test_digest_server_proxy_rfc2069() {
+        let mut server = DigestServer::new("atlanta.com", b"key")
+            .qop(&[])
+            .proxy(true);
+        let (name, value) = server.challenge(0, false);
+        assert_eq!(name, SipRFCHeader::ProxyAuthenticate);
+        assert!(!value.contains("qop"));
+
+        let request = authorize(&mut DigestClient::new("alice", "secret"), name, &value);
+        assert!(request.contains("Proxy-Authorization: "));
+        let authorized = verify(&mut server, &request, 5).unwrap();
+        assert_eq!(authorized.qop, None);
+        assert_eq!(server.authentication_info(&authorized, b"", None), None);
+    }
+
+    #[test]
+    fn test_digest_server_rspauth_auth_int() {
+        let mut server = DigestServer::new("atlanta.com", b"key").qop(&[Qop::AuthInt]);
+        let (name, value) = server.challenge(0, false);
+        let request = authorize(&mut DigestClient::new("alice", "secret"), name, &value);
+        let authorized = verify(&mut server, &request, 5).unwrap();
+        assert_eq!(authorized.qop, Some((Qop::AuthInt, 1, "8d3f".to_string())));
+
+        // rspauth covers the body of response, not the body of request
+        let rspauth = DigestInput {
+            algorithm: DigestAlgorithm::MD5,
+            nonce: &authorized.nonce,
+            method: "",
+            uri: "sip:atlanta.com",
+            qop: Some((Qop::AuthInt, "00000001", "8d3f")),
+            body: b"v=0\r\n",
+        }
+        .response(&hash_credentials(
+            DigestAlgorithm::MD5,
+            "alice",
+            "atlanta.com",
+            "secret",
+        ));
+        let info = server
+            .authentication_info(&authorized, b"v=0\r\n", None)
+            .unwrap();
+        assert!(info.contains(&format!("rspauth=\"{}\"", rspauth)));
+        let info = server.authentication_info(&authorized, b"", None).unwrap();
+        assert!(!info.contains(&rspauth));
+    }
+}
@@ -1,11 +1,12 @@
 use crate::common::{
-    bnfcore::is_token_char,
-    errorparse::SipParseError,
+    bnfcore::{is_hexdig, is_token_char},
+    errorparse::{map_sip_err, SipParseError, SipParseErrorKind},
     hostport::HostPort,
     nom_wrappers::{from_utf8_nom, take_quoted_string, take_sws, take_while_trim_sws},
     take_sws_token,
 };
-use alloc::collections::btree_map::{BTreeMap, Keys};
+use alloc::collections::btree_map::{BTreeMap, Iter, Keys};
+use core::fmt;
 use nom::{bytes::complete::take_while, multi::many0};
 use unicase::Ascii;
 
@@ -18,9 +19,23 @@ pub struct GenericParam<'a> {
     // It needs at least to dump quoted params as is
 }
 
+/// Parser of unquoted parameter value. Takes parameter name,
+/// so header may accept other values than token for its own parameters
+pub(crate) type ParamValueParserFn =
+    for<'p> fn(&str, &'p [u8]) -> nom::IResult<&'p [u8], &'p [u8], SipParseError<'p>>;
+
+/// Unquoted value of generic-param is token
+pub(crate) fn take_token_value<'p>(
+    _name: &str,
+    input: &'p [u8],
+) -> nom::IResult<&'p [u8], &'p [u8], SipParseError<'p>> {
+    take_while(is_token_char)(input)
+}
+
 impl<'a> GenericParam<'a> {
     fn parse(
         input: &'a [u8],
+        take_value: ParamValueParserFn,
     ) -> nom::IResult<&[u8], (Ascii<&'a str>, Option<&'a str>), SipParseError> {
         let (input, (_, parameter_name, _)) = take_while_trim_sws(input, is_token_char)?;
 
@@ -40,7 +55,7 @@ impl<'a> GenericParam<'a> {
         } else if input[0] == b'[' {
             HostPort::take_ipv6_host(input)?
         } else {
-            take_while(is_token_char)(input)?
+            take_value(param_name, input)?
         };
 
         let (input, _) = take_sws(input)?;
@@ -56,6 +71,10 @@ pub struct GenericParams<'a> {
 }
 
 impl<'a> GenericParams<'a> {
+    pub(crate) fn from_map(params: BTreeMap<Ascii<&'a str>, Option<&'a str>>) -> Self {
+        GenericParams { params }
+    }
+
     pub fn get(&self, key: &'a str) -> Option<&Option<&'a str>> {
         let key = Ascii::new(key);
         self.params.get(&key)
@@ -65,29 +84,90 @@ impl<'a> GenericParams<'a> {
         self.params.keys()
     }
 
+    pub fn iter(&self) -> Iter<'_, Ascii<&'a str>, Option<&'a str>> {
+        self.params.iter()
+    }
+
     pub fn contains(&self, key: &'a str) -> bool {
         let key = Ascii::new(key);
         self.params.contains_key(&key)
     }
 
     pub fn parse(input: &'a [u8]) -> nom::IResult<&[u8], GenericParams<'a>, SipParseError> {
-        let (input, vec_res) = many0(many_params_parser)(input)?;
-        Ok((
-            input,
-            GenericParams {
-                params: vec_res.into_iter().collect(),
+        GenericParams::parse_with(input, take_token_value)
+    }
+
+    /// Parses parameters, unquoted values are taken by `take_value`.
+    /// The first occurrence of duplicated parameter is kept
+    pub(crate) fn parse_with(
+        input: &'a [u8],
+        take_value: ParamValueParserFn,
+    ) -> nom::IResult<&'a [u8], GenericParams<'a>, SipParseError<'a>> {
+        let params_input = input;
+        let (input, vec_res) = many0(|input| many_params_parser(input, take_value))(input)
+            .map_err(|e| {
+                map_sip_err(e, |e| e.with_kind(SipParseErrorKind::Parameter, params_input))
+            })?;
+        let mut params = BTreeMap::new();
+        for (name, value) in vec_res {
+            params.entry(name).or_insert(value);
+        }
+        Ok((input, GenericParams { params }))
+    }
+}
+
+/// Writes parameter in `;name=value` form.
+/// Values that are not tokens are written as quoted strings or IPv6 references.
+/// Quoted-pairs of parsed values are kept, other `"` and `\` are escaped.
+pub(crate) fn write_param(
+    f: &mut fmt::Formatter,
+    name: &str,
+    value: Option<&str>,
+) -> fmt::Result {
+    write!(f, ";{}", name)?;
+    let value = match value {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+    if !value.is_empty() && value.bytes().all(is_token_char) {
+        return write!(f, "={}", value);
+    }
+    if value.contains(':') && value.bytes().all(|c| is_hexdig(c) || c == b':' || c == b'.') {
+        return write!(f, "=[{}]", value);
+    }
+    f.write_str("=\"")?;
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(escaped) => write!(f, "\\{}", escaped)?,
+                None => f.write_str("\\\\")?,
             },
-        ))
+            '"' => f.write_str("\\\"")?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+impl<'a> fmt::Display for GenericParams<'a> {
+    /// Writes parameters in `;name=value` form, see [`write_param`]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, value) in self.params.iter() {
+            write_param(f, name, *value)?;
+        }
+        Ok(())
     }
 }
 
 fn many_params_parser(
     input: &[u8],
+    take_value: ParamValueParserFn,
 ) -> nom::IResult<&[u8], (Ascii<&str>, Option<&str>), SipParseError> {
     if input.len() < 2 || input[0] != b';' {
         return sip_parse_error!(1, "GenericParamsParser parse error");
     }
-    GenericParam::parse(&input[1..])
+    GenericParam::parse(&input[1..], take_value)
 }
 #[cfg(test)]
 mod tests {
@@ -136,14 +216,14 @@ mod tests {
         expected_value: Option<&str>,
         expected_len: usize,
     ) {
-        let (i, (name, value)) = GenericParam::parse(input_str.as_bytes()).unwrap();
+        let (i, (name, value)) = GenericParam::parse(input_str.as_bytes(), take_token_value).unwrap();
         assert_eq!(name, expected_name);
         assert_eq!(value, expected_value);
         assert_eq!(i.len(), expected_len);
     }
 
     fn fail_parameter_test(input_str: &str) {
-        match GenericParam::parse(input_str.as_bytes()) {
+        match GenericParam::parse(input_str.as_bytes(), take_token_value) {
             Ok((_, _)) => panic!(),
             Err(_) => {}
         }
@@ -181,6 +261,19 @@ mod tests {
         parameter_test("a=bla$bla", "a", Some("bla"), 4);
     }
 
+    #[test]
+    fn parameters_display_test() {
+        let (_, params) = GenericParams::parse(
+            "; lr ;received=[2001:db8::9:255]; +sip.instance=\"<urn:uuid:0d9a>\";q=0.5\r\n"
+                .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(
+            alloc::format!("{}", params),
+            ";+sip.instance=\"<urn:uuid:0d9a>\";lr;q=0.5;received=[2001:db8::9:255]"
+        );
+    }
+
     #[test]
     fn parameter_incorrect_parse_test() {
         fail_parameter_test("");
@@ -1,9 +1,15 @@
 use crate::{
-    common::{bnfcore::*, errorparse::SipParseError, nom_wrappers::from_utf8_nom, take_sws_token},
+    common::{
+        bnfcore::*,
+        errorparse::{map_sip_err, SipParseError, SipParseErrorKind},
+        nom_wrappers::from_utf8_nom,
+        take_sws_token,
+    },
     headers::{
+        generic_params::{take_token_value, ParamValueParserFn},
         parsers::ExtensionParser,
         traits::{HeaderValueParserFn, SipHeaderParser},
-        GenericParams, SipRFCHeader, SipUri,
+        via_header, GenericParams, SipRFCHeader, SipUri, Uri,
     },
 };
 use alloc::collections::{BTreeMap, VecDeque};
@@ -103,7 +109,7 @@ pub struct HeaderValue<'a> {
     pub vstr: &'a str,
     pub vtype: HeaderValueType,
     vtags: Option<HeaderTags<'a>>,
-    sip_uri: Option<SipUri<'a>>,
+    uri: Option<Uri<'a>>,
 }
 
 impl<'a> HeaderValue<'a> {
@@ -112,7 +118,7 @@ impl<'a> HeaderValue<'a> {
             vstr: "",
             vtype: HeaderValueType::EmptyValue,
             vtags: None,
-            sip_uri: None,
+            uri: None,
         }
     }
 
@@ -120,7 +126,7 @@ impl<'a> HeaderValue<'a> {
         val: &'a [u8],
         vtype: HeaderValueType,
         vtags: Option<HeaderTags<'a>>,
-        sip_uri: Option<SipUri<'a>>,
+        uri: Option<Uri<'a>>,
     ) -> nom::IResult<&'a [u8], HeaderValue<'a>, SipParseError<'a>> {
         let (_, vstr) = from_utf8_nom(val)?;
 
@@ -130,7 +136,7 @@ impl<'a> HeaderValue<'a> {
                 vstr: vstr,
                 vtype: vtype,
                 vtags: vtags,
-                sip_uri: sip_uri,
+                uri,
             },
         ))
     }
@@ -139,8 +145,14 @@ impl<'a> HeaderValue<'a> {
         self.vtags.as_ref()
     }
 
+    /// SIP or SIPS URI of name-addr value
     pub fn sip_uri(&self) -> Option<&SipUri<'a>> {
-        self.sip_uri.as_ref()
+        self.uri.as_ref().and_then(Uri::sip)
+    }
+
+    /// URI of name-addr, Alert-Info, Call-Info or Error-Info value
+    pub fn uri(&self) -> Option<&Uri<'a>> {
+        self.uri.as_ref()
     }
 }
 
@@ -197,6 +209,17 @@ impl<'a> Header<'a> {
         input: &'a [u8],
         parser: HeaderValueParserFn,
     ) -> nom::IResult<&'a [u8], (HeaderValue<'a>, Option<GenericParams<'a>>), SipParseError<'a>>
+    {
+        Header::take_value_with(input, parser, take_token_value)
+    }
+
+    /// Same as [`take_value`](Header::take_value),
+    /// unquoted values of parameters are taken by `param_parser`
+    pub(crate) fn take_value_with(
+        input: &'a [u8],
+        parser: HeaderValueParserFn,
+        param_parser: ParamValueParserFn,
+    ) -> nom::IResult<&'a [u8], (HeaderValue<'a>, Option<GenericParams<'a>>), SipParseError<'a>>
     {
         if is_crlf(input) {
             return Ok((input, (HeaderValue::create_empty_value(), None))); // This is header with empty value
@@ -215,7 +238,7 @@ impl<'a> Header<'a> {
         }
 
         if inp[0] == b';' {
-            let (inp, params) = Header::try_take_parameters(inp)?;
+            let (inp, params) = Header::try_take_parameters(inp, param_parser)?;
             return Ok((inp, (value, params)));
         }
         Ok((inp, (value, None)))
@@ -223,11 +246,12 @@ impl<'a> Header<'a> {
 
     fn try_take_parameters(
         input: &'a [u8],
+        param_parser: ParamValueParserFn,
     ) -> nom::IResult<&'a [u8], Option<GenericParams<'a>>, SipParseError<'a>> {
         if input.is_empty() || input[0] != b';' {
             return Ok((input, None));
         }
-        let (input, parameters) = GenericParams::parse(input)?;
+        let (input, parameters) = GenericParams::parse_with(input, param_parser)?;
         Ok((input, Some(parameters)))
     }
 
@@ -235,11 +259,24 @@ impl<'a> Header<'a> {
         input: &'a [u8],
     ) -> nom::IResult<&[u8], (Option<SipRFCHeader>, VecDeque<Header<'a>>), SipParseError> {
         let mut headers = VecDeque::new();
-        let (input, header_name) = Header::take_name(input)?;
+        let line = input;
+        let (input, header_name) = Header::take_name(input).map_err(|e| {
+            map_sip_err(e, |e| e.with_kind(SipParseErrorKind::HeaderName, line))
+        })?;
         let (rfc_type, value_parser) = Header::find_parser(header_name);
+        let param_parser = match rfc_type {
+            Some(SipRFCHeader::Via) => via_header::take_param_value,
+            _ => take_token_value,
+        };
         let mut inp = input;
         loop {
-            let (input, (value, params)) = Header::take_value(inp, value_parser)?;
+            let (input, (value, params)) =
+                Header::take_value_with(inp, value_parser, param_parser).map_err(|e| {
+                    map_sip_err(e, |e| {
+                        e.with_kind(SipParseErrorKind::HeaderValue, inp)
+                            .in_header(header_name)
+                    })
+                })?;
             headers.push_back(Header::new(header_name, value, params, &inp[..inp.len() - input.len()]));
             if input[0] == b',' {
                 let (input, _) = take_sws_token::comma(input)?;
@@ -251,4 +288,20 @@ impl<'a> Header<'a> {
         }
         Ok((inp, (rfc_type, headers)))
     }
+
+    /// Parses header line with one value, e.g. `Max-Forwards: 69\r\n`,
+    /// so it can be added to [`SipHeaders`](crate::SipHeaders) with `insert`, `prepend` or `replace`.
+    /// Line must end with CRLF
+    pub fn from_line(line: &'a [u8]) -> Result<Header<'a>, SipParseError<'a>> {
+        let (rest, (_, mut headers)) = Header::parse(line).map_err(SipParseError::from)?;
+        if rest != b"\r\n" {
+            return Err(SipParseError::new(3, Some("Header line must end with CRLF"))
+                .with_kind(SipParseErrorKind::HeaderValue, rest));
+        }
+        match headers.pop_front() {
+            Some(hdr) if headers.is_empty() => Ok(hdr),
+            _ => Err(SipParseError::new(4, Some("Header line contains several values"))
+                .with_kind(SipParseErrorKind::HeaderValue, line)),
+        }
+    }
 }
@@ -1,18 +1,86 @@
 use crate::{
-    common::{bnfcore::is_crlf, errorparse::SipParseError},
-    headers::{SipHeader, SipRFCHeader},
+    common::{
+        bnfcore::is_crlf,
+        errorparse::{map_sip_err, SipParseError, SipParseErrorKind},
+        sip_date::SipDate,
+        sip_method::SipMethod,
+    },
+    headers::{
+        header::{Header, HeaderTagType},
+        SipHeader, SipRFCHeader,
+    },
 };
-use alloc::collections::{
-    btree_map::{BTreeMap, Keys},
-    VecDeque,
+use alloc::{
+    collections::{
+        btree_map::{BTreeMap, Keys},
+        VecDeque,
+    },
+    vec::Vec,
 };
-use core::str;
+use core::str::{self, FromStr};
 use nom::bytes::complete::tag;
 use unicase::Ascii;
 
+/// Header field line as it appears in message.
+/// One line may contain several comma-separated header values.
+#[derive(PartialEq, Debug)]
+pub struct HeaderLine<'a> {
+    /// Type of header if it is defined in rfc
+    pub rfc_type: Option<SipRFCHeader>,
+    /// Header name as it is written in message (compact form is kept)
+    pub name: Ascii<&'a str>,
+    /// Count of header values in the line
+    pub values_count: usize,
+    /// Bytes of the line without trailing CRLF.
+    /// `None` if the line was modified and must be rebuilt from its values
+    pub raw: Option<&'a [u8]>,
+    /// Line failed to parse and is kept as [`InvalidHeader`]
+    pub invalid: bool,
+}
+
+impl<'a> HeaderLine<'a> {
+    fn new(hdr: &SipHeader<'a>) -> HeaderLine<'a> {
+        HeaderLine {
+            rfc_type: SipRFCHeader::from_str(&hdr.name),
+            name: hdr.name,
+            values_count: 1,
+            raw: None,
+            invalid: false,
+        }
+    }
+
+    fn is_same_header(&self, rfc_type: Option<SipRFCHeader>, name: &Ascii<&str>) -> bool {
+        if self.invalid {
+            return false;
+        }
+        match rfc_type {
+            Some(_) => self.rfc_type == rfc_type,
+            None => self.rfc_type.is_none() && self.name == *name,
+        }
+    }
+}
+
+/// Header line that failed to parse in lenient mode
+/// (see [`Headers::parse_lenient`]).
+/// It is serialized as is.
+#[derive(Debug)]
+pub struct InvalidHeader<'a> {
+    /// Header name, empty if the name itself is malformed
+    pub name: Ascii<&'a str>,
+    /// Type of header if it is defined in rfc
+    pub rfc_type: Option<SipRFCHeader>,
+    /// Bytes of the line without trailing CRLF
+    pub raw: &'a [u8],
+    /// Error of header parser
+    pub error: SipParseError<'a>,
+}
+
 pub struct Headers<'a> {
     rfc_headers: BTreeMap<SipRFCHeader, VecDeque<SipHeader<'a>>>,
     ext_headers: Option<BTreeMap<Ascii<&'a str>, VecDeque<SipHeader<'a>>>>,
+    /// Header lines in original order
+    lines: Vec<HeaderLine<'a>>,
+    invalid: Vec<InvalidHeader<'a>>,
 }
 
 impl<'a> Headers<'a> {
@@ -72,6 +140,8 @@ impl<'a> Headers<'a> {
         Headers {
             ext_headers: None,
             rfc_headers: BTreeMap::<SipRFCHeader, VecDeque<SipHeader<'a>>>::new(),
+            lines: Vec::new(),
+            invalid: Vec::new(),
         }
     }
 
@@ -115,6 +185,267 @@ impl<'a> Headers<'a> {
         }
     }
 
+    /// Header lines in the order they appear in message
+    pub fn lines(&self) -> &[HeaderLine<'a>] {
+        &self.lines
+    }
+
+    /// Headers that failed to parse in lenient mode, in the order they appear in message
+    pub fn invalid_headers(&self) -> &[InvalidHeader<'a>] {
+        &self.invalid
+    }
+
+    pub fn has_invalid_headers(&self) -> bool {
+        !self.invalid.is_empty()
+    }
+
+    pub(crate) fn invalid_headers_mut(&mut self) -> &mut [InvalidHeader<'a>] {
+        &mut self.invalid
+    }
+
+    /// Value of header that must be present only one time
+    fn single_value(&self, hdr: SipRFCHeader) -> Result<Option<&SipHeader<'a>>, SipParseError<'a>> {
+        match self.get_rfc(hdr) {
+            Some(hdrs) if hdrs.len() == 1 => Ok(Some(&hdrs[0])),
+            Some(hdrs) => Err(value_error(
+                &hdrs[1],
+                1,
+                match hdr {
+                    SipRFCHeader::ContentLength => {
+                        "Content-Length header must be present only one time"
+                    }
+                    _ => "Header must be present only one time",
+                },
+            )),
+            None => Ok(None),
+        }
+    }
+
+    fn number_value<T: FromStr>(&self, hdr: SipRFCHeader) -> Result<Option<T>, SipParseError<'a>> {
+        match self.single_value(hdr)? {
+            Some(hdr) => match hdr.value.vstr.parse() {
+                Ok(number) => Ok(Some(number)),
+                Err(_) => Err(value_error(hdr, 2, "Number is out of range")),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Content-Length header value.
+    /// Returns error if header is repeated or value is out of range
+    pub fn content_length(&self) -> Result<Option<u64>, SipParseError<'a>> {
+        self.number_value(SipRFCHeader::ContentLength)
+    }
+
+    /// Max-Forwards header value
+    pub fn max_forwards(&self) -> Result<Option<u32>, SipParseError<'a>> {
+        self.number_value(SipRFCHeader::MaxForwards)
+    }
+
+    /// Expires header value in seconds
+    pub fn expires(&self) -> Result<Option<u32>, SipParseError<'a>> {
+        self.number_value(SipRFCHeader::Expires)
+    }
+
+    /// Min-Expires header value in seconds
+    pub fn min_expires(&self) -> Result<Option<u32>, SipParseError<'a>> {
+        self.number_value(SipRFCHeader::MinExpires)
+    }
+
+    /// Sequence number and method of CSeq header.
+    /// Sequence number must be less than 2**31
+    /// ([rfc3261 section-8.1.1.5](https://tools.ietf.org/html/rfc3261#section-8.1.1.5))
+    pub fn cseq(&self) -> Result<Option<(u32, SipMethod<'a>)>, SipParseError<'a>> {
+        let hdr = match self.single_value(SipRFCHeader::CSeq)? {
+            Some(hdr) => hdr,
+            None => return Ok(None),
+        };
+        let tag = |tag_type| {
+            let value = hdr.value.tags()?.get(&tag_type)?;
+            str::from_utf8(value).ok()
+        };
+        let number = match tag(HeaderTagType::Number).map(str::parse::<u32>) {
+            Some(Ok(number)) if number < 1 << 31 => number,
+            _ => return Err(value_error(hdr, 2, "Number is out of range")),
+        };
+        match tag(HeaderTagType::Method).and_then(SipMethod::from_str) {
+            Some(method) => Ok(Some((number, method))),
+            None => Err(value_error(hdr, 3, "Invalid method")),
+        }
+    }
+
+    /// Date header value
+    pub fn date(&self) -> Result<Option<SipDate>, SipParseError<'a>> {
+        match self.single_value(SipRFCHeader::Date)? {
+            Some(hdr) => match SipDate::parse(hdr.value.vstr.as_bytes()) {
+                Ok((_, date)) => Ok(Some(date)),
+                Err(_) => Err(value_error(hdr, 4, "Invalid date")),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Retry-After header value in seconds, comment and parameters are skipped
+    pub fn retry_after(&self) -> Result<Option<u32>, SipParseError<'a>> {
+        let hdr = match self.single_value(SipRFCHeader::RetryAfter)? {
+            Some(hdr) => hdr,
+            None => return Ok(None),
+        };
+        let seconds = hdr
+            .value
+            .tags()
+            .and_then(|tags| tags.get(&HeaderTagType::Seconds))
+            .and_then(|seconds| str::from_utf8(seconds).ok()?.parse().ok());
+        match seconds {
+            Some(seconds) => Ok(Some(seconds)),
+            None => Err(value_error(hdr, 2, "Number is out of range")),
+        }
+    }
+
+    /// Returns all headers in the order they appear in message
+    pub fn iter(&self) -> impl Iterator<Item = &SipHeader<'a>> {
+        self.lines_with_values()
+            .into_iter()
+            .flat_map(|(_, values)| values)
+    }
+
+    /// Header lines in original order with the values they contain
+    pub(crate) fn lines_with_values(&self) -> Vec<(&HeaderLine<'a>, Vec<&SipHeader<'a>>)> {
+        // Position of the first not visited value of each header
+        let mut rfc_positions = BTreeMap::<SipRFCHeader, usize>::new();
+        let mut ext_positions = BTreeMap::<Ascii<&'a str>, usize>::new();
+        let mut result = Vec::with_capacity(self.lines.len());
+        for line in self.lines.iter() {
+            let (hdrs, position) = match line.rfc_type {
+                Some(rfc_type) => (
+                    self.get_rfc(rfc_type),
+                    rfc_positions.entry(rfc_type).or_insert(0),
+                ),
+                None => (
+                    self.ext_headers.as_ref().and_then(|h| h.get(&line.name)),
+                    ext_positions.entry(line.name).or_insert(0),
+                ),
+            };
+            let values = match hdrs {
+                Some(hdrs) => hdrs
+                    .range(*position..*position + line.values_count)
+                    .collect(),
+                None => Vec::new(),
+            };
+            *position += line.values_count;
+            result.push((line, values));
+        }
+        result
+    }
+
+    /// Adds header after all other headers
+    pub fn insert(&mut self, hdr: SipHeader<'a>) {
+        self.lines.push(HeaderLine::new(&hdr));
+        self.add_header(hdr, false);
+    }
+
+    /// Adds header above other headers with the same name
+    /// or on top of all headers if there are no such headers.
+    /// Use it to add `Via` or `Record-Route` header.
+    pub fn prepend(&mut self, hdr: SipHeader<'a>) {
+        let line = HeaderLine::new(&hdr);
+        let position = self
+            .lines
+            .iter()
+            .position(|l| l.is_same_header(line.rfc_type, &line.name))
+            .unwrap_or(0);
+        self.lines.insert(position, line);
+        self.add_header(hdr, true);
+    }
+
+    /// Replaces all headers with the same name by `hdr`.
+    /// New header takes place of the first replaced one.
+    /// Returns replaced headers.
+    pub fn replace(&mut self, hdr: SipHeader<'a>) -> Option<VecDeque<SipHeader<'a>>> {
+        let line = HeaderLine::new(&hdr);
+        // Removal also drops invalid lines of the header,
+        // so position is counted among the lines that are left
+        let is_removed = |l: &HeaderLine| match line.rfc_type {
+            Some(_) => l.rfc_type == line.rfc_type,
+            None => l.rfc_type.is_none() && l.name == line.name,
+        };
+        let position = self
+            .lines
+            .iter()
+            .position(|l| l.is_same_header(line.rfc_type, &line.name))
+            .map(|idx| self.lines[..idx].iter().filter(|l| !is_removed(l)).count());
+        let removed = match line.rfc_type {
+            Some(rfc_type) => self.remove_rfc(rfc_type),
+            None => self.remove_ext(&hdr.name),
+        };
+        self.lines
+            .insert(position.unwrap_or(self.lines.len()), line);
+        self.add_header(hdr, false);
+        removed
+    }
+
+    /// Removes all headers of the type including invalid ones. Returns removed headers.
+    pub fn remove_rfc(&mut self, hdr: SipRFCHeader) -> Option<VecDeque<SipHeader<'a>>> {
+        self.lines.retain(|l| l.rfc_type != Some(hdr));
+        self.invalid.retain(|h| h.rfc_type != Some(hdr));
+        self.rfc_headers.remove(&hdr)
+    }
+
+    /// Removes all extension headers with the name including invalid ones.
+    /// Returns removed headers.
+    pub fn remove_ext(&mut self, name: &str) -> Option<VecDeque<SipHeader<'a>>> {
+        let name = Ascii::new(name);
+        self.lines
+            .retain(|l| l.rfc_type.is_some() || l.name != name);
+        self.invalid
+            .retain(|h| h.rfc_type.is_some() || h.name != name);
+        let ext_headers = self.ext_headers.as_mut()?;
+        let key = *ext_headers.keys().find(|k| **k == name)?;
+        let removed = ext_headers.remove(&key);
+        if ext_headers.is_empty() {
+            self.ext_headers = None;
+        }
+        removed
+    }
+
+    /// Removes topmost value of header. Use it to remove `Route` or `Via` header.
+    pub fn pop_rfc(&mut self, hdr: SipRFCHeader) -> Option<SipHeader<'a>> {
+        let hdrs = self.rfc_headers.get_mut(&hdr)?;
+        let value = hdrs.pop_front();
+        if hdrs.is_empty() {
+            self.rfc_headers.remove(&hdr);
+        }
+        if let Some(position) = self
+            .lines
+            .iter()
+            .position(|l| l.rfc_type == Some(hdr) && !l.invalid)
+        {
+            let line = &mut self.lines[position];
+            line.values_count -= 1;
+            line.raw = None;
+            if line.values_count == 0 {
+                self.lines.remove(position);
+            }
+        }
+        value
+    }
+
+    fn add_header(&mut self, hdr: SipHeader<'a>, to_front: bool) {
+        let hdrs = match SipRFCHeader::from_str(&hdr.name) {
+            Some(rfc_type) => self.rfc_headers.entry(rfc_type).or_default(),
+            None => self
+                .ext_headers
+                .get_or_insert_with(BTreeMap::new)
+                .entry(hdr.name)
+                .or_default(),
+        };
+        if to_front {
+            hdrs.push_front(hdr);
+        } else {
+            hdrs.push_back(hdr);
+        }
+    }
+
     pub fn get_rfc_headers_keys(&self) -> Keys<'_, SipRFCHeader, VecDeque<SipHeader<'a>>> {
         self.rfc_headers.keys()
     }
@@ -129,20 +460,40 @@ impl<'a> Headers<'a> {
     }
 
     pub fn parse(input: &'a [u8]) -> nom::IResult<&[u8], Headers<'a>, SipParseError> {
+        Headers::take_headers(input, false)
+    }
+
+    /// Parses headers like [`parse`](Headers::parse) but doesn't fail on malformed header.
+    /// Such header is kept as [`InvalidHeader`] with the parse error
+    /// and is available via [`invalid_headers`](Headers::invalid_headers).
+    /// Error is still returned if the end of header line can't be found.
+    pub fn parse_lenient(
+        input: &'a [u8],
+    ) -> nom::IResult<&'a [u8], Headers<'a>, SipParseError<'a>> {
+        Headers::take_headers(input, true)
+    }
+
+    pub(crate) fn take_headers(
+        input: &'a [u8],
+        lenient: bool,
+    ) -> nom::IResult<&'a [u8], Headers<'a>, SipParseError<'a>> {
         let mut headers_result = Headers::new();
         let mut inp2 = input;
         loop {
-            let (input, (rfc_type, vec_headers)) = SipHeader::parse(inp2)?;
-            match rfc_type {
-                Some(hdr_type) => {
-                    headers_result.add_rfc_header(hdr_type, vec_headers);
+            let input = match headers_result.take_header(inp2) {
+                Ok((input, _)) => input,
+                Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) if lenient => {
+                    match split_header_line(inp2) {
+                        Some((line, input)) if !line.is_empty() => {
+                            headers_result.add_invalid_header(line, e);
+                            &input[2..] // skip crlf of header field
+                        }
+                        _ => return Err(nom::Err::Error(e)),
+                    }
                 }
-                None => {
-                    headers_result.add_extension_header(vec_headers);
-                }
-            }
-            let (input, _) = tag("\r\n")(input)?; // move to header parse
-            inp2 = input; // skip crlf of header field
+                Err(e) => return Err(e),
+            };
+            inp2 = input;
             if is_crlf(inp2) {
                 // end of headers and start of body part
                 break;
@@ -150,6 +501,75 @@ impl<'a> Headers<'a> {
         }
         Ok((inp2, headers_result))
     }
+
+    /// Parses one header line with its CRLF and adds it to headers
+    fn take_header(&mut self, line: &'a [u8]) -> nom::IResult<&'a [u8], (), SipParseError<'a>> {
+        let (input, (rfc_type, vec_headers)) = SipHeader::parse(line)?;
+        let name = vec_headers[0].name;
+        let (rest, _) = tag("\r\n")(input).map_err(|e| {
+            map_sip_err(e, |e| {
+                e.with_kind(SipParseErrorKind::HeaderValue, input)
+                    .in_header(name.into_inner())
+            })
+        })?; // move to header parse
+        self.lines.push(HeaderLine {
+            rfc_type,
+            name,
+            values_count: vec_headers.len(),
+            raw: Some(&line[..line.len() - input.len()]),
+            invalid: false,
+        });
+        match rfc_type {
+            Some(hdr_type) => {
+                self.add_rfc_header(hdr_type, vec_headers);
+            }
+            None => {
+                self.add_extension_header(vec_headers);
+            }
+        }
+        Ok((rest, ()))
+    }
+
+    fn add_invalid_header(&mut self, line: &'a [u8], error: SipParseError<'a>) {
+        let name = match Header::take_name(line) {
+            Ok((_, name)) => name,
+            Err(_) => "",
+        };
+        let rfc_type = SipRFCHeader::from_str(name);
+        self.lines.push(HeaderLine {
+            rfc_type,
+            name: Ascii::new(name),
+            values_count: 0,
+            raw: Some(line),
+            invalid: true,
+        });
+        self.invalid.push(InvalidHeader {
+            name: Ascii::new(name),
+            rfc_type,
+            raw: line,
+            error,
+        });
+    }
+}
+
+/// Error of header value that failed typed conversion
+fn value_error<'a>(hdr: &SipHeader<'a>, code: u32, message: &'a str) -> SipParseError<'a> {
+    SipParseError::new(code, Some(message))
+        .with_kind(SipParseErrorKind::HeaderValue, hdr.value.vstr.as_bytes())
+        .in_header(hdr.name.into_inner())
+}
+
+/// Splits header field line (with folded continuation lines) from the input.
+/// Returns the line without CRLF and the input starting from CRLF
+fn split_header_line(input: &[u8]) -> Option<(&[u8], &[u8])> {
+    let mut idx = 0;
+    loop {
+        let crlf = idx + input[idx..].windows(2).position(|w| w == b"\r\n")?;
+        match input.get(crlf + 2) {
+            Some(b' ') | Some(b'\t') => idx = crlf + 2,
+            _ => return Some((&input[..crlf], &input[crlf..])),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -205,8 +625,130 @@ mod tests {
                     "Value"
                 );
                 assert_eq!(hdrs.get_rfc(SipRFCHeader::Route).unwrap().len(), 2);
+
+                assert_eq!(hdrs.lines().len(), 9);
+                assert_eq!(hdrs.lines()[1].raw, Some(&b"To: sip:user@example.com"[..]));
+                assert_eq!(hdrs.lines()[0].values_count, 3);
+                let order: Vec<&str> = hdrs.iter().map(|h| h.value.vstr).collect();
+                assert_eq!(
+                    order,
+                    [
+                        "application/sdp",
+                        "application/pkcs7-mime",
+                        "application/h.245",
+                        "sip:user@example.com",
+                        "<sip:192.0.2.254:5060>",
+                        "<sip:[2001:db8::1]>",
+                        "70",
+                        "lwsdisp.1234abcd@funky.example.com",
+                        "60 OPTIONS",
+                        "Value",
+                        "SIP/2.0/UDP funky.example.com"
+                    ]
+                );
             }
             Err(_) => panic!(),
         }
     }
+
+    #[test]
+    fn headers_parse_lenient_test() {
+        let input = "Max-Forwards: 70\r\n\
+             CSeq: abc\r\n \
+              INVITE\r\n\
+             Bad Header\r\n\
+             Call-ID: a84b4c76e66710\r\n\r\n"
+            .as_bytes();
+        assert!(Headers::parse(input).is_err());
+        let (rest, hdrs) = Headers::parse_lenient(input).unwrap();
+        assert_eq!(rest, b"\r\n");
+        assert_eq!(hdrs.lines().len(), 4);
+        assert_eq!(hdrs.iter().count(), 2);
+        assert!(hdrs.get_rfc(SipRFCHeader::CSeq).is_none());
+
+        let invalid = hdrs.invalid_headers();
+        assert_eq!(invalid.len(), 2);
+        assert_eq!(invalid[0].rfc_type, Some(SipRFCHeader::CSeq));
+        assert_eq!(invalid[0].raw, b"CSeq: abc\r\n INVITE");
+        assert_eq!(invalid[0].error.kind(), SipParseErrorKind::HeaderValue);
+        assert_eq!(invalid[0].error.details().unwrap().header, Some("CSeq"));
+        assert_eq!(invalid[1].name, "");
+        assert_eq!(invalid[1].raw, b"Bad Header");
+        assert_eq!(invalid[1].error.kind(), SipParseErrorKind::HeaderName);
+
+        assert!(Headers::parse_lenient(b"\r\n").is_err());
+        assert!(Headers::parse_lenient(b"CSeq: abc").is_err());
+    }
+
+    #[test]
+    fn headers_replace_with_invalid_test() {
+        let input = "Call-ID: a84b4c76e66710\r\n\
+             CSeq: abc\r\n\
+             CSeq: 1 OPTIONS\r\n\r\n"
+            .as_bytes();
+        let (_, mut hdrs) = Headers::parse_lenient(input).unwrap();
+        let (_, (_, mut cseq)) = Header::parse(b"CSeq: 2 OPTIONS\r\n").unwrap();
+        let replaced = hdrs.replace(cseq.pop_front().unwrap()).unwrap();
+        assert_eq!(replaced[0].value.vstr, "1 OPTIONS");
+        assert!(!hdrs.has_invalid_headers());
+        assert_eq!(hdrs.lines().len(), 2);
+        assert_eq!(hdrs.lines()[1].rfc_type, Some(SipRFCHeader::CSeq));
+        assert_eq!(
+            hdrs.get_rfc_s(SipRFCHeader::CSeq).unwrap().value.vstr,
+            "2 OPTIONS"
+        );
+    }
+
+    #[test]
+    fn headers_typed_numbers_test() {
+        let (_, hdrs) = Headers::parse(
+            "CSeq: 2147483647 REGISTER\r\n\
+             Max-Forwards: 70\r\n\
+             Expires: 4294967295\r\n\
+             Retry-After: 18000;duration=3600\r\n\
+             l: 12\r\n\r\n"
+                .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(
+            hdrs.cseq().unwrap(),
+            Some((2147483647, SipMethod::REGISTER))
+        );
+        assert_eq!(hdrs.max_forwards().unwrap(), Some(70));
+        assert_eq!(hdrs.expires().unwrap(), Some(4294967295));
+        assert_eq!(hdrs.content_length().unwrap(), Some(12));
+        assert_eq!(hdrs.min_expires().unwrap(), None);
+        assert_eq!(hdrs.retry_after().unwrap(), Some(18000));
+
+        let (_, hdrs) = Headers::parse(
+            "CSeq: 2147483648 INVITE\r\n\
+             Expires: 4294967296\r\n\
+             Max-Forwards: 1\r\n\
+             Max-Forwards: 2\r\n\r\n"
+                .as_bytes(),
+        )
+        .unwrap();
+        let err = hdrs.cseq().unwrap_err();
+        match err {
+            SipParseError::HeaderValue(details) => assert_eq!(details.header, Some("CSeq")),
+            _ => panic!("{:?}", err),
+        }
+        let err = hdrs.expires().unwrap_err();
+        assert_eq!(err.details().unwrap().header, Some("Expires"));
+        assert!(hdrs.max_forwards().is_err());
+    }
+
+    #[test]
+    fn headers_date_test() {
+        let (_, hdrs) =
+            Headers::parse("Date: Sat, 13 Nov 2010 23:29:00 GMT\r\n\r\n".as_bytes()).unwrap();
+        let date = hdrs.date().unwrap().unwrap();
+        assert_eq!(date.unix_seconds(), 1289690940);
+
+        // Date header parser checks only shape, calendar is checked by typed getter
+        let (_, hdrs) =
+            Headers::parse("Date: Fri, 13 Nov 2010 23:29:60 GMT\r\n\r\n".as_bytes()).unwrap();
+        let err = hdrs.date().unwrap_err();
+        assert_eq!(err.details().unwrap().header, Some("Date"));
+    }
 }
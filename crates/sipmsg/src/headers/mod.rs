@@ -1,4 +1,6 @@
 mod headers;
+pub use headers::HeaderLine as SipHeaderLine;
+pub use headers::InvalidHeader as SipInvalidHeader;
 pub use headers::Headers as SipHeaders;
 
 mod header;
@@ -17,6 +19,18 @@ pub use generic_params::GenericParams;
 pub mod sipuri;
 pub use sipuri::SipUri;
 
+pub mod teluri;
+pub use teluri::TelUri;
+
+pub mod uri;
+pub use uri::{AbsoluteUri, Uri};
+
+pub mod name_addr_header;
+pub use name_addr_header::{ContactValue, NameAddr, QValue};
+
+pub mod via_header;
+pub use via_header::{SentProtocol, ViaHeader};
+
 mod name_addr;
 mod parsers;
 mod auth_params;
\ No newline at end of file
@@ -11,7 +11,7 @@ use nom::{
     sequence::tuple,
 };
 
-use crate::SipUri;
+use crate::Uri;
 
 #[derive(PartialEq, Debug)]
 pub enum NameAddrValueType {
@@ -30,11 +30,7 @@ fn predict_value_type(input: &[u8]) -> NameAddrValueType {
         return NameAddrValueType::AquoutedSipURI;
     }
 
-    if &input[..3] != b"sip" {
-        return NameAddrValueType::TokenDisplayName;
-    }
-
-    if input[3] == b':' || &input[3..5] == b"s:" {
+    if Uri::is_uri_start(input) {
         return NameAddrValueType::SipURI; // this is start of URI, display name isn't present
     }
 
@@ -61,7 +57,7 @@ fn take_display_name(
 
 pub fn take<'a>(
     source_input: &'a [u8],
-) -> nom::IResult<&'a [u8], (&[u8], HeaderTags<'a>, Option<SipUri>), SipParseError<'a>> {
+) -> nom::IResult<&'a [u8], (&[u8], HeaderTags<'a>, Option<Uri>), SipParseError<'a>> {
     if source_input.len() < 5 {
         return sip_parse_error!(2, "name-addr header value is too short");
     }
@@ -92,13 +88,13 @@ pub fn take<'a>(
         return sip_parse_error!(2, "Contact header value is too short");
     }
 
-    let is_sip_uri = &input[..4] == b"sip:" || &input[..5] == b"sips:";
-    if !is_sip_uri && !is_quoted_uri {
+    let is_known_uri = Uri::is_uri_start(input);
+    if !is_known_uri && !is_quoted_uri {
         return sip_parse_error!(4, "Absolute uri in contact header without <> not supported");
     }
 
-    if is_sip_uri {
-        let (input, sipuri) = SipUri::parse_ext(input, is_quoted_uri)?;
+    if is_known_uri {
+        let (input, uri) = Uri::parse_ext(input, is_quoted_uri)?;
         let mut count_wsps_after_raquout = 0;
         let input = if is_quoted_uri {
             let (input, wsps_after) = take_sws_token::raquot(input)?;
@@ -112,7 +108,7 @@ pub fn take<'a>(
             (
                 &source_input[..source_input.len() - input.len() - count_wsps_after_raquout],
                 tags,
-                Some(sipuri),
+                Some(uri),
             ),
         ));
     }
@@ -127,7 +123,7 @@ pub fn take<'a>(
         (
             &source_input[..source_input.len() - input.len() - spaces_after_raquot.len()],
             tags,
-            None,
+            Uri::from_bracketed(uri),
         ),
     ))
 }
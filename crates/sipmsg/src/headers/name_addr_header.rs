@@ -0,0 +1,348 @@
+use crate::{
+    common::{
+        bnfcore::is_digit,
+        errorparse::{map_sip_err, SipParseError, SipParseErrorKind},
+        nom_wrappers::from_utf8_nom,
+    },
+    headers::{
+        generic_params::write_param,
+        header::{Header, HeaderTagType},
+        name_addr,
+        parsers::Contact,
+        traits::SipHeaderParser,
+        Uri,
+    },
+};
+use alloc::{borrow::Cow, collections::btree_map::BTreeMap, string::String};
+use core::{fmt, str};
+use unicase::Ascii;
+
+/// qvalue  =  ( "0" [ "." 0*3DIGIT ] ) / ( "1" [ "." 0*3("0") ] )
+///
+/// Stored as thousandths to be compared exactly
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct QValue(u16);
+
+impl QValue {
+    /// Returns `None` if value is more than 1000
+    pub fn from_thousandths(value: u16) -> Option<QValue> {
+        if value <= 1000 {
+            Some(QValue(value))
+        } else {
+            None
+        }
+    }
+
+    pub fn thousandths(&self) -> u16 {
+        self.0
+    }
+
+    pub fn parse(s: &str) -> Option<QValue> {
+        let bytes = s.as_bytes();
+        if bytes.is_empty() || (bytes[0] != b'0' && bytes[0] != b'1') {
+            return None;
+        }
+        let fraction = match &bytes[1..] {
+            [] => &[][..],
+            [b'.', fraction @ ..] => fraction,
+            _ => return None,
+        };
+        if fraction.len() > 3 || !fraction.iter().all(|c| is_digit(*c)) {
+            return None;
+        }
+        let mut value = u16::from(bytes[0] - b'0') * 1000;
+        for (idx, c) in fraction.iter().enumerate() {
+            value += u16::from(c - b'0') * [100, 10, 1][idx];
+        }
+        QValue::from_thousandths(value)
+    }
+}
+
+impl fmt::Display for QValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0 / 1000)?;
+        let mut fraction = self.0 % 1000;
+        if fraction == 0 {
+            return Ok(());
+        }
+        f.write_str(".")?;
+        let mut divider = 100;
+        while fraction != 0 {
+            write!(f, "{}", fraction / divider)?;
+            fraction %= divider;
+            divider /= 10;
+        }
+        Ok(())
+    }
+}
+
+/// Typed value of From, To, Contact, Route, Record-Route or Reply-To header:
+/// `[ display-name ] LAQUOT addr-spec RAQUOT` or `addr-spec` with header parameters.
+///
+/// ```rust
+/// use sipmsg::{NameAddr, SipHeaders, SipRFCHeader};
+///
+/// let (_, headers) = SipHeaders::parse(
+///     b"From: \"Alice \\\"A\\\"\" <sip:alice@atlanta.com>;tag=1928301774\r\n\r\n",
+/// )
+/// .unwrap();
+/// let from = NameAddr::from_header(headers.get_rfc_s(SipRFCHeader::From).unwrap()).unwrap();
+/// assert_eq!(from.display_name.as_deref(), Some("Alice \"A\""));
+/// assert_eq!(from.uri.sip().unwrap().hostport.host, "atlanta.com");
+/// assert_eq!(from.tag, Some("1928301774"));
+/// ```
+#[derive(PartialEq, Debug)]
+pub struct NameAddr<'a> {
+    /// Display name without quotes and with quoted-pairs unescaped
+    pub display_name: Option<Cow<'a, str>>,
+    pub uri: Uri<'a>,
+    /// `tag` parameter of From and To
+    pub tag: Option<&'a str>,
+    /// `q` parameter of Contact
+    pub q: Option<QValue>,
+    /// `expires` parameter of Contact in seconds
+    pub expires: Option<u32>,
+    /// Other header parameters
+    params: BTreeMap<Ascii<&'a str>, Option<&'a str>>,
+}
+
+/// Removes backslashes of quoted-pairs
+pub(crate) fn unescape_quoted(s: &str) -> Cow<'_, str> {
+    if !s.contains('\\') {
+        return Cow::Borrowed(s);
+    }
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+    Cow::Owned(unescaped)
+}
+
+impl<'a> NameAddr<'a> {
+    /// Builds typed value from parsed header.
+    /// Returns error for wildcard Contact, use [`ContactValue`] for Contact header
+    pub fn from_header(hdr: &Header<'a>) -> Result<NameAddr<'a>, SipParseError<'a>> {
+        NameAddr::from_parsed(hdr)
+            .map_err(|e| SipParseError::from(e).in_header(hdr.name.into_inner()))
+    }
+
+    fn from_parsed(hdr: &Header<'a>) -> Result<NameAddr<'a>, nom::Err<SipParseError<'a>>> {
+        // Value is parsed again because the header keeps its URI
+        let (_, (_, tags, uri)) = name_addr::take(hdr.value.vstr.as_bytes())?;
+        let uri = match uri {
+            Some(uri) => uri,
+            None => return sip_parse_error!(1, "Invalid URI"),
+        };
+        let display_name = match tags.get(&HeaderTagType::DisplayName) {
+            Some(name) => Some(unescape_quoted(from_utf8_nom(name)?.1)),
+            None => None,
+        };
+        let mut name_addr = NameAddr {
+            display_name,
+            uri,
+            tag: None,
+            q: None,
+            expires: None,
+            params: BTreeMap::new(),
+        };
+        for (name, value) in hdr.params().iter().flat_map(|p| p.iter()) {
+            name_addr.set_param(*name, *value).map_err(|e| {
+                map_sip_err(e, |e| {
+                    e.with_kind(SipParseErrorKind::Parameter, hdr.raw_value_param)
+                })
+            })?;
+        }
+        Ok(name_addr)
+    }
+
+    fn set_param(
+        &mut self,
+        name: Ascii<&'a str>,
+        value: Option<&'a str>,
+    ) -> Result<(), nom::Err<SipParseError<'a>>> {
+        if name == "tag" {
+            self.tag = match value {
+                Some(tag) => Some(tag),
+                None => return sip_parse_error!(2, "Empty tag parameter"),
+            };
+        } else if name == "q" {
+            self.q = match value.and_then(QValue::parse) {
+                Some(q) => Some(q),
+                None => return sip_parse_error!(3, "Invalid q parameter"),
+            };
+        } else if name == "expires" {
+            self.expires = match value.and_then(|expires| expires.parse().ok()) {
+                Some(expires) => Some(expires),
+                None => return sip_parse_error!(4, "Invalid expires parameter"),
+            };
+        } else {
+            self.params.insert(name, value);
+        }
+        Ok(())
+    }
+
+    /// Parses header value with parameters
+    pub fn parse(input: &'a [u8]) -> nom::IResult<&'a [u8], NameAddr<'a>, SipParseError<'a>> {
+        let (rest, value) = ContactValue::parse(input)?;
+        match value {
+            ContactValue::NameAddr(name_addr) => Ok((rest, name_addr)),
+            ContactValue::Wildcard => sip_parse_error!(5, "Wildcard is allowed only in Contact"),
+        }
+    }
+
+    /// Other header parameters
+    pub fn params(&self) -> &BTreeMap<Ascii<&'a str>, Option<&'a str>> {
+        &self.params
+    }
+}
+
+impl<'a> fmt::Display for NameAddr<'a> {
+    /// URI is always enclosed in `<>`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(display_name) = &self.display_name {
+            f.write_str("\"")?;
+            for c in display_name.chars() {
+                if c == '"' || c == '\\' {
+                    f.write_str("\\")?;
+                }
+                write!(f, "{}", c)?;
+            }
+            f.write_str("\" ")?;
+        }
+        write!(f, "<{}>", self.uri)?;
+        if let Some(tag) = self.tag {
+            write!(f, ";tag={}", tag)?;
+        }
+        if let Some(q) = self.q {
+            write!(f, ";q={}", q)?;
+        }
+        if let Some(expires) = self.expires {
+            write!(f, ";expires={}", expires)?;
+        }
+        for (name, value) in self.params.iter() {
+            write_param(f, name, *value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Typed value of Contact header
+#[derive(PartialEq, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum ContactValue<'a> {
+    /// `Contact: *` of REGISTER request that removes all bindings
+    Wildcard,
+    NameAddr(NameAddr<'a>),
+}
+
+impl<'a> ContactValue<'a> {
+    pub fn from_header(hdr: &Header<'a>) -> Result<ContactValue<'a>, SipParseError<'a>> {
+        let is_wildcard = hdr
+            .value
+            .tags()
+            .is_some_and(|tags| tags.contains_key(&HeaderTagType::Star));
+        if is_wildcard {
+            return Ok(ContactValue::Wildcard);
+        }
+        NameAddr::from_header(hdr).map(ContactValue::NameAddr)
+    }
+
+    /// Parses header value with parameters
+    pub fn parse(input: &'a [u8]) -> nom::IResult<&'a [u8], ContactValue<'a>, SipParseError<'a>> {
+        let (rest, (value, params)) = Header::take_value(input, Contact::take_value)?;
+        let hdr = Header::new("Contact", value, params, &input[..input.len() - rest.len()]);
+        match ContactValue::from_header(&hdr) {
+            Ok(value) => Ok((rest, value)),
+            Err(e) => Err(nom::Err::Error(e)),
+        }
+    }
+}
+
+impl<'a> fmt::Display for ContactValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ContactValue::Wildcard => f.write_str("*"),
+            ContactValue::NameAddr(name_addr) => write!(f, "{}", name_addr),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_qvalue() {
+        assert_eq!(QValue::parse("0.7").unwrap().thousandths(), 700);
+        assert_eq!(QValue::parse("1").unwrap().thousandths(), 1000);
+        assert_eq!(QValue::parse("1.000").unwrap().thousandths(), 1000);
+        assert_eq!(QValue::parse("0.005").unwrap().thousandths(), 5);
+        assert_eq!(QValue::parse("0.").unwrap().thousandths(), 0);
+        assert!(QValue::parse("1.1").is_none());
+        assert!(QValue::parse("0.1234").is_none());
+        assert!(QValue::parse("2").is_none());
+        assert!(QValue::parse(".5").is_none());
+        assert!(QValue::parse("0,5").is_none());
+        assert!(QValue::parse("0.7") > QValue::parse("0.65"));
+        for q in ["0", "0.7", "0.05", "0.123", "1"].iter() {
+            assert_eq!(QValue::parse(q).unwrap().to_string(), *q);
+        }
+    }
+
+    #[test]
+    fn test_name_addr_parse() {
+        let (rest, value) = ContactValue::parse(
+            b"\"Mr. \\\"W\\\"\" <sip:watson@worcester.bell-telephone.com;transport=tcp>\
+            ;q=0.7; expires=3600;+sip.instance=\"<urn:uuid:0d9a>\"\r\n",
+        )
+        .unwrap();
+        assert_eq!(rest, b"\r\n");
+        let contact = match value {
+            ContactValue::NameAddr(contact) => contact,
+            ContactValue::Wildcard => panic!("Unexpected wildcard"),
+        };
+        assert_eq!(contact.display_name.as_deref(), Some("Mr. \"W\""));
+        assert_eq!(
+            contact.uri.sip().unwrap().hostport.host,
+            "worcester.bell-telephone.com"
+        );
+        assert_eq!(contact.q, QValue::from_thousandths(700));
+        assert_eq!(contact.expires, Some(3600));
+        assert_eq!(contact.tag, None);
+        assert_eq!(
+            contact.params().get(&Ascii::new("+sip.instance")),
+            Some(&Some("<urn:uuid:0d9a>"))
+        );
+
+        // Quoted parameter survives serialization
+        let mut written = contact.to_string();
+        assert!(written.ends_with(";+sip.instance=\"<urn:uuid:0d9a>\""));
+        written.push_str("\r\n");
+        let (_, reparsed) = NameAddr::parse(written.as_bytes()).unwrap();
+        assert_eq!(reparsed, contact);
+
+        let (_, value) = ContactValue::parse(b"*\r\n").unwrap();
+        assert_eq!(value, ContactValue::Wildcard);
+        assert_eq!(value.to_string(), "*");
+        assert!(NameAddr::parse(b"*\r\n").is_err());
+
+        let (_, to) = NameAddr::parse(b"sip:bob@biloxi.com;tag=a6c85cf\r\n").unwrap();
+        assert_eq!(to.display_name, None);
+        assert_eq!(to.tag, Some("a6c85cf"));
+        assert_eq!(to.to_string(), "<sip:bob@biloxi.com>;tag=a6c85cf");
+
+        let (_, to) = NameAddr::parse(b"Bob <tel:+1-201-555-0123>\r\n").unwrap();
+        assert_eq!(to.display_name.as_deref(), Some("Bob"));
+        assert_eq!(to.uri.tel().unwrap().number, "+1-201-555-0123");
+        assert_eq!(to.to_string(), "\"Bob\" <tel:+1-201-555-0123>");
+
+        assert!(NameAddr::parse(b"<sip:a@b.com>;q=2\r\n").is_err());
+        assert!(NameAddr::parse(b"<sip:a@b.com>;expires=-1\r\n").is_err());
+        assert!(NameAddr::parse(b"<sip:a@b.com>;tag\r\n").is_err());
+    }
+}
@@ -3,6 +3,7 @@ use crate::{
     headers::{
         header::{HeaderTagType, HeaderTags, HeaderValue, HeaderValueType},
         traits::SipHeaderParser,
+        Uri,
     },
 };
 
@@ -28,7 +29,7 @@ impl SipHeaderParser for AlertInfoParser {
             &source_input[..source_input.len() - input.len() - spaces_after_raquot.len()],
             HeaderValueType::AbsoluteURI,
             Some(tags),
-            None,
+            Uri::from_bracketed(uri),
         )?;
         Ok((input, hdr_val))
     }
@@ -50,6 +51,9 @@ mod tests {
             "http://www.example.com/sounds/moo.wav".as_bytes()
         );
         assert_eq!(val.vstr, "<http://www.example.com/sounds/moo.wav>");
+        let uri = val.uri().unwrap().absolute().unwrap();
+        assert_eq!(uri.scheme, "http");
+        assert_eq!(uri.hier_part, "//www.example.com/sounds/moo.wav");
 
         match AlertInfoParser::take_value("random word\r\n".as_bytes()) {
             Ok((_, _)) => {
@@ -1,6 +1,8 @@
 use crate::{
     common::{
-        bnfcore::is_alpha, errorparse::SipParseError, nom_wrappers::take_quoted_string,
+        bnfcore::{is_alpha, is_token_char},
+        errorparse::SipParseError,
+        nom_wrappers::take_quoted_string,
         take_sws_token,
     },
     headers::{
@@ -8,7 +10,7 @@ use crate::{
         traits::SipHeaderParser,
     },
 };
-use nom::bytes::complete::take_while;
+use nom::bytes::complete::{take_while, take_while1};
 
 pub struct AuthenticationInfoParser;
 
@@ -32,14 +34,21 @@ impl SipHeaderParser for AuthenticationInfoParser {
             return sip_parse_error!(1, "AuthentificatiionInfo value name is invalid");
         }
         let (input, (_, _, _)) = take_sws_token::equal(input)?;
-        let (input, (_, value, spaces_after_rdquot)) = take_quoted_string(input).unwrap();
+        // qop and nc values are tokens, others are quoted
+        let (input, value, spaces_after_rdquot) = if input.first() == Some(&b'"') {
+            let (input, (_, value, spaces_after_rdquot)) = take_quoted_string(input)?;
+            (input, value, spaces_after_rdquot.len())
+        } else {
+            let (input, value) = take_while1(is_token_char)(input)?;
+            (input, value, 0)
+        };
 
         let mut tags = HeaderTags::new();
         tags.insert(HeaderTagType::AinfoType, info_name);
         tags.insert(HeaderTagType::AinfoValue, value);
 
         let (_, hdr_val) = HeaderValue::new(
-            &source_input[..source_input.len() - input.len() - spaces_after_rdquot.len()],
+            &source_input[..source_input.len() - input.len() - spaces_after_rdquot],
             HeaderValueType::TokenValue,
             Some(tags),
             None,
@@ -67,5 +76,10 @@ mod test {
             val.tags().unwrap()[&HeaderTagType::AinfoValue],
             "47364c23432d2e131a5fb210812c".as_bytes()
         );
+
+        let (input, val) = AuthenticationInfoParser::take_value(b"nc=00000001, qop=auth").unwrap();
+        assert_eq!(input, b", qop=auth");
+        assert_eq!(val.vstr, "nc=00000001");
+        assert_eq!(val.tags().unwrap()[&HeaderTagType::AinfoValue], b"00000001");
     }
 }
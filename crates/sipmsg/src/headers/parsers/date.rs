@@ -24,6 +24,7 @@ use nom::{
 //                  / "Sep" / "Oct" / "Nov" / "Dec"
 
 /// Be careful. It is not a full validation of date.
+/// Calendar and day of week are checked by [`SipDate::parse`](crate::SipDate::parse)
 pub struct Date;
 
 impl Date {
@@ -110,5 +111,9 @@ mod test {
             Date::take_value("Sat, 13 Nov 2010 23:29:00 GMT \r\n".as_bytes()).unwrap();
         assert_eq!(val.vstr, "Sat, 13 Nov 2010 23:29:00 GMT");
         assert_eq!(input, b" \r\n");
+
+        // Only shape of date is checked
+        assert!(Date::take_value(b"Fri, 13 Nov 2010 23:29:60 GMT").is_ok());
+        assert!(Date::take_value(b"Sat, 13 Nov 10 23:29:00 GMT").is_err());
     }
 }
@@ -36,6 +36,8 @@ pub enum SipRFCHeader {
     MimeVersion,
     MinExpires,
     Organization,
+    /// [rfc3325](https://tools.ietf.org/html/rfc3325)
+    PAssertedIdentity,
     Priority,
     ProxyAuthenticate,
     ProxyAuthorization,
@@ -100,6 +102,7 @@ impl SipRFCHeader {
         match_str!("MIME-Version", SipRFCHeader::MimeVersion);
         match_str!("Min-Expires", SipRFCHeader::MinExpires);
         match_str!("Organization", SipRFCHeader::Organization);
+        match_str!("P-Asserted-Identity", SipRFCHeader::PAssertedIdentity);
         match_str!("Priority", SipRFCHeader::Priority);
         match_str!("Proxy-Authenticate", SipRFCHeader::ProxyAuthenticate);
         match_str!("Proxy-Authorization", SipRFCHeader::ProxyAuthorization);
@@ -153,6 +156,7 @@ impl SipRFCHeader {
             &SipRFCHeader::MimeVersion => "MIME-Version",
             &SipRFCHeader::MinExpires => "Min-Expires",
             &SipRFCHeader::Organization => "Organization",
+            &SipRFCHeader::PAssertedIdentity => "P-Asserted-Identity",
             &SipRFCHeader::Priority => "Priority",
             &SipRFCHeader::ProxyAuthenticate => "Proxy-Authenticate",
             &SipRFCHeader::ProxyAuthorization => "Proxy-Authorization",
@@ -201,6 +205,7 @@ impl SipRFCHeader {
             &SipRFCHeader::InReplyTo => CallID::take_value,
             &SipRFCHeader::MaxForwards => digit_header::take,
             &SipRFCHeader::Organization => utf8_trim_header::take,
+            &SipRFCHeader::PAssertedIdentity => From::take_value,
             &SipRFCHeader::Priority => token_header::take,
             &SipRFCHeader::ProxyAuthenticate => Authorization::take_value,
             &SipRFCHeader::ProxyAuthorization => Authorization::take_value,
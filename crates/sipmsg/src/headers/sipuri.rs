@@ -1,12 +1,18 @@
 use crate::{
-    common::bnfcore::is_unreserved, common::hostport::HostPort,
+    common::bnfcore::{is_alphanum, is_digit, is_hexdig, is_hnv_char, is_paramchar},
+    common::hostport::HostPort,
+    common::percent::{decode, unreserved_decoded_bytes},
+    common::{sip_method::SipMethod, transport::Transport},
     common::nom_wrappers::from_utf8_nom, common::nom_wrappers::take_while_with_escaped,
-    errorparse::SipParseError, headers::GenericParams, userinfo::UserInfo,
+    errorparse::{map_sip_err, SipParseError, SipParseErrorKind},
+    headers::GenericParams,
+    userinfo::UserInfo,
 };
-use alloc::collections::btree_map::BTreeMap;
+use alloc::{borrow::Cow, collections::btree_map::BTreeMap, string::String, vec::Vec};
 use nom::bytes::complete::{take, take_till, take_until};
 
-use core::str;
+use core::{fmt, str};
+use unicase::Ascii;
 
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum RequestUriScheme {
@@ -15,24 +21,23 @@ pub enum RequestUriScheme {
 }
 
 impl RequestUriScheme {
-    pub fn from_bytes(s: &[u8]) -> Result<RequestUriScheme, nom::Err<SipParseError>> {
-        match s {
-            b"sip" => Ok(Self::SIP),
-            b"sips" => Ok(Self::SIPS),
-            _ => sip_parse_error!(101, "Can't parse sipuri scheme"),
+    pub fn as_str(&self) -> &str {
+        match self {
+            RequestUriScheme::SIP => "sip",
+            RequestUriScheme::SIPS => "sips",
         }
     }
-}
-
-/// hnv-unreserved  =  "[" / "]" / "/" / "?" / ":" / "+" / "$"
-#[inline]
-fn is_hnv_unreserved_char(c: u8) -> bool {
-    c == b'[' || c == b']' || c == b'/' || c == b'?' || c == b':' || c == b'+' || c == b'$'
-}
 
-#[inline]
-fn is_hnv_char(c: u8) -> bool {
-    is_unreserved(c) || is_hnv_unreserved_char(c)
+    /// Scheme is case-insensitive
+    pub fn from_bytes(s: &[u8]) -> Result<RequestUriScheme, nom::Err<SipParseError>> {
+        if s.eq_ignore_ascii_case(b"sip") {
+            Ok(Self::SIP)
+        } else if s.eq_ignore_ascii_case(b"sips") {
+            Ok(Self::SIPS)
+        } else {
+            sip_parse_error!(101, "Can't parse sipuri scheme")
+        }
+    }
 }
 
 // header          =  hname "=" hvalue
@@ -109,8 +114,7 @@ pub struct SipUri<'a> {
     pub scheme: RequestUriScheme,
     user_info: Option<UserInfo<'a>>,
     pub hostport: HostPort<'a>,
-    // Temporary use parsing from generic-parameters.rs
-    // TODO make according RFC
+    /// Values are kept escaped, typed accessors check them on access
     parameters: Option<GenericParams<'a>>,
     headers: Option<BTreeMap<&'a str, &'a str>>,
 }
@@ -128,20 +132,124 @@ impl<'a> SipUri<'a> {
         self.headers.as_ref()
     }
 
+    /// Value of parameter with escaped characters decoded.
+    /// Returns `Some(None)` if parameter has no value
+    pub fn decoded_param(&self, name: &str) -> Option<Option<Cow<'a, str>>> {
+        param_value(self.parameters.as_ref(), name).map(|value| value.map(decode))
+    }
+
+    /// Value of header with escaped characters decoded.
+    /// Name is compared case-insensitively after decoding
+    pub fn decoded_header(&self, name: &str) -> Option<Cow<'a, str>> {
+        self.headers
+            .as_ref()?
+            .iter()
+            .find(|(hname, _)| decode(hname).eq_ignore_ascii_case(name))
+            .map(|(_, value)| decode(value))
+    }
+
+    fn param(&self, name: &str) -> Option<&'a str> {
+        param_value(self.parameters.as_ref(), name).flatten()
+    }
+
+    /// `transport` parameter
+    pub fn transport(&self) -> Option<Transport<'a>> {
+        self.param("transport").and_then(Transport::from_str)
+    }
+
+    /// `user` parameter: "phone", "ip", "dialstring" or other token
+    pub fn user_param(&self) -> Option<&'a str> {
+        self.param("user")
+    }
+
+    /// `method` parameter
+    pub fn method_param(&self) -> Option<SipMethod<'a>> {
+        self.param("method").and_then(SipMethod::from_str)
+    }
+
+    /// `ttl` parameter. `None` if value is not 1*3DIGIT in 0-255 range
+    pub fn ttl(&self) -> Option<u8> {
+        self.param("ttl")
+            .filter(|ttl| ttl.len() <= 3 && ttl.bytes().all(is_digit))
+            .and_then(|ttl| ttl.parse().ok())
+    }
+
+    /// `maddr` parameter. IPv6 address is enclosed in `[]`.
+    /// `None` if value is not a valid host
+    pub fn maddr(&self) -> Option<&'a str> {
+        self.param("maddr").filter(|maddr| is_valid_host(&decode(maddr)))
+    }
+
+    /// `lr` parameter of loose router
+    pub fn lr(&self) -> bool {
+        param_value(self.parameters.as_ref(), "lr").is_some()
+    }
+
+    /// `gr` parameter of GRUU ([rfc5627](https://tools.ietf.org/html/rfc5627)).
+    /// Returns `Some(None)` if parameter has no value
+    pub fn gr(&self) -> Option<Option<&'a str>> {
+        param_value(self.parameters.as_ref(), "gr")
+    }
+
+    /// `ob` parameter of outbound proxy ([rfc5626](https://tools.ietf.org/html/rfc5626))
+    pub fn ob(&self) -> bool {
+        param_value(self.parameters.as_ref(), "ob").is_some()
+    }
+
+    /// `comp` parameter ([rfc3486](https://tools.ietf.org/html/rfc3486)), e.g. "sigcomp"
+    pub fn comp(&self) -> Option<&'a str> {
+        self.param("comp")
+    }
+
+    /// Parameters that have no typed accessor
+    pub fn other_params(&self) -> impl Iterator<Item = (&'a str, Option<&'a str>)> + '_ {
+        self.parameters
+            .iter()
+            .flat_map(|p| p.iter())
+            .filter(|(name, _)| !KNOWN_PARAMS.iter().any(|p| name.eq_ignore_ascii_case(p)))
+            .map(|(name, value)| (name.into_inner(), *value))
+    }
+
+    // uri-parameters  =  *( ";" uri-parameter )
+    // uri-parameter   =  pname [ "=" pvalue ]
+    // pname           =  1*paramchar
+    // pvalue          =  1*paramchar
+    fn take_params(
+        mut input: &'a [u8],
+    ) -> nom::IResult<&'a [u8], GenericParams<'a>, SipParseError<'a>> {
+        let mut params = BTreeMap::new();
+        while input.starts_with(b";") {
+            let (rest, name) = take_while_with_escaped(&input[1..], is_paramchar)?;
+            if name.is_empty() {
+                return sip_parse_error!(1, "Empty URI parameter name");
+            }
+            let (rest, value) = if rest.starts_with(b"=") {
+                let (rest, value) = take_while_with_escaped(&rest[1..], is_paramchar)?;
+                if value.is_empty() {
+                    return sip_parse_error!(2, "Empty URI parameter value");
+                }
+                (rest, Some(from_utf8_nom(value)?.1))
+            } else {
+                (rest, None)
+            };
+            let (_, name) = from_utf8_nom(name)?;
+            // The first occurrence of duplicated parameter is kept
+            params.entry(Ascii::new(name)).or_insert(value);
+            input = rest;
+        }
+        Ok((input, GenericParams::from_map(params)))
+    }
+
     fn try_parse_params(
         input: &'a [u8],
-    ) -> nom::IResult<&[u8], Option<GenericParams<'a>>, SipParseError> {
+    ) -> nom::IResult<&'a [u8], Option<GenericParams<'a>>, SipParseError<'a>> {
         if input[0] != b';' {
             return Ok((input, None));
         }
-        match GenericParams::parse(input) {
-            Ok((input, params)) => {
-                return Ok((input, Some(params)));
-            }
-            Err(e) => {
-                return Err(e);
-            }
-        }
+        let (input, params) = SipUri::take_params(input).map_err(|e| {
+            map_sip_err(e, |e| e.with_kind(SipParseErrorKind::Parameter, input))
+        })?;
+        Ok((input, Some(params)))
     }
 
     fn try_parse_headers(
@@ -160,11 +268,19 @@ impl<'a> SipUri<'a> {
         }
     }
 
-    // This function written not well. So, if you want, you can refactor this function
     pub fn parse_ext(
         input: &'a [u8],
         parse_with_parameters: bool,
     ) -> nom::IResult<&[u8], SipUri<'a>, SipParseError> {
+        SipUri::take_uri(input, parse_with_parameters)
+            .map_err(|e| map_sip_err(e, |e| e.with_kind(SipParseErrorKind::Uri, input)))
+    }
+
+    // This function written not well. So, if you want, you can refactor this function
+    fn take_uri(
+        input: &'a [u8],
+        parse_with_parameters: bool,
+    ) -> nom::IResult<&'a [u8], SipUri<'a>, SipParseError<'a>> {
         let (input, uri_scheme) = take_until(":")(input)?;
         let (input_after_scheme, _) = take(1usize)(input)?; // skip ':'
         let scheme = RequestUriScheme::from_bytes(uri_scheme)?;
@@ -236,6 +352,226 @@ impl<'a> SipUri<'a> {
     pub fn parse(input: &'a [u8]) -> nom::IResult<&[u8], SipUri<'a>, SipParseError> {
         SipUri::parse_ext(input, true)
     }
+
+    /// Compares URIs according to
+    /// [rfc3261 section-19.1.4](https://tools.ietf.org/html/rfc3261#section-19.1.4):
+    /// - sip and sips URIs are never equivalent;
+    /// - user and password are case-sensitive, host and other components are not;
+    /// - escaped unreserved characters are equivalent to unescaped ones,
+    ///   escaped reserved characters are not;
+    /// - `user`, `ttl`, `method`, `maddr` and `transport` parameters must match
+    ///   if present in either URI, other parameters are compared only if present in both;
+    /// - headers must be present in both URIs and match,
+    ///   names are case-insensitive and values are case-sensitive.
+    ///
+    /// ```rust
+    /// use sipmsg::SipUri;
+    ///
+    /// let (_, a) = SipUri::parse(b"sip:%61lice@AtLanTa.com;Transport=UDP").unwrap();
+    /// let (_, b) = SipUri::parse(b"sip:alice@atlanta.com;transport=udp").unwrap();
+    /// assert!(a.equivalent(&b));
+    /// assert_eq!(a.hash_key(), b.hash_key());
+    ///
+    /// let (_, c) = SipUri::parse(b"sip:ALICE@atlanta.com;transport=udp").unwrap();
+    /// assert!(!a.equivalent(&c));
+    /// ```
+    pub fn equivalent(&self, other: &SipUri) -> bool {
+        if self.scheme != other.scheme
+            || !self.hostport.host.eq_ignore_ascii_case(other.hostport.host)
+            || self.hostport.port != other.hostport.port
+        {
+            return false;
+        }
+        let (user, other_user) = (self.user_info.as_ref(), other.user_info.as_ref());
+        if !eq_optional(user.map(|u| u.value), other_user.map(|u| u.value), false)
+            || !eq_optional(
+                user.and_then(|u| u.password),
+                other_user.and_then(|u| u.password),
+                false,
+            )
+        {
+            return false;
+        }
+        if !self.params_equivalent(other) {
+            return false;
+        }
+        match (&self.headers, &other.headers) {
+            (Some(headers), Some(other_headers)) => {
+                headers.len() == other_headers.len()
+                    && headers.iter().all(|(name, value)| {
+                        other_headers.iter().any(|(other_name, other_value)| {
+                            eq_escaped(name, other_name, true)
+                                && eq_escaped(value, other_value, false)
+                        })
+                    })
+            }
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    fn params_equivalent(&self, other: &SipUri) -> bool {
+        let params = self.parameters.iter().flat_map(|p| p.iter());
+        for (name, value) in params {
+            match param_value(other.parameters.as_ref(), name) {
+                Some(other_value) if !eq_optional(*value, other_value, true) => return false,
+                None if is_significant_param(name) => return false,
+                _ => {}
+            }
+        }
+        let mut other_params = other.parameters.iter().flat_map(|p| p.iter());
+        other_params.all(|(name, _)| {
+            !is_significant_param(name) || param_value(self.parameters.as_ref(), name).is_some()
+        })
+    }
+
+    /// Normalised form of the URI that can be used as a key of hash map.
+    /// Equivalent URIs have equal keys, but equal keys don't guarantee
+    /// equivalence because parameters present in one URI only are not included.
+    pub fn hash_key(&self) -> String {
+        let mut key = String::new();
+        key.push_str(self.scheme.as_str());
+        key.push(':');
+        if let Some(user_info) = &self.user_info {
+            push_unescaped(&mut key, user_info.value, false);
+            if let Some(password) = user_info.password {
+                key.push(':');
+                push_unescaped(&mut key, password, false);
+            }
+            key.push('@');
+        }
+        push_unescaped(&mut key, self.hostport.host, true);
+        if let Some(port) = self.hostport.port {
+            key.push_str(&alloc::format!(":{}", port));
+        }
+        if let Some(params) = &self.parameters {
+            for (name, value) in params.iter().filter(|(k, _)| is_significant_param(k)) {
+                key.push(';');
+                push_unescaped(&mut key, name, true);
+                if let Some(value) = value {
+                    key.push('=');
+                    push_unescaped(&mut key, value, true);
+                }
+            }
+        }
+        if let Some(headers) = &self.headers {
+            let mut normalized: Vec<String> = headers
+                .iter()
+                .map(|(name, value)| {
+                    let mut header = String::new();
+                    push_unescaped(&mut header, name, true);
+                    header.push('=');
+                    push_unescaped(&mut header, value, false);
+                    header
+                })
+                .collect();
+            normalized.sort();
+            for (idx, header) in normalized.iter().enumerate() {
+                key.push(if idx == 0 { '?' } else { '&' });
+                key.push_str(header);
+            }
+        }
+        key
+    }
+}
+
+fn param_value<'p>(
+    params: Option<&GenericParams<'p>>,
+    name: &str,
+) -> Option<Option<&'p str>> {
+    params?
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, value)| *value)
+}
+
+/// Parameters that have typed accessors
+static KNOWN_PARAMS: &[&str] = &[
+    "transport", "user", "method", "ttl", "maddr", "lr", "gr", "ob", "comp",
+];
+
+/// host of `maddr` parameter: hostname, IPv4address or IPv6reference
+fn is_valid_host(host: &str) -> bool {
+    let bytes = host.as_bytes();
+    if bytes.is_empty() {
+        return false;
+    }
+    if bytes[0] == b'[' {
+        let ipv6 = &bytes[1..];
+        return ipv6.len() > 2
+            && ipv6.ends_with(b"]")
+            && ipv6[..ipv6.len() - 1]
+                .iter()
+                .all(|c| is_hexdig(*c) || *c == b':' || *c == b'.');
+    }
+    is_alphanum(bytes[0]) && bytes.iter().all(|c| is_alphanum(*c) || *c == b'-' || *c == b'.')
+}
+
+/// Parameters that must match if present in either URI
+/// ([rfc3261 section-19.1.4](https://tools.ietf.org/html/rfc3261#section-19.1.4))
+fn is_significant_param(name: &str) -> bool {
+    ["user", "ttl", "method", "maddr", "transport"]
+        .iter()
+        .any(|p| p.eq_ignore_ascii_case(name))
+}
+
+/// Compares strings with escaped characters decoded
+fn eq_escaped(a: &str, b: &str, ignore_case: bool) -> bool {
+    if ignore_case {
+        unreserved_decoded_bytes(a)
+            .map(|(c, escaped)| (c.to_ascii_lowercase(), escaped))
+            .eq(unreserved_decoded_bytes(b).map(|(c, escaped)| (c.to_ascii_lowercase(), escaped)))
+    } else {
+        unreserved_decoded_bytes(a).eq(unreserved_decoded_bytes(b))
+    }
+}
+
+fn eq_optional(a: Option<&str>, b: Option<&str>, ignore_case: bool) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => eq_escaped(a, b, ignore_case),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Appends string with escaped unreserved characters decoded to the key.
+/// Other escaped characters and non-ASCII bytes are written as `%XX`
+/// so that the key is unambiguous.
+fn push_unescaped(key: &mut String, s: &str, lowercase: bool) {
+    for (c, escaped) in unreserved_decoded_bytes(s) {
+        let c = if lowercase { c.to_ascii_lowercase() } else { c };
+        if escaped || !c.is_ascii() {
+            key.push_str(&alloc::format!("%{:02X}", c));
+        } else {
+            key.push(char::from(c));
+        }
+    }
+}
+
+impl<'a> fmt::Display for SipUri<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:", self.scheme.as_str())?;
+        if let Some(user_info) = &self.user_info {
+            write!(f, "{}@", user_info)?;
+        }
+        write!(f, "{}", self.hostport)?;
+        if let Some(params) = &self.parameters {
+            for (name, value) in params.iter() {
+                match value {
+                    Some(value) => write!(f, ";{}={}", name, value)?,
+                    None => write!(f, ";{}", name)?,
+                }
+            }
+        }
+        if let Some(headers) = &self.headers {
+            let mut delimiter = '?';
+            for (name, value) in headers.iter() {
+                write!(f, "{}{}={}", delimiter, name, value)?;
+                delimiter = '&';
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -364,4 +700,149 @@ mod tests {
 
         assert_eq!(rest, b" ;transport=tcp");
     }
+
+    #[test]
+    fn test_sip_uri_display() {
+        for uri in [
+            "sip:atlanta.com",
+            "sips:alice:secretword@atlanta.com:5061;transport=tcp",
+            "sip:+1-212-555-1212:1234@gateway.com;user=phone",
+            "sip:alice@[2001:db8::10]:8080;lr",
+            "sip:atlanta.com;method=REGISTER?to=alice%40atlanta.com",
+        ]
+        .iter()
+        {
+            let (_, sip_uri) = SipUri::parse(uri.as_bytes()).unwrap();
+            assert_eq!(alloc::format!("{}", sip_uri), *uri);
+        }
+    }
+
+    #[test]
+    fn test_sip_uri_decoded() {
+        let (_, uri) = SipUri::parse(
+            b"sip:%61lice%20s:p%40ss@atlanta.com;maddr=%31.2.3.4;lr?Subject=project%20x",
+        )
+        .unwrap();
+        let user_info = uri.user_info().unwrap();
+        assert_eq!(user_info.value, "%61lice%20s");
+        assert_eq!(user_info.decoded_value(), "alice s");
+        assert_eq!(user_info.decoded_password().unwrap(), "p@ss");
+        assert_eq!(uri.decoded_param("MADDR"), Some(Some(Cow::from("1.2.3.4"))));
+        assert_eq!(uri.decoded_param("lr"), Some(None));
+        assert_eq!(uri.decoded_param("ttl"), None);
+        assert_eq!(uri.decoded_header("subject").unwrap(), "project x");
+        assert_eq!(uri.decoded_header("priority"), None);
+    }
+
+    #[test]
+    fn test_sip_uri_typed_params() {
+        let (_, uri) = SipUri::parse(
+            b"sip:alice@atlanta.com;transport=TLS;user=phone;method=REGISTER;ttl=15;\
+            maddr=239.255.255.1;lr;gr=urn:uuid:f81d4fae;ob;comp=sigcomp;foo=bar;baz",
+        )
+        .unwrap();
+        assert_eq!(uri.transport(), Some(Transport::TLS));
+        assert_eq!(uri.user_param(), Some("phone"));
+        assert_eq!(uri.method_param(), Some(SipMethod::REGISTER));
+        assert_eq!(uri.ttl(), Some(15));
+        assert_eq!(uri.maddr(), Some("239.255.255.1"));
+        assert!(uri.lr());
+        assert_eq!(uri.gr(), Some(Some("urn:uuid:f81d4fae")));
+        assert!(uri.ob());
+        assert_eq!(uri.comp(), Some("sigcomp"));
+        let other: Vec<_> = uri.other_params().collect();
+        assert_eq!(other, [("baz", None), ("foo", Some("bar"))]);
+
+        let (_, uri) = SipUri::parse(b"sip:atlanta.com;maddr=[2001:db8::1];gr").unwrap();
+        assert_eq!(uri.maddr(), Some("[2001:db8::1]"));
+        assert_eq!(uri.gr(), Some(None));
+        assert_eq!(uri.transport(), None);
+        assert!(!uri.lr());
+        assert!(!uri.ob());
+
+        for uri in [
+            "sip:atlanta.com;ttl=256",
+            "sip:atlanta.com;ttl=-1",
+            "sip:atlanta.com;ttl",
+            "sip:atlanta.com;transport",
+            "sip:atlanta.com;maddr=-host",
+            "sip:atlanta.com;maddr",
+            "sip:atlanta.com;user",
+        ]
+        .iter()
+        {
+            // Invalid values of known parameters don't fail parsing
+            let (_, uri) = SipUri::parse(uri.as_bytes()).unwrap();
+            assert_eq!(uri.ttl(), None, "{}", uri);
+            assert_eq!(uri.transport(), None, "{}", uri);
+            assert_eq!(uri.method_param(), None, "{}", uri);
+            assert_eq!(uri.maddr(), None, "{}", uri);
+            assert_eq!(uri.user_param(), None, "{}", uri);
+        }
+
+        let (_, uri) = SipUri::parse(b"sip:atlanta.com;ttl=+5;transport=udp;Transport=tcp").unwrap();
+        assert_eq!(uri.ttl(), None);
+        assert_eq!(uri.transport(), Some(Transport::UDP));
+
+        for uri in ["sip:atlanta.com;transport=\"a b\"", "sip:atlanta.com;method="].iter() {
+            match SipUri::parse(uri.as_bytes()).unwrap_err() {
+                nom::Err::Error(e) => assert_eq!(e.kind(), SipParseErrorKind::Parameter),
+                _ => panic!("unexpected error for {}", uri),
+            }
+        }
+    }
+
+    #[test]
+    fn test_sip_uri_equivalent() {
+        // rfc3261 section-19.1.4 examples
+        let equivalent = [
+            ("sip:%61lice@atlanta.com;transport=TCP", "sip:alice@AtLanTa.CoM;Transport=tcp"),
+            ("sip:carol@chicago.com", "sip:carol@chicago.com;newparam=5"),
+            ("sip:carol@chicago.com", "sip:carol@chicago.com;security=on"),
+            (
+                "sip:biloxi.com;transport=tcp;method=REGISTER?to=sip:bob%40biloxi.com",
+                "sip:biloxi.com;method=REGISTER;transport=tcp?to=sip:bob%40biloxi.com",
+            ),
+            (
+                "sip:alice@atlanta.com?subject=project%20x&priority=urgent",
+                "sip:alice@atlanta.com?priority=urgent&subject=project%20x",
+            ),
+            ("sip:h?subject=%70roject%2Fx", "sip:h?subject=project%2fx"),
+            ("sip:h?Subject=x", "sip:h?subject=x"),
+        ];
+        for (a, b) in equivalent.iter() {
+            let (_, a) = SipUri::parse(a.as_bytes()).unwrap();
+            let (_, b) = SipUri::parse(b.as_bytes()).unwrap();
+            assert!(a.equivalent(&b));
+            assert!(b.equivalent(&a));
+            assert_eq!(a.hash_key(), b.hash_key());
+        }
+
+        let not_equivalent = [
+            ("SIP:ALICE@AtLanTa.CoM;Transport=udp", "sip:alice@AtLanTa.CoM;Transport=UDP"),
+            ("sip:bob@biloxi.com", "sip:bob@biloxi.com:5060"),
+            ("sip:bob@biloxi.com", "sip:bob@biloxi.com;transport=udp"),
+            ("sip:bob@biloxi.com", "sips:bob@biloxi.com"),
+            ("sip:bob@biloxi.com;newparam=5", "sip:bob@biloxi.com;newparam=6"),
+            (
+                "sip:carol@chicago.com;newparam=5",
+                "sip:carol@chicago.com;newparam=6;security=on",
+            ),
+            ("sip:carol@chicago.com", "sip:carol@chicago.com?Subject=next%20meeting"),
+            ("sip:a%3Bb@h", "sip:a;b@h"),
+            ("sip:h;maddr=a%3Ab", "sip:h;maddr=a:b"),
+            ("sip:h?subject=a%2fb", "sip:h?subject=a/b"),
+            ("sip:h?subject=Project", "sip:h?subject=project"),
+            (
+                "sip:carol@chicago.com?Subject=next%20meeting",
+                "sip:carol@chicago.com?Subject=another%20meeting",
+            ),
+        ];
+        for (a, b) in not_equivalent.iter() {
+            let (_, a) = SipUri::parse(a.as_bytes()).unwrap();
+            let (_, b) = SipUri::parse(b.as_bytes()).unwrap();
+            assert!(!a.equivalent(&b));
+            assert!(!b.equivalent(&a));
+        }
+    }
 }
@@ -0,0 +1,231 @@
+use crate::common::{
+    bnfcore::{is_alphanum, is_digit, is_hexdig, is_paramchar},
+    errorparse::{map_sip_err, SipParseError, SipParseErrorKind},
+    nom_wrappers::{from_utf8_nom, take_while_with_escaped},
+};
+use alloc::collections::btree_map::BTreeMap;
+use core::fmt;
+use nom::bytes::complete::{tag_no_case, take_while, take_while1};
+use unicase::Ascii;
+
+/// visual-separator  =  "-" / "." / "(" / ")"
+#[inline]
+fn is_visual_separator(c: u8) -> bool {
+    c == b'-' || c == b'.' || c == b'(' || c == b')'
+}
+
+/// phonedigit  =  DIGIT / [ visual-separator ]
+#[inline]
+fn is_phonedigit(c: u8) -> bool {
+    is_digit(c) || is_visual_separator(c)
+}
+
+/// phonedigit-hex  =  HEXDIG / "*" / "#" / [ visual-separator ]
+#[inline]
+fn is_phonedigit_hex(c: u8) -> bool {
+    is_hexdig(c) || c == b'*' || c == b'#' || is_visual_separator(c)
+}
+
+/// pname  =  1*( alphanum / "-" )
+#[inline]
+fn is_pname_char(c: u8) -> bool {
+    is_alphanum(c) || c == b'-'
+}
+
+// telephone-uri        = "tel:" telephone-subscriber
+// telephone-subscriber = global-number / local-number
+// global-number        = global-number-digits *par
+// local-number         = local-number-digits *par context *par
+// par                  = parameter / extension / isdn-subaddress
+/// [rfc3966](https://tools.ietf.org/html/rfc3966) telephone number URI.
+/// Ex: `tel:+1-201-555-0123`, `tel:7042;phone-context=example.com`
+#[derive(PartialEq, Debug)]
+pub struct TelUri<'a> {
+    /// Number as it is written, with visual separators.
+    /// Global number starts with `+`
+    pub number: &'a str,
+    phone_context: Option<&'a str>,
+    extension: Option<&'a str>,
+    isdn_subaddress: Option<&'a str>,
+    /// Parameters other than `phone-context`, `ext` and `isub`
+    parameters: BTreeMap<Ascii<&'a str>, Option<&'a str>>,
+}
+
+impl<'a> TelUri<'a> {
+    /// Number in E.164 form that starts with `+`
+    pub fn is_global(&self) -> bool {
+        self.number.starts_with('+')
+    }
+
+    /// `phone-context` parameter. It is always present for local numbers
+    pub fn phone_context(&self) -> Option<&'a str> {
+        self.phone_context
+    }
+
+    /// `ext` parameter
+    pub fn extension(&self) -> Option<&'a str> {
+        self.extension
+    }
+
+    /// `isub` parameter
+    pub fn isdn_subaddress(&self) -> Option<&'a str> {
+        self.isdn_subaddress
+    }
+
+    /// Other parameters
+    pub fn params(&self) -> &BTreeMap<Ascii<&'a str>, Option<&'a str>> {
+        &self.parameters
+    }
+
+    fn take_number(input: &'a [u8]) -> nom::IResult<&'a [u8], &'a [u8], SipParseError<'a>> {
+        let (rest, number) = if input.starts_with(b"+") {
+            let (rest, digits) = take_while(is_phonedigit)(&input[1..])?;
+            if !digits.iter().any(|c| is_digit(*c)) {
+                return sip_parse_error!(1, "Global number must contain digits");
+            }
+            (rest, &input[..digits.len() + 1])
+        } else {
+            let (rest, digits) = take_while1(is_phonedigit_hex)(input)?;
+            if digits.iter().all(|c| is_visual_separator(*c)) {
+                return sip_parse_error!(2, "Local number must contain digits");
+            }
+            (rest, digits)
+        };
+        Ok((rest, number))
+    }
+
+    /// parameter  =  ";" pname ["=" pvalue ]
+    fn take_param(
+        input: &'a [u8],
+    ) -> nom::IResult<&'a [u8], (&'a str, Option<&'a str>), SipParseError<'a>> {
+        let (input, name) = take_while1(is_pname_char)(&input[1..])?;
+        let (_, name) = from_utf8_nom(name)?;
+        if !input.starts_with(b"=") {
+            return Ok((input, (name, None)));
+        }
+        let (input, value) = take_while_with_escaped(&input[1..], is_paramchar)?;
+        if value.is_empty() {
+            return sip_parse_error!(3, "Empty value of tel parameter");
+        }
+        let (_, value) = from_utf8_nom(value)?;
+        Ok((input, (name, Some(value))))
+    }
+
+    /// If `parse_with_parameters` is false parameters are not parsed:
+    /// they belong to the header when URI is not enclosed in `<>`
+    pub fn parse_ext(
+        input: &'a [u8],
+        parse_with_parameters: bool,
+    ) -> nom::IResult<&'a [u8], TelUri<'a>, SipParseError<'a>> {
+        TelUri::take_uri(input, parse_with_parameters)
+            .map_err(|e| map_sip_err(e, |e| e.with_kind(SipParseErrorKind::Uri, input)))
+    }
+
+    fn take_uri(
+        input: &'a [u8],
+        parse_with_parameters: bool,
+    ) -> nom::IResult<&'a [u8], TelUri<'a>, SipParseError<'a>> {
+        let (input, _) = tag_no_case("tel:")(input)?;
+        let (mut input, number) = TelUri::take_number(input)?;
+        let (_, number) = from_utf8_nom(number)?;
+        let mut uri = TelUri {
+            number,
+            phone_context: None,
+            extension: None,
+            isdn_subaddress: None,
+            parameters: BTreeMap::new(),
+        };
+        while parse_with_parameters && input.starts_with(b";") {
+            let (rest, (name, value)) = TelUri::take_param(input)?;
+            let name = Ascii::new(name);
+            if name == "phone-context" {
+                uri.phone_context = value;
+            } else if name == "ext" {
+                uri.extension = value;
+            } else if name == "isub" {
+                uri.isdn_subaddress = value;
+            } else {
+                uri.parameters.insert(name, value);
+            }
+            input = rest;
+        }
+        if parse_with_parameters && !uri.is_global() && uri.phone_context.is_none() {
+            return sip_parse_error!(4, "Local number requires phone-context parameter");
+        }
+        Ok((input, uri))
+    }
+
+    pub fn parse(input: &'a [u8]) -> nom::IResult<&'a [u8], TelUri<'a>, SipParseError<'a>> {
+        TelUri::parse_ext(input, true)
+    }
+}
+
+impl<'a> fmt::Display for TelUri<'a> {
+    /// Parameters are written in order recommended by
+    /// [rfc3966 section-5.1.5](https://tools.ietf.org/html/rfc3966#section-5.1.5)
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "tel:{}", self.number)?;
+        if let Some(isub) = self.isdn_subaddress {
+            write!(f, ";isub={}", isub)?;
+        }
+        if let Some(ext) = self.extension {
+            write!(f, ";ext={}", ext)?;
+        }
+        if let Some(context) = self.phone_context {
+            write!(f, ";phone-context={}", context)?;
+        }
+        for (name, value) in self.parameters.iter() {
+            match value {
+                Some(value) => write!(f, ";{}={}", name, value)?,
+                None => write!(f, ";{}", name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tel_uri_parse() {
+        let (rest, uri) = TelUri::parse(b"tel:+1-201-555-0123>").unwrap();
+        assert_eq!(rest, b">");
+        assert_eq!(uri.number, "+1-201-555-0123");
+        assert!(uri.is_global());
+        assert_eq!(uri.phone_context(), None);
+
+        let (rest, uri) =
+            TelUri::parse(b"tel:7042;phone-context=example.com;ext=22;isub=%31a;tsp=x SIP/2.0")
+                .unwrap();
+        assert_eq!(rest, b" SIP/2.0");
+        assert_eq!(uri.number, "7042");
+        assert!(!uri.is_global());
+        assert_eq!(uri.phone_context(), Some("example.com"));
+        assert_eq!(uri.extension(), Some("22"));
+        assert_eq!(uri.isdn_subaddress(), Some("%31a"));
+        assert_eq!(uri.params().get(&Ascii::new("TSP")), Some(&Some("x")));
+
+        let (rest, uri) = TelUri::parse_ext(b"TEL:+33(0)1.23;ext=1", false).unwrap();
+        assert_eq!(rest, b";ext=1");
+        assert_eq!(uri.number, "+33(0)1.23");
+        assert_eq!(uri.extension(), None);
+
+        assert!(TelUri::parse(b"tel:7042").is_err());
+        assert!(TelUri::parse(b"tel:+").is_err());
+        assert!(TelUri::parse(b"tel:--").is_err());
+        assert!(TelUri::parse(b"tel:+1;ext=").is_err());
+        assert!(TelUri::parse(b"sip:+1@example.com").is_err());
+    }
+
+    #[test]
+    fn test_tel_uri_display() {
+        let (_, uri) =
+            TelUri::parse(b"tel:863-1234;tsp=a;phone-context=+1-914-555;isub=1;ext=2").unwrap();
+        assert_eq!(
+            alloc::format!("{}", uri),
+            "tel:863-1234;isub=1;ext=2;phone-context=+1-914-555;tsp=a"
+        );
+    }
+}
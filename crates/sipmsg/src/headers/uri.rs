@@ -0,0 +1,261 @@
+use crate::{
+    common::{
+        bnfcore::{is_alpha, is_alphanum, is_reserved, is_unreserved},
+        errorparse::{map_sip_err, SipParseError, SipParseErrorKind},
+        nom_wrappers::{from_utf8_nom, take_while_with_escaped},
+    },
+    headers::{sipuri::RequestUriScheme, teluri::TelUri, SipUri},
+};
+use core::fmt;
+use nom::bytes::complete::{tag, take_while1};
+
+/// scheme  =  ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )
+#[inline]
+fn is_scheme_char(c: u8) -> bool {
+    is_alphanum(c) || c == b'+' || c == b'-' || c == b'.'
+}
+
+/// uric  =  reserved / unreserved / escaped.
+/// "[" and "]" are allowed for IPv6 references
+/// ([rfc2732](https://tools.ietf.org/html/rfc2732))
+#[inline]
+fn is_uric(c: u8) -> bool {
+    is_reserved(c) || is_unreserved(c) || c == b'[' || c == b']'
+}
+
+// absoluteURI    =  scheme ":" ( hier-part / opaque-part )
+// hier-part      =  ( net-path / abs-path ) [ "?" query ]
+/// URI of scheme other than sip, sips and tel.
+/// Ex: `mailto:watson@bell-telephone.com`, `http://www.example.com/alice/photo.jpg`
+#[derive(PartialEq, Debug)]
+pub struct AbsoluteUri<'a> {
+    pub scheme: &'a str,
+    /// Part between `:` and `?` (hier-part without query or opaque-part)
+    pub hier_part: &'a str,
+    /// Part after `?`
+    pub query: Option<&'a str>,
+}
+
+impl<'a> AbsoluteUri<'a> {
+    fn take_uri(input: &'a [u8]) -> nom::IResult<&'a [u8], AbsoluteUri<'a>, SipParseError<'a>> {
+        if input.is_empty() || !is_alpha(input[0]) {
+            return sip_parse_error!(1, "URI scheme must start with a letter");
+        }
+        let (input, scheme) = take_while1(is_scheme_char)(input)?;
+        let (input, _) = tag(":")(input)?;
+        let (rest, uri) = take_while_with_escaped(input, is_uric)?;
+        let (hier_part, query) = match uri.iter().position(|c| *c == b'?') {
+            Some(idx) => (&uri[..idx], Some(&uri[idx + 1..])),
+            None => (uri, None),
+        };
+        if hier_part.is_empty() {
+            return sip_parse_error!(2, "Empty absolute URI");
+        }
+        let (_, scheme) = from_utf8_nom(scheme)?;
+        let (_, hier_part) = from_utf8_nom(hier_part)?;
+        let query = match query {
+            Some(query) => Some(from_utf8_nom(query)?.1),
+            None => None,
+        };
+        Ok((
+            rest,
+            AbsoluteUri {
+                scheme,
+                hier_part,
+                query,
+            },
+        ))
+    }
+
+    pub fn parse(input: &'a [u8]) -> nom::IResult<&'a [u8], AbsoluteUri<'a>, SipParseError<'a>> {
+        AbsoluteUri::take_uri(input)
+            .map_err(|e| map_sip_err(e, |e| e.with_kind(SipParseErrorKind::Uri, input)))
+    }
+}
+
+impl<'a> fmt::Display for AbsoluteUri<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.scheme, self.hier_part)?;
+        if let Some(query) = self.query {
+            write!(f, "?{}", query)?;
+        }
+        Ok(())
+    }
+}
+
+/// Any URI that may appear in Request-URI or in headers
+/// (To, From, Contact, P-Asserted-Identity, Call-Info and others)
+#[derive(PartialEq, Debug)]
+pub enum Uri<'a> {
+    Sip(SipUri<'a>),
+    Sips(SipUri<'a>),
+    Tel(TelUri<'a>),
+    Absolute(AbsoluteUri<'a>),
+}
+
+/// Scheme of URI if input starts with `scheme:`
+fn take_scheme(input: &[u8]) -> Option<&[u8]> {
+    let scheme_len = input.iter().position(|c| !is_scheme_char(*c))?;
+    if input[scheme_len] == b':' {
+        Some(&input[..scheme_len])
+    } else {
+        None
+    }
+}
+
+impl<'a> Uri<'a> {
+    /// Returns true if input starts with sip, sips or tel scheme
+    pub(crate) fn is_uri_start(input: &[u8]) -> bool {
+        match take_scheme(input) {
+            Some(scheme) => {
+                scheme.eq_ignore_ascii_case(b"sip")
+                    || scheme.eq_ignore_ascii_case(b"sips")
+                    || scheme.eq_ignore_ascii_case(b"tel")
+            }
+            None => false,
+        }
+    }
+
+    /// Parses URI enclosed in `<>`. Returns `None` if URI is invalid.
+    pub(crate) fn from_bracketed(input: &'a [u8]) -> Option<Uri<'a>> {
+        match Uri::parse(input) {
+            Ok((&[], uri)) => Some(uri),
+            _ => None,
+        }
+    }
+
+    pub fn scheme(&self) -> &str {
+        match self {
+            Uri::Sip(_) => "sip",
+            Uri::Sips(_) => "sips",
+            Uri::Tel(_) => "tel",
+            Uri::Absolute(uri) => uri.scheme,
+        }
+    }
+
+    /// SIP or SIPS URI
+    pub fn sip(&self) -> Option<&SipUri<'a>> {
+        match self {
+            Uri::Sip(uri) | Uri::Sips(uri) => Some(uri),
+            _ => None,
+        }
+    }
+
+    pub fn tel(&self) -> Option<&TelUri<'a>> {
+        match self {
+            Uri::Tel(uri) => Some(uri),
+            _ => None,
+        }
+    }
+
+    pub fn absolute(&self) -> Option<&AbsoluteUri<'a>> {
+        match self {
+            Uri::Absolute(uri) => Some(uri),
+            _ => None,
+        }
+    }
+
+    /// If `parse_with_parameters` is false parameters of sip, sips and tel URIs
+    /// are not parsed: they belong to the header when URI is not enclosed in `<>`.
+    /// URI of other schemes is parsed as [`AbsoluteUri`].
+    pub fn parse_ext(
+        input: &'a [u8],
+        parse_with_parameters: bool,
+    ) -> nom::IResult<&'a [u8], Uri<'a>, SipParseError<'a>> {
+        let scheme = match take_scheme(input) {
+            Some(scheme) => scheme,
+            None => {
+                let err = SipParseError::new(1, Some("URI scheme is absent"));
+                return Err(nom::Err::Error(
+                    err.with_kind(SipParseErrorKind::Uri, input),
+                ));
+            }
+        };
+        if scheme.eq_ignore_ascii_case(b"tel") {
+            let (input, uri) = TelUri::parse_ext(input, parse_with_parameters)?;
+            return Ok((input, Uri::Tel(uri)));
+        }
+        if scheme.eq_ignore_ascii_case(b"sip") || scheme.eq_ignore_ascii_case(b"sips") {
+            let (input, uri) = SipUri::parse_ext(input, parse_with_parameters)?;
+            return Ok((input, Uri::from(uri)));
+        }
+        let (input, uri) = AbsoluteUri::parse(input)?;
+        Ok((input, Uri::Absolute(uri)))
+    }
+
+    pub fn parse(input: &'a [u8]) -> nom::IResult<&'a [u8], Uri<'a>, SipParseError<'a>> {
+        Uri::parse_ext(input, true)
+    }
+}
+
+impl<'a> From<SipUri<'a>> for Uri<'a> {
+    fn from(uri: SipUri<'a>) -> Uri<'a> {
+        match uri.scheme {
+            RequestUriScheme::SIP => Uri::Sip(uri),
+            RequestUriScheme::SIPS => Uri::Sips(uri),
+        }
+    }
+}
+
+impl<'a> From<TelUri<'a>> for Uri<'a> {
+    fn from(uri: TelUri<'a>) -> Uri<'a> {
+        Uri::Tel(uri)
+    }
+}
+
+impl<'a> From<AbsoluteUri<'a>> for Uri<'a> {
+    fn from(uri: AbsoluteUri<'a>) -> Uri<'a> {
+        Uri::Absolute(uri)
+    }
+}
+
+impl<'a> fmt::Display for Uri<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Uri::Sip(uri) | Uri::Sips(uri) => write!(f, "{}", uri),
+            Uri::Tel(uri) => write!(f, "{}", uri),
+            Uri::Absolute(uri) => write!(f, "{}", uri),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uri_parse() {
+        let (rest, uri) = Uri::parse(b"sips:alice@atlanta.com>").unwrap();
+        assert_eq!(rest, b">");
+        assert_eq!(uri.scheme(), "sips");
+        assert_eq!(uri.sip().unwrap().hostport.host, "atlanta.com");
+
+        let (_, uri) = Uri::parse(b"tel:+1-201-555-0123").unwrap();
+        assert_eq!(uri.tel().unwrap().number, "+1-201-555-0123");
+
+        let (rest, uri) = Uri::parse(b"mailto:watson@bell-telephone.com> ;q=0.1").unwrap();
+        assert_eq!(rest, b"> ;q=0.1");
+        let abs = uri.absolute().unwrap();
+        assert_eq!(abs.scheme, "mailto");
+        assert_eq!(abs.hier_part, "watson@bell-telephone.com");
+        assert_eq!(abs.query, None);
+
+        let (rest, uri) = Uri::parse(b"http://[2001:db8::1]/a%20b?x=1&y=2 SIP/2.0").unwrap();
+        assert_eq!(rest, b" SIP/2.0");
+        let abs = uri.absolute().unwrap();
+        assert_eq!(abs.scheme, "http");
+        assert_eq!(abs.hier_part, "//[2001:db8::1]/a%20b");
+        assert_eq!(abs.query, Some("x=1&y=2"));
+        assert_eq!(
+            alloc::format!("{}", uri),
+            "http://[2001:db8::1]/a%20b?x=1&y=2"
+        );
+
+        let (_, uri) = Uri::parse(b"urn:service:sos").unwrap();
+        assert_eq!(uri.absolute().unwrap().hier_part, "service:sos");
+
+        assert!(Uri::parse(b"bob@biloxi.com").is_err());
+        assert!(Uri::parse(b"1http://example.com").is_err());
+        assert!(Uri::parse(b"http:").is_err());
+    }
+}
@@ -0,0 +1,415 @@
+use crate::{
+    common::{
+        bnfcore::{is_digit, is_token_char},
+        errorparse::{map_sip_err, SipParseError, SipParseErrorKind},
+        hostport::HostPort,
+        nom_wrappers::from_utf8_nom,
+        transport::Transport,
+    },
+    headers::{
+        generic_params::{take_token_value, write_param},
+        header::{Header, HeaderTagType},
+        parsers::Via,
+        traits::SipHeaderParser,
+    },
+};
+use alloc::{collections::btree_map::BTreeMap, string::String};
+use core::{
+    fmt::{self, Write},
+    net::{IpAddr, SocketAddr},
+    str,
+};
+use nom::bytes::complete::take_while;
+use unicase::Ascii;
+
+/// Prefix of branch generated according to
+/// [rfc3261 section-8.1.1.7](https://tools.ietf.org/html/rfc3261#section-8.1.1.7)
+pub const BRANCH_MAGIC_COOKIE: &str = "z9hG4bK";
+
+/// Takes unquoted value of Via parameter.
+/// via-received  =  "received" EQUAL (IPv4address / IPv6address),
+/// IPv6 address is not enclosed in `[]` and is not a token
+pub(crate) fn take_param_value<'p>(
+    name: &str,
+    input: &'p [u8],
+) -> nom::IResult<&'p [u8], &'p [u8], SipParseError<'p>> {
+    if name.eq_ignore_ascii_case("received") {
+        take_while(|c| is_token_char(c) || c == b':')(input)
+    } else {
+        take_token_value(name, input)
+    }
+}
+
+/// Parses number that contains only digits, unlike `str::parse` that accepts `+`
+fn parse_digits<T: str::FromStr>(s: &str, max_len: usize) -> Option<T> {
+    if s.is_empty() || s.len() > max_len || !s.bytes().all(is_digit) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// sent-protocol  =  protocol-name SLASH protocol-version SLASH transport
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SentProtocol<'a> {
+    pub name: &'a str,
+    pub version: &'a str,
+    pub transport: Transport<'a>,
+}
+
+impl<'a> fmt::Display for SentProtocol<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.name, self.version, self.transport)
+    }
+}
+
+/// Typed value of Via header
+/// ([rfc3261 section-20.42](https://tools.ietf.org/html/rfc3261#section-20.42)).
+///
+/// ```rust
+/// use sipmsg::{SipHeaders, SipRFCHeader, SipTransport, ViaHeader};
+///
+/// let (_, headers) = SipHeaders::parse(
+///     b"Via: SIP/2.0/UDP bobspc.biloxi.com:5060;branch=z9hG4bKnashds7;rport\r\n\r\n",
+/// )
+/// .unwrap();
+/// let hdr = headers.get_rfc_s(SipRFCHeader::Via).unwrap();
+/// let mut via = ViaHeader::from_header(hdr).unwrap();
+/// assert_eq!(via.protocol.transport, SipTransport::UDP);
+/// assert_eq!(via.sent_by.host, "bobspc.biloxi.com");
+/// assert!(via.has_magic_cookie());
+///
+/// via.set_source("192.0.2.4:9988".parse().unwrap());
+/// assert_eq!(
+///     via.to_string(),
+///     "SIP/2.0/UDP bobspc.biloxi.com:5060;branch=z9hG4bKnashds7;received=192.0.2.4;rport=9988"
+/// );
+/// ```
+#[derive(PartialEq, Debug)]
+pub struct ViaHeader<'a> {
+    pub protocol: SentProtocol<'a>,
+    pub sent_by: HostPort<'a>,
+    pub branch: Option<&'a str>,
+    pub received: Option<IpAddr>,
+    /// [rfc3581](https://tools.ietf.org/html/rfc3581) `rport` parameter.
+    /// `Some(None)` if client requests it without value
+    pub rport: Option<Option<u16>>,
+    pub maddr: Option<&'a str>,
+    pub ttl: Option<u8>,
+    /// via-extension parameters
+    params: BTreeMap<Ascii<&'a str>, Option<&'a str>>,
+}
+
+impl<'a> ViaHeader<'a> {
+    /// Builds typed value from parsed Via header
+    pub fn from_header(hdr: &Header<'a>) -> Result<ViaHeader<'a>, SipParseError<'a>> {
+        ViaHeader::from_parsed(hdr).map_err(|e| SipParseError::from(e).in_header("Via"))
+    }
+
+    fn from_parsed(hdr: &Header<'a>) -> Result<ViaHeader<'a>, nom::Err<SipParseError<'a>>> {
+        let tags = match hdr.value.tags() {
+            Some(tags) => tags,
+            None => return sip_parse_error!(1, "Header is not Via"),
+        };
+        let tag = |tag_type| match tags.get(&tag_type) {
+            Some(value) => Ok(from_utf8_nom(value)?.1),
+            None => sip_parse_error!(1, "Header is not Via"),
+        };
+        let transport = match Transport::from_str(tag(HeaderTagType::ProtocolTransport)?) {
+            Some(transport) => transport,
+            None => return sip_parse_error!(2, "Invalid transport"),
+        };
+        let port = match tags.get(&HeaderTagType::Port) {
+            Some(port) => match parse_digits(from_utf8_nom(port)?.1, 5) {
+                Some(port) => Some(port),
+                None => return sip_parse_error!(3, "Invalid port"),
+            },
+            None => None,
+        };
+        let mut via = ViaHeader {
+            protocol: SentProtocol {
+                name: tag(HeaderTagType::ProtocolName)?,
+                version: tag(HeaderTagType::ProtocolVersion)?,
+                transport,
+            },
+            sent_by: HostPort {
+                host: tag(HeaderTagType::Host)?,
+                port,
+            },
+            branch: None,
+            received: None,
+            rport: None,
+            maddr: None,
+            ttl: None,
+            params: BTreeMap::new(),
+        };
+        for (name, value) in hdr.params().iter().flat_map(|p| p.iter()) {
+            via.set_param(*name, *value).map_err(|e| {
+                map_sip_err(e, |e| {
+                    e.with_kind(SipParseErrorKind::Parameter, hdr.raw_value_param)
+                })
+            })?;
+        }
+        Ok(via)
+    }
+
+    fn set_param(
+        &mut self,
+        name: Ascii<&'a str>,
+        value: Option<&'a str>,
+    ) -> Result<(), nom::Err<SipParseError<'a>>> {
+        if name == "branch" {
+            self.branch = match value {
+                Some(branch) => Some(branch),
+                None => return sip_parse_error!(4, "Empty branch parameter"),
+            };
+        } else if name == "received" {
+            self.received = match value.and_then(|addr| addr.parse().ok()) {
+                Some(addr) => Some(addr),
+                None => return sip_parse_error!(5, "Invalid received parameter"),
+            };
+        } else if name == "rport" {
+            self.rport = match value {
+                Some(port) => match parse_digits(port, 5) {
+                    Some(port) => Some(Some(port)),
+                    None => return sip_parse_error!(6, "Invalid rport parameter"),
+                },
+                None => Some(None),
+            };
+        } else if name == "maddr" {
+            self.maddr = match value {
+                Some(maddr) => Some(maddr),
+                None => return sip_parse_error!(7, "Empty maddr parameter"),
+            };
+        } else if name == "ttl" {
+            // ttl  =  1*3DIGIT ; 0 to 255
+            self.ttl = match value.and_then(|ttl| parse_digits(ttl, 3)) {
+                Some(ttl) => Some(ttl),
+                None => return sip_parse_error!(8, "Invalid ttl parameter"),
+            };
+        } else {
+            self.params.insert(name, value);
+        }
+        Ok(())
+    }
+
+    /// Writes `Via` header line to `buf` and parses it back,
+    /// so updated value can be put into headers instead of the topmost Via
+    /// with [`pop_rfc`](crate::SipHeaders::pop_rfc) and [`prepend`](crate::SipHeaders::prepend).
+    /// Previous content of `buf` is cleared
+    pub fn to_header<'b>(&self, buf: &'b mut String) -> Result<Header<'b>, SipParseError<'b>> {
+        buf.clear();
+        // Writing to String can't fail
+        let _ = write!(buf, "Via: {}\r\n", self);
+        let (_, (_, mut hdrs)) = Header::parse(buf.as_bytes()).map_err(SipParseError::from)?;
+        match hdrs.pop_front() {
+            Some(hdr) if hdrs.is_empty() => Ok(hdr),
+            _ => Err(
+                SipParseError::new(9, Some("Via value contains several headers")).in_header("Via"),
+            ),
+        }
+    }
+
+    /// Parses value of Via header with parameters
+    pub fn parse(input: &'a [u8]) -> nom::IResult<&'a [u8], ViaHeader<'a>, SipParseError<'a>> {
+        let (rest, (value, params)) =
+            Header::take_value_with(input, Via::take_value, take_param_value)?;
+        let hdr = Header::new("Via", value, params, &input[..input.len() - rest.len()]);
+        ViaHeader::from_parsed(&hdr).map(|via| (rest, via))
+    }
+
+    /// Branch starts with magic cookie, so it is unique
+    /// and can be used for transaction matching
+    pub fn has_magic_cookie(&self) -> bool {
+        matches!(self.branch, Some(branch) if branch.starts_with(BRANCH_MAGIC_COOKIE))
+    }
+
+    /// via-extension parameters
+    pub fn params(&self) -> &BTreeMap<Ascii<&'a str>, Option<&'a str>> {
+        &self.params
+    }
+
+    pub fn set_received(&mut self, addr: IpAddr) {
+        self.received = Some(addr);
+    }
+
+    pub fn set_rport(&mut self, port: u16) {
+        self.rport = Some(Some(port));
+    }
+
+    /// Fills `received` and `rport` by the address request was received from.
+    /// `received` is added if it differs from sent-by host
+    /// ([rfc3261 section-18.2.1](https://tools.ietf.org/html/rfc3261#section-18.2.1))
+    /// or if client requested `rport`
+    /// ([rfc3581 section-4](https://tools.ietf.org/html/rfc3581#section-4))
+    pub fn set_source(&mut self, source: SocketAddr) {
+        let sent_by_ip: Option<IpAddr> = self.sent_by.host.parse().ok();
+        if self.rport.is_some() {
+            self.set_rport(source.port());
+            self.set_received(source.ip());
+        } else if sent_by_ip != Some(source.ip()) {
+            self.set_received(source.ip());
+        }
+    }
+}
+
+impl<'a> fmt::Display for ViaHeader<'a> {
+    /// IPv6 address of `received` is written without brackets:
+    /// `received = IPv4address / IPv6address`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.protocol, self.sent_by)?;
+        if let Some(branch) = self.branch {
+            write!(f, ";branch={}", branch)?;
+        }
+        if let Some(received) = self.received {
+            write!(f, ";received={}", received)?;
+        }
+        match self.rport {
+            Some(Some(port)) => write!(f, ";rport={}", port)?,
+            Some(None) => f.write_str(";rport")?,
+            None => {}
+        }
+        if let Some(maddr) = self.maddr {
+            if maddr.contains(':') {
+                write!(f, ";maddr=[{}]", maddr)?;
+            } else {
+                write!(f, ";maddr={}", maddr)?;
+            }
+        }
+        if let Some(ttl) = self.ttl {
+            write!(f, ";ttl={}", ttl)?;
+        }
+        for (name, value) in self.params.iter() {
+            write_param(f, name, *value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GenericParams, SipHeaders, SipRFCHeader};
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_via_header_parse() {
+        let (rest, via) = ViaHeader::parse(
+            b"SIP/2.0/TCP [2001:db8::9]:5061;branch=z9hG4bK776;received=[2001:db8::1];\
+            rport=8080;maddr=224.2.0.1;ttl=16;hidden;ext=1\r\n",
+        )
+        .unwrap();
+        assert_eq!(rest, b"\r\n");
+        assert_eq!(
+            via.protocol,
+            SentProtocol {
+                name: "SIP",
+                version: "2.0",
+                transport: Transport::TCP
+            }
+        );
+        assert_eq!(via.sent_by.host, "2001:db8::9");
+        assert_eq!(via.sent_by.port, Some(5061));
+        assert_eq!(via.branch, Some("z9hG4bK776"));
+        assert!(via.has_magic_cookie());
+        assert_eq!(via.received, Some("2001:db8::1".parse().unwrap()));
+        assert_eq!(via.rport, Some(Some(8080)));
+        assert_eq!(via.maddr, Some("224.2.0.1"));
+        assert_eq!(via.ttl, Some(16));
+        assert_eq!(via.params().get(&Ascii::new("hidden")), Some(&None));
+        assert_eq!(via.params().get(&Ascii::new("ext")), Some(&Some("1")));
+        assert_eq!(
+            via.to_string(),
+            "SIP/2.0/TCP [2001:db8::9]:5061;branch=z9hG4bK776;received=2001:db8::1;\
+            rport=8080;maddr=224.2.0.1;ttl=16;ext=1;hidden"
+        );
+        let (_, via) = ViaHeader::parse(b"SIP/2.0/TCP h;received=2001:db8::1\r\n").unwrap();
+        assert_eq!(via.received, Some("2001:db8::1".parse().unwrap()));
+
+        let (_, via) = ViaHeader::parse(b"SIP/2.0/UDP 192.0.2.1;branch=1234\r\n").unwrap();
+        assert!(!via.has_magic_cookie());
+        assert_eq!(via.rport, None);
+
+        assert!(ViaHeader::parse(b"SIP/2.0/UDP 192.0.2.1;ttl=256\r\n").is_err());
+        assert!(ViaHeader::parse(b"SIP/2.0/UDP 192.0.2.1;rport=a\r\n").is_err());
+        assert!(ViaHeader::parse(b"SIP/2.0/UDP 192.0.2.1;received=host\r\n").is_err());
+        assert!(ViaHeader::parse(b"SIP/2.0/UDP 192.0.2.1;branch\r\n").is_err());
+        assert!(ViaHeader::parse(b"SIP/2.0/UDP 192.0.2.1;ttl=+5\r\n").is_err());
+        assert!(ViaHeader::parse(b"SIP/2.0/UDP 192.0.2.1;ttl=0016\r\n").is_err());
+        assert!(ViaHeader::parse(b"SIP/2.0/UDP 192.0.2.1;rport=+5060\r\n").is_err());
+
+        // The first occurrence of duplicated parameter is kept
+        let (_, via) = ViaHeader::parse(
+            b"SIP/2.0/UDP 192.0.2.1;branch=z9hG4bK1;ttl=1;Branch=z9hG4bK2;ttl=2;ext=a;EXT=b\r\n",
+        )
+        .unwrap();
+        assert_eq!(via.branch, Some("z9hG4bK1"));
+        assert_eq!(via.ttl, Some(1));
+        assert_eq!(via.params().get(&Ascii::new("ext")), Some(&Some("a")));
+
+        // Unbracketed IPv6 address is accepted only in Via
+        let (rest, _) = GenericParams::parse(b";received=2001:db8::1\r\n").unwrap();
+        assert_eq!(rest, b":db8::1\r\n");
+    }
+
+    #[test]
+    fn test_via_header_quoted_extension() {
+        let (_, via) =
+            ViaHeader::parse(b"SIP/2.0/UDP 192.0.2.1;branch=z9hG4bK1;ext=\"a \\\"b\\\"\"\r\n")
+                .unwrap();
+        assert_eq!(
+            via.params().get(&Ascii::new("ext")),
+            Some(&Some("a \\\"b\\\""))
+        );
+        assert_eq!(
+            via.to_string(),
+            "SIP/2.0/UDP 192.0.2.1;branch=z9hG4bK1;ext=\"a \\\"b\\\"\""
+        );
+        let mut line = String::new();
+        let hdr = via.to_header(&mut line).unwrap();
+        assert_eq!(ViaHeader::from_header(&hdr).unwrap(), via);
+    }
+
+    #[test]
+    fn test_via_header_set_source() {
+        let (_, mut via) = ViaHeader::parse(b"SIP/2.0/UDP 192.0.2.1:5060\r\n").unwrap();
+        via.set_source("192.0.2.1:5060".parse().unwrap());
+        assert_eq!(via.received, None);
+        via.set_source("192.0.2.4:5060".parse().unwrap());
+        assert_eq!(via.received, Some("192.0.2.4".parse().unwrap()));
+        assert_eq!(via.rport, None);
+
+        let (_, mut via) = ViaHeader::parse(b"SIP/2.0/UDP 192.0.2.1:5060;rport\r\n").unwrap();
+        via.set_source("192.0.2.1:9988".parse().unwrap());
+        assert_eq!(via.received, Some("192.0.2.1".parse().unwrap()));
+        assert_eq!(via.rport, Some(Some(9988)));
+    }
+
+    #[test]
+    fn test_via_header_write_back() {
+        let input = b"Via: SIP/2.0/UDP 192.0.2.1:5060;branch=z9hG4bK1;rport\r\n\
+            Via: SIP/2.0/UDP 192.0.2.9;branch=z9hG4bK2\r\n\
+            Call-ID: a84b4c76e66710\r\n\r\n";
+        let mut line = String::new();
+        let (_, mut headers) = SipHeaders::parse(input).unwrap();
+        let top_via = headers.get_rfc(SipRFCHeader::Via).unwrap().front().unwrap();
+        let mut via = ViaHeader::from_header(top_via).unwrap();
+        via.set_source("[2001:db8::1]:9988".parse().unwrap());
+        let hdr = via.to_header(&mut line).unwrap();
+        headers.pop_rfc(SipRFCHeader::Via);
+        headers.prepend(hdr);
+
+        let vias = headers.get_rfc(SipRFCHeader::Via).unwrap();
+        assert_eq!(vias.len(), 2);
+        assert_eq!(
+            vias[0].raw_value_param,
+            b"SIP/2.0/UDP 192.0.2.1:5060;branch=z9hG4bK1;received=2001:db8::1;rport=9988"
+        );
+        let via = ViaHeader::from_header(&vias[0]).unwrap();
+        assert_eq!(via.received, Some("2001:db8::1".parse().unwrap()));
+        assert_eq!(via.rport, Some(Some(9988)));
+        assert_eq!(
+            ViaHeader::from_header(&vias[1]).unwrap().branch,
+            Some("z9hG4bK2")
+        );
+    }
+}
@@ -26,20 +26,23 @@
 //! CSeq: 986759 INVITE\r\n\r\nbody_stuff"
 //! .as_bytes();
 //!
-//! // First parameter not realized yet.
-//! // It should consist be residue if Content-Length is less then actual body length.
-//! let (_, sip_msg) = SipMessage::parse(invite_msg_buf).unwrap();
+//! // First parameter is residue of buffer after the body.
+//! // Content-Length is absent here, so the whole rest of buffer is the body.
+//! let (residue, sip_msg) = SipMessage::parse(invite_msg_buf).unwrap();
+//! assert!(residue.is_empty());
 //! let request = sip_msg.request().unwrap();
 //! assert_eq!(request.rl.method, SipMethod::INVITE);
 //! assert_eq!(request.rl.sip_version, SipVersion(2, 0));
 //!
 //! // RURI
-//! assert_eq!(request.rl.uri.scheme, SipRequestUriScheme::SIP);
-//! assert_eq!(request.rl.uri.user_info().unwrap().value, "bob");
-//! assert_eq!(request.rl.uri.hostport.host, "biloxi.com");
-//! assert_eq!(request.rl.uri.params().unwrap().get(&"user"), Some(&Some("phone")));
-//! assert_eq!(request.rl.uri.headers().unwrap().get(&"to"), Some(&"alice%40atlanta.com"));
-//! assert_eq!(request.rl.uri.headers().unwrap().get(&"priority"), Some(&"urgent"));
+//! let ruri = request.rl.uri.sip().unwrap();
+//! assert_eq!(ruri.scheme, SipRequestUriScheme::SIP);
+//! assert_eq!(ruri.user_info().unwrap().value, "bob");
+//! assert_eq!(ruri.hostport.host, "biloxi.com");
+//! assert_eq!(ruri.params().unwrap().get(&"user"), Some(&Some("phone")));
+//! assert_eq!(ruri.headers().unwrap().get(&"to"), Some(&"alice%40atlanta.com"));
+//! assert_eq!(ruri.decoded_header("to").unwrap(), "alice@atlanta.com");
+//! assert_eq!(ruri.headers().unwrap().get(&"priority"), Some(&"urgent"));
 //!
 //! let call_id_header = request.headers.get_rfc_s(SipRFCHeader::CallID).unwrap();
 //! assert_eq!(call_id_header.value.vstr, "f81d4fae-7dec-11d0-a765-00a0c91e6bf6@foo.bar.com");
@@ -110,12 +113,19 @@
 //! ```
 //!
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 extern crate nom;
 
 #[macro_use]
 pub mod common;
 pub use common::errorparse;
+pub use common::errorparse::{
+    SipParseError, SipParseErrorDetails, SipParseErrorKind, SipParseErrorPosition,
+};
+pub use common::sip_date::SipDate;
 pub use common::sip_method::SipMethod;
+pub use common::transport::Transport as SipTransport;
 
 mod message;
 pub use message::get_message_type as get_sip_message_type;
@@ -130,7 +140,9 @@ pub use request::Request as SipRequest;
 pub use request::RequestLine as SipRequestLine;
 
 mod response;
+pub use response::OtherStatusCode as SipResponseOtherStatusCode;
 pub use response::Response as SipResponse;
+pub use response::StatusClass as SipResponseStatusClass;
 pub use response::StatusCode as SipResponseStatusCode;
 pub use response::StatusLine as SipResponseStatusLine;
 
@@ -139,5 +151,38 @@ pub use headers::sipuri::RequestUriScheme as SipRequestUriScheme;
 pub use headers::*;
 
 mod serializer;
+pub use serializer::SerializeError as SipSerializeError;
+pub use serializer::SipMsgSerializer;
+
+mod owned;
+pub use owned::OwnedMessageError as SipOwnedMessageError;
+pub use owned::OwnedSipMessage;
+
+mod builder;
+pub use builder::BuildError as SipBuildError;
+pub use builder::RequestBuilder as SipRequestBuilder;
+pub use builder::ResponseBuilder as SipResponseBuilder;
+
+mod stream;
+pub use stream::StreamDecoder as SipStreamDecoder;
+
+mod validation;
+pub use validation::Violation as SipViolation;
+
+#[cfg(feature = "digest")]
+pub mod digest;
+#[cfg(feature = "digest")]
+pub use digest::DigestAlgorithm as SipDigestAlgorithm;
+#[cfg(feature = "digest")]
+pub use digest::DigestChallenge as SipDigestChallenge;
+#[cfg(feature = "digest")]
+pub use digest::DigestClient as SipDigestClient;
+#[cfg(feature = "digest")]
+pub use digest::DigestError as SipDigestError;
+#[cfg(feature = "digest")]
+pub use digest::DigestServer as SipDigestServer;
+
+#[cfg(feature = "sdp")]
+pub mod sdp;
 
 pub use unicase::Ascii as SipAscii;
@@ -1,12 +1,44 @@
-use crate::common::errorparse::SipParseError;
-use crate::{SipRequest, SipResponse};
-use nom;
+use crate::common::{
+    bnfcore::is_token_char,
+    errorparse::{SipParseError, SipParseErrorKind},
+};
+use crate::{
+    owned::OwnedMessageError,
+    validation::{validate_message, Violation},
+    OwnedSipMessage, SipHeaders, SipRequest, SipResponse,
+};
+#[cfg(feature = "sdp")]
+use crate::sdp::SessionDescription;
+use alloc::vec::Vec;
+use core::{convert::TryFrom, fmt};
+use nom::{self, bytes::complete::take};
 
 /// SIP-Version
 /// ex. `SIP/2.0 -> SipVersion(2, 0)`
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct SipVersion(pub u8, pub u8);
 
+impl SipVersion {
+    /// Version of digits in `SIP/<major>.<minor>`. Each number must fit into `u8`
+    pub(crate) fn from_digits<'a>(
+        major: &'a [u8],
+        minor: &'a [u8],
+    ) -> Result<SipVersion, nom::Err<SipParseError<'a>>> {
+        let number =
+            |digits: &[u8]| -> Option<u8> { core::str::from_utf8(digits).ok()?.parse().ok() };
+        match (number(major), number(minor)) {
+            (Some(major), Some(minor)) => Ok(SipVersion(major, minor)),
+            _ => sip_parse_error!(2, "Invalid SIP version"),
+        }
+    }
+}
+
+impl fmt::Display for SipVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SIP/{}.{}", self.0, self.1)
+    }
+}
+
 pub enum SipMessage<'a> {
     Request(SipRequest<'a>),
     Response(SipResponse<'a>),
@@ -29,21 +61,144 @@ impl<'a> SipMessage<'a> {
         }
     }
 
+    pub fn headers(&self) -> &SipHeaders<'a> {
+        match self {
+            SipMessage::Request(r) => &r.headers,
+            SipMessage::Response(r) => &r.headers,
+        }
+    }
+
+    pub fn headers_mut(&mut self) -> &mut SipHeaders<'a> {
+        match self {
+            SipMessage::Request(r) => &mut r.headers,
+            SipMessage::Response(r) => &mut r.headers,
+        }
+    }
+
+    pub fn body(&self) -> Option<&'a [u8]> {
+        match self {
+            SipMessage::Request(r) => r.body,
+            SipMessage::Response(r) => r.body,
+        }
+    }
+
+    /// SDP body of message, see [`SessionDescription::from_body`]
+    #[cfg(feature = "sdp")]
+    pub fn sdp(&self) -> Result<Option<SessionDescription<'a>>, SipParseError<'a>> {
+        SessionDescription::from_body(self.headers(), self.body())
+    }
+
+    /// Makes copy of the message that doesn't borrow the input buffer.
+    /// Fails if fields were changed so that it is not a valid SIP message anymore
+    pub fn to_owned(&self) -> Result<OwnedSipMessage, OwnedMessageError> {
+        OwnedSipMessage::try_from(self)
+    }
+
+    /// See [`SipRequest::validate`] and [`SipResponse::validate`]
+    pub fn validate(&self) -> Vec<Violation<'a>> {
+        validate_message(self)
+    }
+
     pub fn parse(raw_message: &'a [u8]) -> nom::IResult<&[u8], SipMessage<'a>, SipParseError> {
+        SipMessage::parse_ext(raw_message, false)
+    }
+
+    /// Parses message keeping malformed headers as
+    /// [`SipInvalidHeader`](crate::SipInvalidHeader)s with their errors,
+    /// so the message can still be routed or answered with `400 Bad Request`.
+    /// Start line must be valid.
+    ///
+    /// ```rust
+    /// use sipmsg::{SipMessage, SipRFCHeader};
+    ///
+    /// let buf = b"OPTIONS sip:user@example.com SIP/2.0\r\n\
+    ///     Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKhjhs8ass877\r\n\
+    ///     CSeq: not-a-number OPTIONS\r\n\
+    ///     Content-Length: 0\r\n\r\n";
+    /// assert!(SipMessage::parse(buf).is_err());
+    ///
+    /// let (_, msg) = SipMessage::parse_lenient(buf).unwrap();
+    /// assert!(msg.headers().get_rfc_s(SipRFCHeader::Via).is_some());
+    /// let invalid = &msg.headers().invalid_headers()[0];
+    /// assert_eq!(invalid.rfc_type, Some(SipRFCHeader::CSeq));
+    /// assert_eq!(invalid.error.details().unwrap().position.unwrap().line, 3);
+    /// ```
+    pub fn parse_lenient(
+        raw_message: &'a [u8],
+    ) -> nom::IResult<&'a [u8], SipMessage<'a>, SipParseError<'a>> {
+        SipMessage::parse_ext(raw_message, true)
+    }
+
+    fn parse_ext(
+        raw_message: &'a [u8],
+        lenient: bool,
+    ) -> nom::IResult<&'a [u8], SipMessage<'a>, SipParseError<'a>> {
         match get_message_type(raw_message) {
+            MessageType::Request if lenient => {
+                let (inp, request) = SipRequest::parse_lenient(raw_message)?;
+                Ok((inp, SipMessage::Request(request)))
+            }
             MessageType::Request => {
                 let (inp, request) = SipRequest::parse(raw_message)?;
-                return Ok((inp, SipMessage::Request(request)));
+                Ok((inp, SipMessage::Request(request)))
+            }
+            MessageType::Response if lenient => {
+                let (inp, response) = SipResponse::parse_lenient(raw_message)?;
+                Ok((inp, SipMessage::Response(response)))
             }
             MessageType::Response => {
                 let (inp, response) = SipResponse::parse(raw_message)?;
-                return Ok((inp, SipMessage::Response(response)));
+                Ok((inp, SipMessage::Response(response)))
+            }
+            MessageType::Unknown => {
+                let err = SipParseError::new(
+                    1,
+                    Some("Message is invalid. Can't predict type of message"),
+                );
+                Err(nom::Err::Error(
+                    err.with_kind(SipParseErrorKind::StartLine, raw_message)
+                        .locate(raw_message),
+                ))
             }
-            MessageType::Unknown => sip_parse_error!(1, "Message is invalid. Can't predict type of message"),
         }
     }
 }
 
+/// Splits message body from the input according to the Content-Length header.
+/// Supports compact form `l`. If Content-Length is absent the body is the whole input.
+/// Returns the remainder after the body in the first parameter
+/// or `nom::Err::Incomplete` if the input is shorter than Content-Length.
+pub(crate) fn take_body<'a>(
+    input: &'a [u8],
+    headers: &SipHeaders<'a>,
+) -> nom::IResult<&'a [u8], &'a [u8], SipParseError<'a>> {
+    let content_length = match headers.content_length() {
+        Ok(Some(len)) => len,
+        Ok(None) => return Ok((&input[input.len()..], input)),
+        Err(e) => {
+            let err = match e.details() {
+                Some(details) => SipParseError::new(details.code, details.message),
+                None => SipParseError::new(2, Some("Invalid Content-Length value")),
+            };
+            return Err(nom::Err::Error(err.with_kind(SipParseErrorKind::Body, input)));
+        }
+    };
+    let content_length = match usize::try_from(content_length) {
+        Ok(len) => len,
+        Err(_) => {
+            let err = SipParseError::new(2, Some("Invalid Content-Length value"));
+            return Err(nom::Err::Error(err.with_kind(SipParseErrorKind::Body, input)));
+        }
+    };
+    if content_length > input.len() {
+        return Err(nom::Err::Incomplete(nom::Needed::new(
+            content_length - input.len(),
+        )));
+    }
+    let (rest, body) = take(content_length)(input)?;
+    Ok((rest, body))
+}
+
 #[derive(Debug, PartialEq)]
 pub enum MessageType {
     Request,
@@ -52,24 +207,11 @@ pub enum MessageType {
 }
 
 const SIP: &'static [u8] = &['S' as u8, 'I' as u8, 'P' as u8]; // SIP
-                                                               // First 3 letters of Request Method
-const ACK: &'static [u8] = &['A' as u8, 'C' as u8, 'K' as u8]; // ACK
-const BYE: &'static [u8] = &['B' as u8, 'Y' as u8, 'E' as u8]; // BYE
-const REG: &'static [u8] = &['R' as u8, 'E' as u8, 'G' as u8]; // REGISTER
-const CAN: &'static [u8] = &['C' as u8, 'A' as u8, 'N' as u8]; // CANCEL
-const INF: &'static [u8] = &['I' as u8, 'N' as u8, 'F' as u8]; // INFO
-const INV: &'static [u8] = &['I' as u8, 'N' as u8, 'V' as u8]; // INVITE
-const MES: &'static [u8] = &['M' as u8, 'E' as u8, 'S' as u8]; // MESSAGE
-const NOT: &'static [u8] = &['N' as u8, 'O' as u8, 'T' as u8]; // NOTIFY
-const OPT: &'static [u8] = &['O' as u8, 'P' as u8, 'T' as u8]; // OPTIONS
-const PRA: &'static [u8] = &['P' as u8, 'R' as u8, 'A' as u8]; // PRACK
-const PUB: &'static [u8] = &['P' as u8, 'U' as u8, 'B' as u8]; // PUBLISH
-const REF: &'static [u8] = &['R' as u8, 'E' as u8, 'F' as u8]; // REFER
-const SUB: &'static [u8] = &['S' as u8, 'U' as u8, 'B' as u8]; // SUBSCRIBE
-const UPD: &'static [u8] = &['U' as u8, 'P' as u8, 'D' as u8]; // UPDATE
 
 /// Fast determinates message type and minimal validate for further transmission to suitable parser.
-/// Does not validate full first line, just first 3 bytes.
+/// Does not validate full first line: message is a response if it starts with `SIP`
+/// and a request if it starts with a method token followed by SP.
+/// Extension methods are supported.
 /// ```rust
 /// assert_eq!(
 ///     sipmsg::get_sip_message_type(
@@ -79,26 +221,11 @@ const UPD: &'static [u8] = &['U' as u8, 'P' as u8, 'D' as u8]; // UPDATE
 /// );
 /// ```
 pub fn get_message_type(mt: &[u8]) -> MessageType {
-    if mt.len() < 3 {
-        MessageType::Unknown
-    } else {
-        match &mt[0..3] {
-            SIP => MessageType::Response,
-            ACK => MessageType::Request,
-            BYE => MessageType::Request,
-            REG => MessageType::Request,
-            CAN => MessageType::Request,
-            INF => MessageType::Request,
-            INV => MessageType::Request,
-            MES => MessageType::Request,
-            NOT => MessageType::Request,
-            OPT => MessageType::Request,
-            PRA => MessageType::Request,
-            PUB => MessageType::Request,
-            REF => MessageType::Request,
-            SUB => MessageType::Request,
-            UPD => MessageType::Request,
-            _ => MessageType::Unknown,
-        }
+    if mt.starts_with(SIP) && matches!(mt.get(SIP.len()), None | Some(b'/')) {
+        return MessageType::Response;
+    }
+    match mt.iter().position(|c| !is_token_char(*c)) {
+        Some(method_len) if method_len > 0 && mt[method_len] == b' ' => MessageType::Request,
+        _ => MessageType::Unknown,
     }
 }
@@ -0,0 +1,196 @@
+use crate::{
+    common::errorparse::{SipParseError, SipParseErrorKind},
+    serializer::SipMsgSerializer,
+    SipMessage, SipRequest, SipResponse,
+};
+use alloc::vec::Vec;
+use core::{convert::TryFrom, fmt};
+
+/// Error of converting message to [`OwnedSipMessage`].
+/// Fields of message were changed so that serialized message can't be parsed again
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct OwnedMessageError {
+    /// Element of serialized message that failed to parse
+    pub kind: SipParseErrorKind,
+}
+
+impl fmt::Display for OwnedMessageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "message can't be copied: {}", self.kind.as_str())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OwnedMessageError {}
+
+/// Owned (lifetime-free) SIP message.
+///
+/// Borrowed [`SipMessage`] can not outlive the buffer it was parsed from.
+/// `OwnedSipMessage` keeps its own copy of the message bytes,
+/// so it can be stored in transaction tables, queued or sent to another thread
+/// after the receive buffer is reused.
+/// The bytes are checked on construction and
+/// [`message`](OwnedSipMessage::message) parses them again on every call.
+///
+/// ```rust
+/// use sipmsg::{OwnedSipMessage, SipMessage, SipMethod};
+///
+/// let owned: OwnedSipMessage = {
+///     let buf = b"OPTIONS sip:user@example.com SIP/2.0\r\n\
+///                 Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKhjhs8ass877\r\n\
+///                 Content-Length: 0\r\n\r\n".to_vec();
+///     let (_, msg) = SipMessage::parse(&buf).unwrap();
+///     msg.to_owned().unwrap()
+/// };
+///
+/// let msg = owned.message();
+/// assert_eq!(msg.request().unwrap().rl.method, SipMethod::OPTIONS);
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct OwnedSipMessage {
+    /// Bytes of one complete message that is known to parse
+    raw: Vec<u8>,
+}
+
+impl OwnedSipMessage {
+    /// Parses one message from input and makes owned copy of it
+    pub fn parse(input: &[u8]) -> nom::IResult<&[u8], OwnedSipMessage, SipParseError<'_>> {
+        let (rest, _) = SipMessage::parse(input)?;
+        let raw = input[..input.len() - rest.len()].to_vec();
+        Ok((rest, OwnedSipMessage { raw }))
+    }
+
+    /// Takes ownership of serialized message if it can be parsed again.
+    /// Malformed headers are kept as invalid like [`SipMessage::parse_lenient`] does,
+    /// so everything that was parsed and serialized can be parsed again.
+    fn from_raw(raw: Vec<u8>) -> Result<OwnedSipMessage, OwnedMessageError> {
+        match SipMessage::parse_lenient(&raw) {
+            Ok(_) => Ok(OwnedSipMessage { raw }),
+            Err(e) => Err(OwnedMessageError {
+                kind: SipParseError::from(e).kind(),
+            }),
+        }
+    }
+
+    /// Parses the copy and returns borrowed representation of the message
+    pub fn message(&self) -> SipMessage<'_> {
+        match SipMessage::parse_lenient(&self.raw) {
+            Ok((_, msg)) => msg,
+            Err(_) => unreachable!("bytes of OwnedSipMessage are parsed on construction"),
+        }
+    }
+
+    /// Bytes of the message in wire format
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.raw
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.raw
+    }
+}
+
+/// Serialized message is parsed again.
+/// It fails if the fields of message were changed
+/// so that it is not a valid SIP message anymore.
+impl<'a> TryFrom<&SipMessage<'a>> for OwnedSipMessage {
+    type Error = OwnedMessageError;
+
+    fn try_from(msg: &SipMessage<'a>) -> Result<OwnedSipMessage, OwnedMessageError> {
+        let mut raw = Vec::new();
+        // Serializer without limit can't fail on writing to Vec
+        let _ = SipMsgSerializer::new().append_msg(msg, &mut raw);
+        OwnedSipMessage::from_raw(raw)
+    }
+}
+
+impl<'a> TryFrom<&SipRequest<'a>> for OwnedSipMessage {
+    type Error = OwnedMessageError;
+
+    fn try_from(req: &SipRequest<'a>) -> Result<OwnedSipMessage, OwnedMessageError> {
+        let mut raw = Vec::new();
+        // Serializer without limit can't fail on writing to Vec
+        let _ = SipMsgSerializer::new().append_req(req, &mut raw);
+        OwnedSipMessage::from_raw(raw)
+    }
+}
+
+impl<'a> TryFrom<&SipResponse<'a>> for OwnedSipMessage {
+    type Error = OwnedMessageError;
+
+    fn try_from(resp: &SipResponse<'a>) -> Result<OwnedSipMessage, OwnedMessageError> {
+        let mut raw = Vec::new();
+        // Serializer without limit can't fail on writing to Vec
+        let _ = SipMsgSerializer::new().append_resp(resp, &mut raw);
+        OwnedSipMessage::from_raw(raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SipMethod, SipRFCHeader, SipResponseStatusCode};
+    use alloc::string::{String, ToString};
+
+    #[test]
+    fn owned_outlives_buffer() {
+        let mut owned_msgs = Vec::new();
+        let mut buf = String::new();
+        for i in 0..3 {
+            buf.clear();
+            buf.push_str("SIP/2.0 200 OK\r\nVia: SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK");
+            buf.push(char::from(b'0' + i));
+            buf.push_str("\r\nContent-Length: 4\r\n\r\nbody");
+            let (_, msg) = SipMessage::parse(buf.as_bytes()).unwrap();
+            owned_msgs.push(msg.to_owned().unwrap());
+        }
+
+        for (i, owned) in owned_msgs.iter().enumerate() {
+            let msg = owned.message();
+            let resp = msg.response().unwrap();
+            assert_eq!(resp.sl.status_code, SipResponseStatusCode::OK);
+            assert_eq!(resp.body.unwrap(), b"body");
+            let via = resp.headers.get_rfc_s(SipRFCHeader::Via).unwrap();
+            let branch = via.params().unwrap().get("branch").unwrap().unwrap();
+            assert_eq!(branch.as_bytes()[7], b'0' + i as u8);
+        }
+    }
+
+    #[test]
+    fn owned_parse() {
+        let buf = b"SIP/2.0 100 Trying\r\nContent-Length: 0\r\n\r\nSIP/2.0 180 Ringing\r\n";
+        let (rest, owned) = OwnedSipMessage::parse(buf).unwrap();
+        assert_eq!(rest, b"SIP/2.0 180 Ringing\r\n");
+        assert_eq!(
+            owned.as_bytes(),
+            b"SIP/2.0 100 Trying\r\nContent-Length: 0\r\n\r\n"
+        );
+        let large_body = [b'a'; 10000];
+        let mut buf =
+            b"MESSAGE sip:bob@biloxi.com SIP/2.0\r\nContent-Length: 10000\r\n\r\n".to_vec();
+        buf.extend_from_slice(&large_body);
+        let (_, msg) = SipMessage::parse(&buf).unwrap();
+        let owned = msg.request().unwrap().to_owned().unwrap();
+        assert_eq!(owned.message().body().unwrap(), &large_body[..]);
+    }
+
+    #[test]
+    fn owned_is_send() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<OwnedSipMessage>();
+    }
+
+    #[test]
+    fn owned_invalid_message() {
+        let buf = b"OPTIONS sip:bob@biloxi.com SIP/2.0\r\nContent-Length: 0\r\n\r\n";
+        let (_, mut req) = SipRequest::parse(buf).unwrap();
+        req.rl.method = SipMethod::Extension("NOT A METHOD");
+        req.rl.raw = b"";
+        let err = req.to_owned().unwrap_err();
+        assert_eq!(err.kind, SipParseErrorKind::StartLine);
+        assert_eq!(
+            err.to_string(),
+            "message can't be copied: invalid start line"
+        );
+    }
+}
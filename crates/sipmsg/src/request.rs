@@ -1,12 +1,23 @@
-use crate::common::{errorparse::SipParseError, sip_method::*};
-use crate::{headers::*, message::*};
+use crate::common::{
+    bnfcore::is_token_char,
+    errorparse::{map_sip_err, SipParseError, SipParseErrorKind},
+    sip_method::*,
+};
+use crate::{
+    headers::*,
+    message::*,
+    owned::OwnedMessageError,
+    validation::{validate_request, Violation},
+    OwnedSipMessage,
+};
+use alloc::vec::Vec;
 use nom::{
     bytes::complete::{tag, take_while1},
-    character::{complete, is_alphabetic},
+    character::complete,
     sequence::tuple,
 };
 
-use core::{str, u8};
+use core::{convert::TryFrom, str, u8};
 
 /// [rfc3261 section-7.1](https://tools.ietf.org/html/rfc3261#section-7.1)
 pub struct Request<'a> {
@@ -27,35 +38,110 @@ impl<'a> Request<'a> {
         }
     }
 
+    /// Makes copy of the request that doesn't borrow the input buffer.
+    /// Fails if fields were changed so that it is not a valid SIP message anymore
+    pub fn to_owned(&self) -> Result<OwnedSipMessage, OwnedMessageError> {
+        OwnedSipMessage::try_from(self)
+    }
+
+    /// Checks the request against rfc3261: mandatory and repeated headers,
+    /// CSeq method, Content-Length and SIP version.
+    /// Empty list means the request is valid.
+    /// The first violation defines status code of reply.
+    pub fn validate(&self) -> Vec<Violation<'a>> {
+        validate_request(self)
+    }
+
+    /// Sets method of request. Request line is rebuilt on serialization.
+    pub fn set_method(&mut self, method: SipMethod<'a>) {
+        self.rl.method = method;
+        self.rl.raw = &[];
+    }
+
+    /// Sets Request-URI (`SipUri`, `TelUri` or `Uri`).
+    /// Request line is rebuilt on serialization.
+    pub fn set_uri<U: Into<Uri<'a>>>(&mut self, uri: U) {
+        self.rl.uri = uri.into();
+        self.rl.raw = &[];
+    }
+
+    /// Sets body of request. Content-Length header is updated on serialization,
+    /// it is not added if the request doesn't have it.
+    pub fn set_body(&mut self, body: &'a [u8]) {
+        self.body = Some(body);
+    }
+
+    /// Errors contain position relative to `buf_input`
     pub fn parse(buf_input: &'a [u8]) -> nom::IResult<&[u8], Request, SipParseError> {
+        Request::take_request(buf_input, false).map_err(|e| map_sip_err(e, |e| e.locate(buf_input)))
+    }
+
+    /// Parses request keeping malformed headers as
+    /// [`SipInvalidHeader`](crate::SipInvalidHeader)s.
+    /// See [`SipHeaders::parse_lenient`]
+    pub fn parse_lenient(
+        buf_input: &'a [u8],
+    ) -> nom::IResult<&'a [u8], Request<'a>, SipParseError<'a>> {
+        Request::take_request(buf_input, true).map_err(|e| map_sip_err(e, |e| e.locate(buf_input)))
+    }
+
+    fn take_request(
+        buf_input: &'a [u8],
+        lenient: bool,
+    ) -> nom::IResult<&'a [u8], Request<'a>, SipParseError<'a>> {
         let (input, rl) = RequestLine::parse(buf_input)?;
 
-        let (input, headers) = SipHeaders::parse(input)?;
-        // TODO check header Content-Length and fix buf_input return
-        let (body, _) = tag("\r\n")(input)?;
-        Ok((buf_input, Request::new(rl, headers, Some(body))))
+        let (input, mut headers) = SipHeaders::take_headers(input, lenient)?;
+        for hdr in headers.invalid_headers_mut() {
+            hdr.error.set_position(buf_input);
+        }
+        let (input, _) = tag("\r\n")(input)?;
+        let (input, body) = take_body(input, &headers)?;
+        Ok((input, Request::new(rl, headers, Some(body))))
     }
 }
 
 /// Ex: `INVITE sip:user@example.com SIP/2.0`
 /// The Request line and u8 buffer shoud have the same life time
 pub struct RequestLine<'a> {
-    pub method: SipMethod,
-    pub uri: SipUri<'a>,
+    pub method: SipMethod<'a>,
+    pub uri: Uri<'a>,
     pub sip_version: SipVersion,
-    // Byte representation of request line that includes \r\n
+    // Byte representation of request line that includes \r\n.
+    // Empty if request line was modified by setters. Fields may also be assigned directly,
+    // so serializer writes it only if it still matches the fields
     pub raw: &'a [u8],
 }
 
 impl<'a> RequestLine<'a> {
-    fn parse_method(method: &[u8]) -> Option<SipMethod> {
+    /// `raw` doesn't represent method, URI or version anymore
+    pub(crate) fn is_modified(&self) -> bool {
+        match RequestLine::parse(self.raw) {
+            Ok((_, rl)) => {
+                rl.method != self.method || rl.uri != self.uri || rl.sip_version != self.sip_version
+            }
+            Err(_) => true,
+        }
+    }
+
+    fn parse_method(method: &[u8]) -> Option<SipMethod<'_>> {
         match str::from_utf8(method) {
             Ok(s) => SipMethod::from_str(s),
             Err(_) => None,
         }
     }
     pub fn parse(source_input: &[u8]) -> nom::IResult<&[u8], RequestLine, SipParseError> {
-        let method = take_while1(is_alphabetic);
+        RequestLine::take_request_line(source_input).map_err(|e| {
+            map_sip_err(e, |e| {
+                e.with_kind(SipParseErrorKind::StartLine, source_input)
+            })
+        })
+    }
+
+    fn take_request_line(
+        source_input: &[u8],
+    ) -> nom::IResult<&[u8], RequestLine<'_>, SipParseError<'_>> {
+        let method = take_while1(is_token_char);
         let uri = take_while1(|c| c != b' ' as u8);
         let (input, (method, _, uri, _, _, major_version, _, minor_version, _)) =
             tuple((
@@ -70,24 +156,21 @@ impl<'a> RequestLine<'a> {
                 complete::crlf,
             ))(source_input)?;
 
-        let (_, sip_uri) = SipUri::parse(uri)?;
+        let (_, uri) = Uri::parse(uri)?;
 
-        let sip_version = SipVersion(
-            u8::from_str_radix(str::from_utf8(major_version).unwrap(), 10).unwrap(),
-            u8::from_str_radix(str::from_utf8(minor_version).unwrap(), 10).unwrap(),
-        );
+        let sip_version = SipVersion::from_digits(major_version, minor_version)?;
 
         match RequestLine::parse_method(method) {
             Some(m) => Ok((
                 input,
                 RequestLine {
                     method: m,
-                    uri: sip_uri,
+                    uri,
                     sip_version: sip_version,
                     raw: &source_input[..source_input.len() - input.len()],
                 },
             )),
-            None => return sip_parse_error!(1, "Error cast from_utf8"),
+            None => return sip_parse_error!(1, "Invalid method"),
         }
     }
 }
@@ -1,8 +1,14 @@
-use crate::common::{errorparse::SipParseError, nom_wrappers::from_utf8_nom};
+use crate::common::{
+    errorparse::{map_sip_err, SipParseError, SipParseErrorKind},
+    nom_wrappers::from_utf8_nom,
+};
 use crate::headers::*;
-use crate::message::SipVersion;
+use crate::message::{take_body, SipVersion};
+use crate::validation::{validate_response, Violation};
+use crate::{owned::OwnedMessageError, OwnedSipMessage};
+use alloc::vec::Vec;
 
-use core::str;
+use core::{convert::TryFrom, str};
 use nom::{
     bytes::complete::{tag, take, take_until},
     character::complete,
@@ -24,12 +30,36 @@ pub struct StatusLine<'a> {
     pub sip_version: SipVersion,
     pub status_code: StatusCode,
     pub reason_phrase: &'a str,
-    // Byte representation of request line that includes \r\n
+    // Byte representation of status line that includes \r\n.
+    // Empty if status line was modified by setters. Fields may also be assigned directly,
+    // so serializer writes it only if it still matches the fields
     pub raw: &'a [u8],
 }
 
 impl<'a> StatusLine<'a> {
+    /// `raw` doesn't represent version, status code or reason phrase anymore
+    pub(crate) fn is_modified(&self) -> bool {
+        match StatusLine::parse(self.raw) {
+            Ok((_, sl)) => {
+                sl.sip_version != self.sip_version
+                    || sl.status_code != self.status_code
+                    || sl.reason_phrase != self.reason_phrase
+            }
+            Err(_) => true,
+        }
+    }
+
     pub fn parse(source_input: &'a [u8]) -> nom::IResult<&[u8], StatusLine<'a>, SipParseError> {
+        StatusLine::take_status_line(source_input).map_err(|e| {
+            map_sip_err(e, |e| {
+                e.with_kind(SipParseErrorKind::StartLine, source_input)
+            })
+        })
+    }
+
+    fn take_status_line(
+        source_input: &'a [u8],
+    ) -> nom::IResult<&'a [u8], StatusLine<'a>, SipParseError<'a>> {
         let (input, (_, major_version, _, minor_version, _, status_code, _, reason_phrase, _)) =
             tuple((
                 tag("SIP/"),
@@ -43,12 +73,12 @@ impl<'a> StatusLine<'a> {
                 take(2usize), // skip /r/n
             ))(source_input)?;
 
-        let sip_version = SipVersion(
-            u8::from_str_radix(str::from_utf8(major_version).unwrap(), 10).unwrap(),
-            u8::from_str_radix(str::from_utf8(minor_version).unwrap(), 10).unwrap(),
-        );
+        let sip_version = SipVersion::from_digits(major_version, minor_version)?;
 
         let status_code = StatusCode::from_bytes_str(status_code);
+        if status_code == StatusCode::Unknown {
+            return sip_parse_error!(1, "Invalid status code");
+        }
         let (_, reason_phrase_str) = from_utf8_nom(reason_phrase)?;
         Ok((
             input,
@@ -72,250 +102,500 @@ impl<'a> Response<'a> {
         }
     }
 
+    /// Makes copy of the response that doesn't borrow the input buffer.
+    /// Fails if fields were changed so that it is not a valid SIP message anymore
+    pub fn to_owned(&self) -> Result<OwnedSipMessage, OwnedMessageError> {
+        OwnedSipMessage::try_from(self)
+    }
+
+    /// Checks the response against rfc3261: mandatory and repeated headers,
+    /// Content-Length and SIP version. Empty list means the response is valid.
+    pub fn validate(&self) -> Vec<Violation<'a>> {
+        validate_response(self)
+    }
+
+    /// Sets status code and reason phrase. Status line is rebuilt on serialization.
+    pub fn set_status(&mut self, status_code: StatusCode, reason_phrase: &'a str) {
+        self.sl.status_code = status_code;
+        self.sl.reason_phrase = reason_phrase;
+        self.sl.raw = &[];
+    }
+
+    /// Sets body of response. Content-Length header is updated on serialization,
+    /// it is not added if the response doesn't have it.
+    pub fn set_body(&mut self, body: &'a [u8]) {
+        self.body = Some(body);
+    }
+
+    /// Errors contain position relative to `buf_input`
     pub fn parse(buf_input: &'a [u8]) -> nom::IResult<&[u8], Response<'a>, SipParseError> {
+        Response::take_response(buf_input, false)
+            .map_err(|e| map_sip_err(e, |e| e.locate(buf_input)))
+    }
+
+    /// Parses response keeping malformed headers as
+    /// [`SipInvalidHeader`](crate::SipInvalidHeader)s.
+    /// See [`SipHeaders::parse_lenient`]
+    pub fn parse_lenient(
+        buf_input: &'a [u8],
+    ) -> nom::IResult<&'a [u8], Response<'a>, SipParseError<'a>> {
+        Response::take_response(buf_input, true)
+            .map_err(|e| map_sip_err(e, |e| e.locate(buf_input)))
+    }
+
+    fn take_response(
+        buf_input: &'a [u8],
+        lenient: bool,
+    ) -> nom::IResult<&'a [u8], Response<'a>, SipParseError<'a>> {
         let (input, rl) = StatusLine::parse(buf_input)?;
 
-        let (input, headers) = SipHeaders::parse(input)?;
-        // TODO check header Content-Length and fix buf_input return
-        let (body, _) = tag("\r\n")(input)?;
+        let (input, mut headers) = SipHeaders::take_headers(input, lenient)?;
+        for hdr in headers.invalid_headers_mut() {
+            hdr.error.set_position(buf_input);
+        }
+        let (input, _) = tag("\r\n")(input)?;
+        let (input, body) = take_body(input, &headers)?;
 
-        Ok((buf_input, Response::new(rl, headers, Some(body))))
+        Ok((input, Response::new(rl, headers, Some(body))))
     }
 }
 
+/// Class of status code
+/// ([rfc3261 section-7.2](https://tools.ietf.org/html/rfc3261#section-7.2))
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum StatusClass {
+    /// 1xx
+    Provisional,
+    /// 2xx
+    Success,
+    /// 3xx
+    Redirection,
+    /// 4xx
+    RequestFailure,
+    /// 5xx
+    ServerFailure,
+    /// 6xx
+    GlobalFailure,
+}
+
+/// Valid (100-699) status code that is not registered.
+/// It is made only by [`StatusCode::from_code`] and others,
+/// so a registered code is never `StatusCode::Other`
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct OtherStatusCode(u16);
+
+impl OtherStatusCode {
+    pub fn code(&self) -> u16 {
+        self.0
+    }
+}
+
+/// Response status code.
+/// Contains all codes registered by IANA
+/// ([SIP Response Codes](https://www.iana.org/assignments/sip-parameters/sip-parameters.xhtml#sip-parameters-7)).
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum StatusCode {
     // Provisional 1xx
-    Trying = 100,
-    Ringing = 180,
-    CallIsBeingForwarded = 181,
-    Queued = 182,
-    SessionProgress = 183,
+    Trying,
+    Ringing,
+    CallIsBeingForwarded,
+    Queued,
+    SessionProgress,
+    EarlyDialogTerminated,
 
     // Successful 2xx
-    OK = 200,
+    OK,
+    Accepted,
+    NoNotification,
 
     // Redirection 3xx
-    MultipleChoices = 300,
-    MovedPermanently = 301,
-    MovedTemporarily = 302,
-    UseProxy = 305,
-    AlternativeService = 380,
+    MultipleChoices,
+    MovedPermanently,
+    MovedTemporarily,
+    UseProxy,
+    AlternativeService,
 
     // Request Failure 4xx
-    BadRequest = 400,
-    Unauthorized = 401,
-    PaymentRequired = 402,
-    Forbidden = 403,
-    NotFound = 404,
-    MethodNotAllowed = 405,
-    NotAcceptableResourceContent = 406,
-    ProxyAuthenticationRequired = 407,
-    RequestTimeout = 408,
-    Gone = 410,
-    RequestEntityTooLarge = 413,
-    RequestUriTooLong = 414,
-    UnsupportedMediaType = 415,
-    UnsupportedUriScheme = 416,
-    BadExtension = 420,
-    ExtensionRequired = 421,
-    IntervalTooBrief = 423,
-    TemporarilyUnavailable = 480,
-    CallOrTransactionDoesNotExist = 481,
-    LoopDetected = 482,
-    TooManyHops = 483,
-    AddressIncomplete = 484,
-    Ambiguous = 485,
-    BusyHere = 486,
-    RequestTerminated = 487,
-    NotAcceptableHere = 488,
-    RequestPending = 491,
-    Undecipherable = 493,
+    BadRequest,
+    Unauthorized,
+    PaymentRequired,
+    Forbidden,
+    NotFound,
+    MethodNotAllowed,
+    NotAcceptableResourceContent,
+    ProxyAuthenticationRequired,
+    RequestTimeout,
+    Gone,
+    ConditionalRequestFailed,
+    RequestEntityTooLarge,
+    RequestUriTooLong,
+    UnsupportedMediaType,
+    UnsupportedUriScheme,
+    UnknownResourcePriority,
+    BadExtension,
+    ExtensionRequired,
+    SessionIntervalTooSmall,
+    IntervalTooBrief,
+    BadLocationInformation,
+    BadAlertMessage,
+    UseIdentityHeader,
+    ProvideReferrerIdentity,
+    FlowFailed,
+    AnonymityDisallowed,
+    BadIdentityInfo,
+    UnsupportedCredential,
+    InvalidIdentityHeader,
+    FirstHopLacksOutboundSupport,
+    MaxBreadthExceeded,
+    BadInfoPackage,
+    ConsentNeeded,
+    TemporarilyUnavailable,
+    CallOrTransactionDoesNotExist,
+    LoopDetected,
+    TooManyHops,
+    AddressIncomplete,
+    Ambiguous,
+    BusyHere,
+    RequestTerminated,
+    NotAcceptableHere,
+    BadEvent,
+    RequestPending,
+    Undecipherable,
+    SecurityAgreementRequired,
 
     // Server Failure 5xx
-    ServerInternalError = 500,
-    NotImplemented = 501,
-    BadGateway = 502,
-    ServiceUnavailable = 503,
-    ServerTimeout = 504,
-    VersionNotSupported = 505,
-    MessageTooLarge = 513,
+    ServerInternalError,
+    NotImplemented,
+    BadGateway,
+    ServiceUnavailable,
+    ServerTimeout,
+    VersionNotSupported,
+    MessageTooLarge,
+    PushNotificationServiceNotSupported,
+    PreconditionFailure,
 
     // Global Failures 6xx
-    BusyEverywhere = 600,
-    Decline = 603,
-    DoesNotExistAnywhere = 604,
-    NotAcceptable = 606,
+    BusyEverywhere,
+    Decline,
+    DoesNotExistAnywhere,
+    NotAcceptable,
+    Unwanted,
+    Rejected,
 
-    // Unknown
-    Unknown = 999,
+    /// Valid (100-699) status code that is not registered
+    Other(OtherStatusCode),
+    /// Status code is not a number in range 100-699
+    Unknown,
 }
 
-const BS_100: &'static [u8] = "100".as_bytes();
-const BS_180: &'static [u8] = "180".as_bytes();
-const BS_181: &'static [u8] = "181".as_bytes();
-const BS_182: &'static [u8] = "182".as_bytes();
-const BS_183: &'static [u8] = "183".as_bytes();
-const BS_200: &'static [u8] = "200".as_bytes();
-const BS_300: &'static [u8] = "300".as_bytes();
-const BS_301: &'static [u8] = "301".as_bytes();
-const BS_302: &'static [u8] = "302".as_bytes();
-const BS_305: &'static [u8] = "305".as_bytes();
-const BS_380: &'static [u8] = "380".as_bytes();
-const BS_400: &'static [u8] = "400".as_bytes();
-const BS_401: &'static [u8] = "401".as_bytes();
-const BS_402: &'static [u8] = "402".as_bytes();
-const BS_403: &'static [u8] = "403".as_bytes();
-const BS_404: &'static [u8] = "404".as_bytes();
-const BS_405: &'static [u8] = "405".as_bytes();
-const BS_406: &'static [u8] = "406".as_bytes();
-const BS_407: &'static [u8] = "407".as_bytes();
-const BS_408: &'static [u8] = "408".as_bytes();
-const BS_410: &'static [u8] = "410".as_bytes();
-const BS_413: &'static [u8] = "413".as_bytes();
-const BS_414: &'static [u8] = "414".as_bytes();
-const BS_415: &'static [u8] = "415".as_bytes();
-const BS_416: &'static [u8] = "416".as_bytes();
-const BS_420: &'static [u8] = "420".as_bytes();
-const BS_421: &'static [u8] = "421".as_bytes();
-const BS_423: &'static [u8] = "423".as_bytes();
-const BS_480: &'static [u8] = "480".as_bytes();
-const BS_481: &'static [u8] = "481".as_bytes();
-const BS_482: &'static [u8] = "482".as_bytes();
-const BS_483: &'static [u8] = "483".as_bytes();
-const BS_484: &'static [u8] = "484".as_bytes();
-const BS_485: &'static [u8] = "485".as_bytes();
-const BS_486: &'static [u8] = "486".as_bytes();
-const BS_487: &'static [u8] = "487".as_bytes();
-const BS_488: &'static [u8] = "488".as_bytes();
-const BS_491: &'static [u8] = "491".as_bytes();
-const BS_493: &'static [u8] = "493".as_bytes();
-const BS_500: &'static [u8] = "500".as_bytes();
-const BS_501: &'static [u8] = "501".as_bytes();
-const BS_502: &'static [u8] = "502".as_bytes();
-const BS_503: &'static [u8] = "503".as_bytes();
-const BS_504: &'static [u8] = "504".as_bytes();
-const BS_505: &'static [u8] = "505".as_bytes();
-const BS_513: &'static [u8] = "513".as_bytes();
-const BS_600: &'static [u8] = "600".as_bytes();
-const BS_603: &'static [u8] = "603".as_bytes();
-const BS_604: &'static [u8] = "604".as_bytes();
-const BS_606: &'static [u8] = "606".as_bytes();
-
 impl StatusCode {
+    /// Numeric value of status code. Ex: `StatusCode::Ringing -> 180`.
+    /// Returns 999 for `StatusCode::Unknown`
+    pub fn code(&self) -> u16 {
+        match self {
+            StatusCode::Trying => 100,
+            StatusCode::Ringing => 180,
+            StatusCode::CallIsBeingForwarded => 181,
+            StatusCode::Queued => 182,
+            StatusCode::SessionProgress => 183,
+            StatusCode::EarlyDialogTerminated => 199,
+            StatusCode::OK => 200,
+            StatusCode::Accepted => 202,
+            StatusCode::NoNotification => 204,
+            StatusCode::MultipleChoices => 300,
+            StatusCode::MovedPermanently => 301,
+            StatusCode::MovedTemporarily => 302,
+            StatusCode::UseProxy => 305,
+            StatusCode::AlternativeService => 380,
+            StatusCode::BadRequest => 400,
+            StatusCode::Unauthorized => 401,
+            StatusCode::PaymentRequired => 402,
+            StatusCode::Forbidden => 403,
+            StatusCode::NotFound => 404,
+            StatusCode::MethodNotAllowed => 405,
+            StatusCode::NotAcceptableResourceContent => 406,
+            StatusCode::ProxyAuthenticationRequired => 407,
+            StatusCode::RequestTimeout => 408,
+            StatusCode::Gone => 410,
+            StatusCode::ConditionalRequestFailed => 412,
+            StatusCode::RequestEntityTooLarge => 413,
+            StatusCode::RequestUriTooLong => 414,
+            StatusCode::UnsupportedMediaType => 415,
+            StatusCode::UnsupportedUriScheme => 416,
+            StatusCode::UnknownResourcePriority => 417,
+            StatusCode::BadExtension => 420,
+            StatusCode::ExtensionRequired => 421,
+            StatusCode::SessionIntervalTooSmall => 422,
+            StatusCode::IntervalTooBrief => 423,
+            StatusCode::BadLocationInformation => 424,
+            StatusCode::BadAlertMessage => 425,
+            StatusCode::UseIdentityHeader => 428,
+            StatusCode::ProvideReferrerIdentity => 429,
+            StatusCode::FlowFailed => 430,
+            StatusCode::AnonymityDisallowed => 433,
+            StatusCode::BadIdentityInfo => 436,
+            StatusCode::UnsupportedCredential => 437,
+            StatusCode::InvalidIdentityHeader => 438,
+            StatusCode::FirstHopLacksOutboundSupport => 439,
+            StatusCode::MaxBreadthExceeded => 440,
+            StatusCode::BadInfoPackage => 469,
+            StatusCode::ConsentNeeded => 470,
+            StatusCode::TemporarilyUnavailable => 480,
+            StatusCode::CallOrTransactionDoesNotExist => 481,
+            StatusCode::LoopDetected => 482,
+            StatusCode::TooManyHops => 483,
+            StatusCode::AddressIncomplete => 484,
+            StatusCode::Ambiguous => 485,
+            StatusCode::BusyHere => 486,
+            StatusCode::RequestTerminated => 487,
+            StatusCode::NotAcceptableHere => 488,
+            StatusCode::BadEvent => 489,
+            StatusCode::RequestPending => 491,
+            StatusCode::Undecipherable => 493,
+            StatusCode::SecurityAgreementRequired => 494,
+            StatusCode::ServerInternalError => 500,
+            StatusCode::NotImplemented => 501,
+            StatusCode::BadGateway => 502,
+            StatusCode::ServiceUnavailable => 503,
+            StatusCode::ServerTimeout => 504,
+            StatusCode::VersionNotSupported => 505,
+            StatusCode::MessageTooLarge => 513,
+            StatusCode::PushNotificationServiceNotSupported => 555,
+            StatusCode::PreconditionFailure => 580,
+            StatusCode::BusyEverywhere => 600,
+            StatusCode::Decline => 603,
+            StatusCode::DoesNotExistAnywhere => 604,
+            StatusCode::NotAcceptable => 606,
+            StatusCode::Unwanted => 607,
+            StatusCode::Rejected => 608,
+            StatusCode::Other(code) => code.code(),
+            StatusCode::Unknown => 999,
+        }
+    }
+
+    /// Returns error if code is not in range 100-699
+    pub fn from_code(code: u16) -> Result<StatusCode, nom::Err<SipParseError<'static>>> {
+        if !(100..=699).contains(&code) {
+            return sip_parse_error!(1, "Status code must be in range 100-699");
+        }
+        let status_code = match code {
+            100 => StatusCode::Trying,
+            180 => StatusCode::Ringing,
+            181 => StatusCode::CallIsBeingForwarded,
+            182 => StatusCode::Queued,
+            183 => StatusCode::SessionProgress,
+            199 => StatusCode::EarlyDialogTerminated,
+            200 => StatusCode::OK,
+            202 => StatusCode::Accepted,
+            204 => StatusCode::NoNotification,
+            300 => StatusCode::MultipleChoices,
+            301 => StatusCode::MovedPermanently,
+            302 => StatusCode::MovedTemporarily,
+            305 => StatusCode::UseProxy,
+            380 => StatusCode::AlternativeService,
+            400 => StatusCode::BadRequest,
+            401 => StatusCode::Unauthorized,
+            402 => StatusCode::PaymentRequired,
+            403 => StatusCode::Forbidden,
+            404 => StatusCode::NotFound,
+            405 => StatusCode::MethodNotAllowed,
+            406 => StatusCode::NotAcceptableResourceContent,
+            407 => StatusCode::ProxyAuthenticationRequired,
+            408 => StatusCode::RequestTimeout,
+            410 => StatusCode::Gone,
+            412 => StatusCode::ConditionalRequestFailed,
+            413 => StatusCode::RequestEntityTooLarge,
+            414 => StatusCode::RequestUriTooLong,
+            415 => StatusCode::UnsupportedMediaType,
+            416 => StatusCode::UnsupportedUriScheme,
+            417 => StatusCode::UnknownResourcePriority,
+            420 => StatusCode::BadExtension,
+            421 => StatusCode::ExtensionRequired,
+            422 => StatusCode::SessionIntervalTooSmall,
+            423 => StatusCode::IntervalTooBrief,
+            424 => StatusCode::BadLocationInformation,
+            425 => StatusCode::BadAlertMessage,
+            428 => StatusCode::UseIdentityHeader,
+            429 => StatusCode::ProvideReferrerIdentity,
+            430 => StatusCode::FlowFailed,
+            433 => StatusCode::AnonymityDisallowed,
+            436 => StatusCode::BadIdentityInfo,
+            437 => StatusCode::UnsupportedCredential,
+            438 => StatusCode::InvalidIdentityHeader,
+            439 => StatusCode::FirstHopLacksOutboundSupport,
+            440 => StatusCode::MaxBreadthExceeded,
+            469 => StatusCode::BadInfoPackage,
+            470 => StatusCode::ConsentNeeded,
+            480 => StatusCode::TemporarilyUnavailable,
+            481 => StatusCode::CallOrTransactionDoesNotExist,
+            482 => StatusCode::LoopDetected,
+            483 => StatusCode::TooManyHops,
+            484 => StatusCode::AddressIncomplete,
+            485 => StatusCode::Ambiguous,
+            486 => StatusCode::BusyHere,
+            487 => StatusCode::RequestTerminated,
+            488 => StatusCode::NotAcceptableHere,
+            489 => StatusCode::BadEvent,
+            491 => StatusCode::RequestPending,
+            493 => StatusCode::Undecipherable,
+            494 => StatusCode::SecurityAgreementRequired,
+            500 => StatusCode::ServerInternalError,
+            501 => StatusCode::NotImplemented,
+            502 => StatusCode::BadGateway,
+            503 => StatusCode::ServiceUnavailable,
+            504 => StatusCode::ServerTimeout,
+            505 => StatusCode::VersionNotSupported,
+            513 => StatusCode::MessageTooLarge,
+            555 => StatusCode::PushNotificationServiceNotSupported,
+            580 => StatusCode::PreconditionFailure,
+            600 => StatusCode::BusyEverywhere,
+            603 => StatusCode::Decline,
+            604 => StatusCode::DoesNotExistAnywhere,
+            606 => StatusCode::NotAcceptable,
+            607 => StatusCode::Unwanted,
+            608 => StatusCode::Rejected,
+            _ => StatusCode::Other(OtherStatusCode(code)),
+        };
+        Ok(status_code)
+    }
+
     pub fn from_str(s: &str) -> StatusCode {
         StatusCode::from_bytes_str(s.as_bytes())
     }
 
+    /// Returns `StatusCode::Unknown` if `s` is not a three-digit code in range 100-699
     pub fn from_bytes_str(s: &[u8]) -> StatusCode {
-        match s {
-            BS_100 => StatusCode::Trying,
-            BS_180 => StatusCode::Ringing,
-            BS_181 => StatusCode::CallIsBeingForwarded,
-            BS_182 => StatusCode::Queued,
-            BS_183 => StatusCode::SessionProgress,
-            BS_200 => StatusCode::OK,
-            BS_300 => StatusCode::MultipleChoices,
-            BS_301 => StatusCode::MovedPermanently,
-            BS_302 => StatusCode::MovedTemporarily,
-            BS_305 => StatusCode::UseProxy,
-            BS_380 => StatusCode::AlternativeService,
-            BS_400 => StatusCode::BadRequest,
-            BS_401 => StatusCode::Unauthorized,
-            BS_402 => StatusCode::PaymentRequired,
-            BS_403 => StatusCode::Forbidden,
-            BS_404 => StatusCode::NotFound,
-            BS_405 => StatusCode::MethodNotAllowed,
-            BS_406 => StatusCode::NotAcceptableResourceContent,
-            BS_407 => StatusCode::ProxyAuthenticationRequired,
-            BS_408 => StatusCode::RequestTimeout,
-            BS_410 => StatusCode::Gone,
-            BS_413 => StatusCode::RequestEntityTooLarge,
-            BS_414 => StatusCode::RequestUriTooLong,
-            BS_415 => StatusCode::UnsupportedMediaType,
-            BS_416 => StatusCode::UnsupportedUriScheme,
-            BS_420 => StatusCode::BadExtension,
-            BS_421 => StatusCode::ExtensionRequired,
-            BS_423 => StatusCode::IntervalTooBrief,
-            BS_480 => StatusCode::TemporarilyUnavailable,
-            BS_481 => StatusCode::CallOrTransactionDoesNotExist,
-            BS_482 => StatusCode::LoopDetected,
-            BS_483 => StatusCode::TooManyHops,
-            BS_484 => StatusCode::AddressIncomplete,
-            BS_485 => StatusCode::Ambiguous,
-            BS_486 => StatusCode::BusyHere,
-            BS_487 => StatusCode::RequestTerminated,
-            BS_488 => StatusCode::NotAcceptableHere,
-            BS_491 => StatusCode::RequestPending,
-            BS_493 => StatusCode::Undecipherable,
-            BS_500 => StatusCode::ServerInternalError,
-            BS_501 => StatusCode::NotImplemented,
-            BS_502 => StatusCode::BadGateway,
-            BS_503 => StatusCode::ServiceUnavailable,
-            BS_504 => StatusCode::ServerTimeout,
-            BS_505 => StatusCode::VersionNotSupported,
-            BS_513 => StatusCode::MessageTooLarge,
-            BS_600 => StatusCode::BusyEverywhere,
-            BS_603 => StatusCode::Decline,
-            BS_604 => StatusCode::DoesNotExistAnywhere,
-            BS_606 => StatusCode::NotAcceptable,
-            _ => StatusCode::Unknown,
+        if s.len() != 3 || !s.iter().all(|c| c.is_ascii_digit()) {
+            return StatusCode::Unknown;
+        }
+        let code = s.iter().fold(0, |acc, c| acc * 10 + (c - b'0') as u16);
+        StatusCode::from_code(code).unwrap_or(StatusCode::Unknown)
+    }
+
+    pub fn class(&self) -> Option<StatusClass> {
+        match self.code() {
+            100..=199 => Some(StatusClass::Provisional),
+            200..=299 => Some(StatusClass::Success),
+            300..=399 => Some(StatusClass::Redirection),
+            400..=499 => Some(StatusClass::RequestFailure),
+            500..=599 => Some(StatusClass::ServerFailure),
+            600..=699 => Some(StatusClass::GlobalFailure),
+            _ => None,
         }
     }
 
-    pub fn reason_phrase(&self) -> &str {
+    /// 1xx
+    pub fn is_provisional(&self) -> bool {
+        self.class() == Some(StatusClass::Provisional)
+    }
+
+    /// 2xx
+    pub fn is_success(&self) -> bool {
+        self.class() == Some(StatusClass::Success)
+    }
+
+    /// 3xx
+    pub fn is_redirection(&self) -> bool {
+        self.class() == Some(StatusClass::Redirection)
+    }
+
+    /// 4xx, 5xx or 6xx
+    pub fn is_error(&self) -> bool {
+        self.code() >= 400 && self.class().is_some()
+    }
+
+    /// 2xx-6xx
+    pub fn is_final(&self) -> bool {
+        self.code() >= 200 && self.class().is_some()
+    }
+
+    /// Default reason phrase.
+    /// Unregistered codes get reason phrase of x00 code of the same class
+    /// ([rfc3261 section-8.1.3.2](https://tools.ietf.org/html/rfc3261#section-8.1.3.2))
+    pub fn reason_phrase(&self) -> &'static str {
         match self {
-            &StatusCode::Trying => "Trying",
-            &StatusCode::Ringing => "Ringing",
-            &StatusCode::CallIsBeingForwarded => "Call Is Being Forwarded",
-            &StatusCode::Queued => "Queued",
-            &StatusCode::SessionProgress => "Session Progress",
-            &StatusCode::OK => "OK",
-            &StatusCode::MultipleChoices => "Multiple Choices",
-            &StatusCode::MovedPermanently => "Moved Permanently",
-            &StatusCode::MovedTemporarily => "Moved Temporarily",
-            &StatusCode::UseProxy => "Use Proxy",
-            &StatusCode::AlternativeService => "Alternative Service",
-            &StatusCode::BadRequest => "Bad Request",
-            &StatusCode::Unauthorized => "Unauthorized",
-            &StatusCode::PaymentRequired => "Payment Required",
-            &StatusCode::Forbidden => "Forbidden",
-            &StatusCode::NotFound => "Not Found",
-            &StatusCode::MethodNotAllowed => "Method Not Allowed",
-            &StatusCode::NotAcceptableResourceContent => "Not Acceptable",
-            &StatusCode::ProxyAuthenticationRequired => "Proxy Authentication Required",
-            &StatusCode::RequestTimeout => "Request Timeout",
-            &StatusCode::Gone => "Gone",
-            &StatusCode::RequestEntityTooLarge => "Request Entity Too Large",
-            &StatusCode::RequestUriTooLong => "Request-URI Too Long",
-            &StatusCode::UnsupportedMediaType => "Unsupported Media Type",
-            &StatusCode::UnsupportedUriScheme => "Unsupported URI Scheme",
-            &StatusCode::BadExtension => "Bad Extension",
-            &StatusCode::ExtensionRequired => "Extension Required",
-            &StatusCode::IntervalTooBrief => "Interval Too Brief",
-            &StatusCode::TemporarilyUnavailable => "Temporarily Unavailable",
-            &StatusCode::CallOrTransactionDoesNotExist => "Call/Transaction Does Not Exist",
-            &StatusCode::LoopDetected => "Loop Detected",
-            &StatusCode::TooManyHops => "Too Many Hops",
-            &StatusCode::AddressIncomplete => "Address Incomplete",
-            &StatusCode::Ambiguous => "Ambiguous",
-            &StatusCode::BusyHere => "Busy Here",
-            &StatusCode::RequestTerminated => "Request Terminated",
-            &StatusCode::NotAcceptableHere => "Not Acceptable Here",
-            &StatusCode::RequestPending => "Request Pending",
-            &StatusCode::Undecipherable => "Undecipherable",
-            &StatusCode::ServerInternalError => "Server Internal Error",
-            &StatusCode::NotImplemented => "Not Implemented",
-            &StatusCode::BadGateway => "Bad Gateway",
-            &StatusCode::ServiceUnavailable => "Service Unavailable",
-            &StatusCode::ServerTimeout => "Server Time-out",
-            &StatusCode::VersionNotSupported => "Version Not Supported",
-            &StatusCode::MessageTooLarge => "Message Too Large",
-            &StatusCode::BusyEverywhere => "Busy Everywhere",
-            &StatusCode::Decline => "Decline",
-            &StatusCode::DoesNotExistAnywhere => "Does Not Exist Anywhere",
-            &StatusCode::NotAcceptable => "Not Acceptable",
-            &StatusCode::Unknown => "Unknown",
+            StatusCode::Trying => "Trying",
+            StatusCode::Ringing => "Ringing",
+            StatusCode::CallIsBeingForwarded => "Call Is Being Forwarded",
+            StatusCode::Queued => "Queued",
+            StatusCode::SessionProgress => "Session Progress",
+            StatusCode::EarlyDialogTerminated => "Early Dialog Terminated",
+            StatusCode::OK => "OK",
+            StatusCode::Accepted => "Accepted",
+            StatusCode::NoNotification => "No Notification",
+            StatusCode::MultipleChoices => "Multiple Choices",
+            StatusCode::MovedPermanently => "Moved Permanently",
+            StatusCode::MovedTemporarily => "Moved Temporarily",
+            StatusCode::UseProxy => "Use Proxy",
+            StatusCode::AlternativeService => "Alternative Service",
+            StatusCode::BadRequest => "Bad Request",
+            StatusCode::Unauthorized => "Unauthorized",
+            StatusCode::PaymentRequired => "Payment Required",
+            StatusCode::Forbidden => "Forbidden",
+            StatusCode::NotFound => "Not Found",
+            StatusCode::MethodNotAllowed => "Method Not Allowed",
+            StatusCode::NotAcceptableResourceContent => "Not Acceptable",
+            StatusCode::ProxyAuthenticationRequired => "Proxy Authentication Required",
+            StatusCode::RequestTimeout => "Request Timeout",
+            StatusCode::Gone => "Gone",
+            StatusCode::ConditionalRequestFailed => "Conditional Request Failed",
+            StatusCode::RequestEntityTooLarge => "Request Entity Too Large",
+            StatusCode::RequestUriTooLong => "Request-URI Too Long",
+            StatusCode::UnsupportedMediaType => "Unsupported Media Type",
+            StatusCode::UnsupportedUriScheme => "Unsupported URI Scheme",
+            StatusCode::UnknownResourcePriority => "Unknown Resource-Priority",
+            StatusCode::BadExtension => "Bad Extension",
+            StatusCode::ExtensionRequired => "Extension Required",
+            StatusCode::SessionIntervalTooSmall => "Session Interval Too Small",
+            StatusCode::IntervalTooBrief => "Interval Too Brief",
+            StatusCode::BadLocationInformation => "Bad Location Information",
+            StatusCode::BadAlertMessage => "Bad Alert Message",
+            StatusCode::UseIdentityHeader => "Use Identity Header",
+            StatusCode::ProvideReferrerIdentity => "Provide Referrer Identity",
+            StatusCode::FlowFailed => "Flow Failed",
+            StatusCode::AnonymityDisallowed => "Anonymity Disallowed",
+            StatusCode::BadIdentityInfo => "Bad Identity Info",
+            StatusCode::UnsupportedCredential => "Unsupported Credential",
+            StatusCode::InvalidIdentityHeader => "Invalid Identity Header",
+            StatusCode::FirstHopLacksOutboundSupport => "First Hop Lacks Outbound Support",
+            StatusCode::MaxBreadthExceeded => "Max-Breadth Exceeded",
+            StatusCode::BadInfoPackage => "Bad Info Package",
+            StatusCode::ConsentNeeded => "Consent Needed",
+            StatusCode::TemporarilyUnavailable => "Temporarily Unavailable",
+            StatusCode::CallOrTransactionDoesNotExist => "Call/Transaction Does Not Exist",
+            StatusCode::LoopDetected => "Loop Detected",
+            StatusCode::TooManyHops => "Too Many Hops",
+            StatusCode::AddressIncomplete => "Address Incomplete",
+            StatusCode::Ambiguous => "Ambiguous",
+            StatusCode::BusyHere => "Busy Here",
+            StatusCode::RequestTerminated => "Request Terminated",
+            StatusCode::NotAcceptableHere => "Not Acceptable Here",
+            StatusCode::BadEvent => "Bad Event",
+            StatusCode::RequestPending => "Request Pending",
+            StatusCode::Undecipherable => "Undecipherable",
+            StatusCode::SecurityAgreementRequired => "Security Agreement Required",
+            StatusCode::ServerInternalError => "Server Internal Error",
+            StatusCode::NotImplemented => "Not Implemented",
+            StatusCode::BadGateway => "Bad Gateway",
+            StatusCode::ServiceUnavailable => "Service Unavailable",
+            StatusCode::ServerTimeout => "Server Time-out",
+            StatusCode::VersionNotSupported => "Version Not Supported",
+            StatusCode::MessageTooLarge => "Message Too Large",
+            StatusCode::PushNotificationServiceNotSupported => {
+                "Push Notification Service Not Supported"
+            }
+            StatusCode::PreconditionFailure => "Precondition Failure",
+            StatusCode::BusyEverywhere => "Busy Everywhere",
+            StatusCode::Decline => "Decline",
+            StatusCode::DoesNotExistAnywhere => "Does Not Exist Anywhere",
+            StatusCode::NotAcceptable => "Not Acceptable",
+            StatusCode::Unwanted => "Unwanted",
+            StatusCode::Rejected => "Rejected",
+            StatusCode::Other(code) => match StatusCode::from_code(code.code() / 100 * 100) {
+                Ok(class_code) => class_code.reason_phrase(),
+                Err(_) => "Unknown",
+            },
+            StatusCode::Unknown => "Unknown",
         }
     }
 }
@@ -0,0 +1,395 @@
+use super::sdp_error;
+use crate::SipParseError;
+use alloc::vec::Vec;
+use core::{fmt, str};
+
+/// Media direction attribute
+/// ([rfc8866 section-6.7](https://tools.ietf.org/html/rfc8866#section-6.7))
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Direction {
+    SendRecv,
+    SendOnly,
+    RecvOnly,
+    Inactive,
+}
+
+impl Direction {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Direction::SendRecv => "sendrecv",
+            Direction::SendOnly => "sendonly",
+            Direction::RecvOnly => "recvonly",
+            Direction::Inactive => "inactive",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Direction> {
+        match s {
+            "sendrecv" => Some(Direction::SendRecv),
+            "sendonly" => Some(Direction::SendOnly),
+            "recvonly" => Some(Direction::RecvOnly),
+            "inactive" => Some(Direction::Inactive),
+            _ => None,
+        }
+    }
+
+    pub fn from_flags(send: bool, recv: bool) -> Direction {
+        match (send, recv) {
+            (true, true) => Direction::SendRecv,
+            (true, false) => Direction::SendOnly,
+            (false, true) => Direction::RecvOnly,
+            (false, false) => Direction::Inactive,
+        }
+    }
+
+    pub fn can_send(&self) -> bool {
+        matches!(self, Direction::SendRecv | Direction::SendOnly)
+    }
+
+    pub fn can_recv(&self) -> bool {
+        matches!(self, Direction::SendRecv | Direction::RecvOnly)
+    }
+
+    /// Direction seen by the other side: sendonly becomes recvonly and vice versa
+    pub fn reverse(&self) -> Direction {
+        Direction::from_flags(self.can_recv(), self.can_send())
+    }
+
+    /// Directions allowed by both
+    pub fn intersect(&self, other: Direction) -> Direction {
+        Direction::from_flags(
+            self.can_send() && other.can_send(),
+            self.can_recv() && other.can_recv(),
+        )
+    }
+}
+
+/// a=rtpmap:<payload type> <encoding name>/<clock rate>[/<encoding parameters>]
+#[derive(Clone, PartialEq, Debug)]
+pub struct RtpMap<'a> {
+    pub payload_type: u8,
+    pub encoding: &'a str,
+    pub clock_rate: u32,
+    /// Number of audio channels
+    pub encoding_params: Option<&'a str>,
+}
+
+impl<'a> RtpMap<'a> {
+    fn parse(value: &'a str) -> Option<RtpMap<'a>> {
+        let (payload_type, encoding) = value.split_once(' ')?;
+        let mut parts = encoding.trim().splitn(3, '/');
+        Some(RtpMap {
+            payload_type: payload_type.parse().ok()?,
+            encoding: parts.next().filter(|s| !s.is_empty())?,
+            clock_rate: parts.next()?.parse().ok()?,
+            encoding_params: parts.next(),
+        })
+    }
+}
+
+impl<'a> fmt::Display for RtpMap<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {}/{}",
+            self.payload_type, self.encoding, self.clock_rate
+        )?;
+        if let Some(params) = self.encoding_params {
+            write!(f, "/{}", params)?;
+        }
+        Ok(())
+    }
+}
+
+/// a=fmtp:<format> <format specific parameters>
+#[derive(Clone, PartialEq, Debug)]
+pub struct Fmtp<'a> {
+    pub format: &'a str,
+    pub params: &'a str,
+}
+
+/// ICE candidate ([rfc8839 section-5.1](https://tools.ietf.org/html/rfc8839#section-5.1))
+#[derive(Clone, PartialEq, Debug)]
+pub struct Candidate<'a> {
+    pub foundation: &'a str,
+    pub component: u16,
+    pub transport: &'a str,
+    pub priority: u32,
+    pub address: &'a str,
+    pub port: u16,
+    /// host, srflx, prflx or relay
+    pub typ: &'a str,
+    pub related_address: Option<&'a str>,
+    pub related_port: Option<u16>,
+    /// Extension attributes, e.g. `generation 0`
+    pub extensions: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Candidate<'a> {
+    fn parse(value: &'a str) -> Option<Candidate<'a>> {
+        let mut fields = value.split(' ').filter(|s| !s.is_empty());
+        let mut candidate = Candidate {
+            foundation: fields.next()?,
+            component: fields.next()?.parse().ok()?,
+            transport: fields.next()?,
+            priority: fields.next()?.parse().ok()?,
+            address: fields.next()?,
+            port: fields.next()?.parse().ok()?,
+            typ: match fields.next()? {
+                "typ" => fields.next()?,
+                _ => return None,
+            },
+            related_address: None,
+            related_port: None,
+            extensions: Vec::new(),
+        };
+        while let Some(name) = fields.next() {
+            let value = fields.next()?;
+            match name {
+                "raddr" => candidate.related_address = Some(value),
+                "rport" => candidate.related_port = Some(value.parse().ok()?),
+                _ => candidate.extensions.push((name, value)),
+            }
+        }
+        Some(candidate)
+    }
+}
+
+impl<'a> fmt::Display for Candidate<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {} typ {}",
+            self.foundation,
+            self.component,
+            self.transport,
+            self.priority,
+            self.address,
+            self.port,
+            self.typ
+        )?;
+        if let Some(address) = self.related_address {
+            write!(f, " raddr {}", address)?;
+        }
+        if let Some(port) = self.related_port {
+            write!(f, " rport {}", port)?;
+        }
+        for (name, value) in self.extensions.iter() {
+            write!(f, " {} {}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// SDES crypto attribute ([rfc4568 section-9.1](https://tools.ietf.org/html/rfc4568#section-9.1))
+#[derive(Clone, PartialEq, Debug)]
+pub struct Crypto<'a> {
+    pub tag: u32,
+    pub suite: &'a str,
+    /// Key parameters, e.g. `inline:<key||salt>|2^20|1:32`
+    pub key_params: &'a str,
+    pub session_params: Vec<&'a str>,
+}
+
+impl<'a> Crypto<'a> {
+    fn parse(value: &'a str) -> Option<Crypto<'a>> {
+        let mut fields = value.split(' ').filter(|s| !s.is_empty());
+        Some(Crypto {
+            tag: fields.next()?.parse().ok()?,
+            suite: fields.next()?,
+            key_params: fields.next()?,
+            session_params: fields.collect(),
+        })
+    }
+}
+
+impl<'a> fmt::Display for Crypto<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.tag, self.suite, self.key_params)?;
+        for param in self.session_params.iter() {
+            write!(f, " {}", param)?;
+        }
+        Ok(())
+    }
+}
+
+/// a=<attribute>[:<value>]
+#[derive(Clone, PartialEq, Debug)]
+pub enum Attribute<'a> {
+    RtpMap(RtpMap<'a>),
+    Fmtp(Fmtp<'a>),
+    /// Packet time in milliseconds, it may be fractional, e.g. `22.5`
+    Ptime(f32),
+    Direction(Direction),
+    Candidate(Candidate<'a>),
+    Crypto(Crypto<'a>),
+    /// Attribute that is not known by parser or known attribute
+    /// with malformed value, name and value
+    Other(&'a str, Option<&'a str>),
+}
+
+impl<'a> Attribute<'a> {
+    pub fn name(&self) -> &str {
+        match self {
+            Attribute::RtpMap(_) => "rtpmap",
+            Attribute::Fmtp(_) => "fmtp",
+            Attribute::Ptime(_) => "ptime",
+            Attribute::Direction(direction) => direction.as_str(),
+            Attribute::Candidate(_) => "candidate",
+            Attribute::Crypto(_) => "crypto",
+            Attribute::Other(name, _) => name,
+        }
+    }
+
+    /// Parses value of `a=` line.
+    /// Known attribute with malformed value is kept as [`Attribute::Other`]
+    pub fn parse(line: &'a str) -> Result<Attribute<'a>, SipParseError<'a>> {
+        let (name, value) = match line.split_once(':') {
+            Some((name, value)) => (name, Some(value)),
+            None => (line, None),
+        };
+        if name.is_empty() {
+            return Err(sdp_error(20, "Empty attribute name", line));
+        }
+        let attribute = match (name, value) {
+            ("rtpmap", Some(value)) => RtpMap::parse(value).map(Attribute::RtpMap),
+            ("fmtp", Some(value)) => value.split_once(' ').map(|(format, params)| {
+                Attribute::Fmtp(Fmtp {
+                    format,
+                    params: params.trim(),
+                })
+            }),
+            ("ptime", Some(value)) => parse_ptime(value.trim()).map(Attribute::Ptime),
+            ("candidate", Some(value)) => Candidate::parse(value).map(Attribute::Candidate),
+            ("crypto", Some(value)) => Crypto::parse(value).map(Attribute::Crypto),
+            (name, None) => Direction::from_str(name).map(Attribute::Direction),
+            _ => None,
+        };
+        Ok(attribute.unwrap_or(Attribute::Other(name, value)))
+    }
+}
+
+// ptime-value  =  non-zero-int-or-real
+fn parse_ptime(value: &str) -> Option<f32> {
+    let (int, fraction) = match value.split_once('.') {
+        Some((int, fraction)) => (int, fraction),
+        None => (value, "0"),
+    };
+    if int.is_empty()
+        || fraction.is_empty()
+        || !int
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    value.parse().ok().filter(|ptime| *ptime > 0.0)
+}
+
+impl<'a> fmt::Display for Attribute<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Attribute::RtpMap(rtpmap) => write!(f, "rtpmap:{}", rtpmap),
+            Attribute::Fmtp(fmtp) => write!(f, "fmtp:{} {}", fmtp.format, fmtp.params),
+            Attribute::Ptime(ptime) => write!(f, "ptime:{}", ptime),
+            Attribute::Direction(direction) => f.write_str(direction.as_str()),
+            Attribute::Candidate(candidate) => write!(f, "candidate:{}", candidate),
+            Attribute::Crypto(crypto) => write!(f, "crypto:{}", crypto),
+            Attribute::Other(name, Some(value)) => write!(f, "{}:{}", name, value),
+            Attribute::Other(name, None) => f.write_str(name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn round_trip(line: &str) -> Attribute<'_> {
+        let attribute = Attribute::parse(line).unwrap();
+        assert_eq!(attribute.to_string(), line);
+        attribute
+    }
+
+    #[test]
+    fn test_sdp_attributes() {
+        assert_eq!(
+            round_trip("rtpmap:111 opus/48000/2"),
+            Attribute::RtpMap(RtpMap {
+                payload_type: 111,
+                encoding: "opus",
+                clock_rate: 48000,
+                encoding_params: Some("2"),
+            })
+        );
+        assert_eq!(
+            round_trip("fmtp:101 0-15"),
+            Attribute::Fmtp(Fmtp {
+                format: "101",
+                params: "0-15"
+            })
+        );
+        assert_eq!(round_trip("ptime:20"), Attribute::Ptime(20.0));
+        assert_eq!(round_trip("ptime:22.5"), Attribute::Ptime(22.5));
+        assert_eq!(
+            round_trip("recvonly"),
+            Attribute::Direction(Direction::RecvOnly)
+        );
+        assert_eq!(round_trip("rtcp-mux"), Attribute::Other("rtcp-mux", None));
+        assert_eq!(
+            round_trip("x-custom:a b:c"),
+            Attribute::Other("x-custom", Some("a b:c"))
+        );
+
+        let candidate = round_trip(
+            "candidate:842163049 1 udp 1677729535 203.0.113.7 46154 typ srflx \
+             raddr 10.0.1.1 rport 8998 generation 0",
+        );
+        match candidate {
+            Attribute::Candidate(candidate) => {
+                assert_eq!(candidate.component, 1);
+                assert_eq!(candidate.priority, 1677729535);
+                assert_eq!(candidate.typ, "srflx");
+                assert_eq!(candidate.related_address, Some("10.0.1.1"));
+                assert_eq!(candidate.related_port, Some(8998));
+                assert_eq!(candidate.extensions, [("generation", "0")]);
+            }
+            _ => panic!("candidate expected"),
+        }
+
+        let crypto = round_trip(
+            "crypto:1 AES_CM_128_HMAC_SHA1_80 \
+             inline:PS1uQCVeeCFCanVmcjkpPywjNWhcYD0mXXtxaVBR|2^20|1:32 KDR=1",
+        );
+        match crypto {
+            Attribute::Crypto(crypto) => {
+                assert_eq!(crypto.tag, 1);
+                assert_eq!(crypto.suite, "AES_CM_128_HMAC_SHA1_80");
+                assert_eq!(crypto.session_params, ["KDR=1"]);
+            }
+            _ => panic!("crypto expected"),
+        }
+
+        // Malformed values of known attributes are tolerated
+        for invalid in [
+            "rtpmap:abc PCMU/8000",
+            "rtpmap:0 PCMU",
+            "ptime",
+            "ptime:x",
+            "ptime:0",
+            "ptime:.5",
+            "ptime:-1",
+            "candidate:1 1 udp 1 10.0.0.1 5000 host",
+            "crypto:1 AES_CM_128_HMAC_SHA1_80",
+        ]
+        .iter()
+        {
+            let attribute = round_trip(invalid);
+            assert!(matches!(attribute, Attribute::Other(..)), "{}", invalid);
+        }
+        assert!(Attribute::parse(":value").is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use super::{
+    attribute::{Attribute, Direction, Fmtp, RtpMap},
+    sdp_error, Bandwidth, Connection,
+};
+use crate::SipParseError;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Media description that starts with `m=` line
+/// ([rfc8866 section-5.14](https://tools.ietf.org/html/rfc8866#section-5.14))
+#[derive(Clone, PartialEq, Debug)]
+pub struct MediaDescription<'a> {
+    /// audio, video, text, application or message
+    pub media: &'a str,
+    /// Port 0 means rejected or disabled stream
+    pub port: u16,
+    pub num_ports: Option<u16>,
+    /// Transport protocol, e.g. `RTP/AVP`
+    pub protocol: &'a str,
+    /// Payload types for RTP protocols
+    pub formats: Vec<&'a str>,
+    pub information: Option<&'a str>,
+    pub connections: Vec<Connection<'a>>,
+    pub bandwidths: Vec<Bandwidth<'a>>,
+    pub key: Option<&'a str>,
+    pub attributes: Vec<Attribute<'a>>,
+}
+
+impl<'a> MediaDescription<'a> {
+    /// m=<media> <port>[/<number of ports>] <proto> <fmt> ...
+    pub(super) fn parse_media_line(
+        value: &'a str,
+    ) -> Result<MediaDescription<'a>, SipParseError<'a>> {
+        let invalid = || sdp_error(30, "Invalid media line", value);
+        let mut fields = value.split(' ').filter(|s| !s.is_empty());
+        let media = fields.next().ok_or_else(invalid)?;
+        let port = fields.next().ok_or_else(invalid)?;
+        let (port, num_ports) = match port.split_once('/') {
+            Some((port, num_ports)) => (port, Some(num_ports)),
+            None => (port, None),
+        };
+        let port = port.parse().map_err(|_| invalid())?;
+        let num_ports = match num_ports {
+            Some(num_ports) => Some(num_ports.parse().map_err(|_| invalid())?),
+            None => None,
+        };
+        let protocol = fields.next().ok_or_else(invalid)?;
+        Ok(MediaDescription {
+            media,
+            port,
+            num_ports,
+            protocol,
+            formats: fields.collect(),
+            information: None,
+            connections: Vec::new(),
+            bandwidths: Vec::new(),
+            key: None,
+            attributes: Vec::new(),
+        })
+    }
+
+    /// The first attribute with name
+    pub fn attribute(&self, name: &str) -> Option<&Attribute<'a>> {
+        self.attributes.iter().find(|a| a.name() == name)
+    }
+
+    /// rtpmap attribute of payload type
+    pub fn rtpmap(&self, payload_type: u8) -> Option<&RtpMap<'a>> {
+        self.attributes.iter().find_map(|a| match a {
+            Attribute::RtpMap(rtpmap) if rtpmap.payload_type == payload_type => Some(rtpmap),
+            _ => None,
+        })
+    }
+
+    /// fmtp attribute of format
+    pub fn fmtp(&self, format: &str) -> Option<&Fmtp<'a>> {
+        self.attributes.iter().find_map(|a| match a {
+            Attribute::Fmtp(fmtp) if fmtp.format == format => Some(fmtp),
+            _ => None,
+        })
+    }
+
+    /// Stream is rejected or disabled by port 0
+    pub fn is_rejected(&self) -> bool {
+        self.port == 0
+    }
+
+    /// Replaces direction attribute of media or adds it
+    pub fn set_direction(&mut self, direction: Direction) {
+        let attribute = Attribute::Direction(direction);
+        match self
+            .attributes
+            .iter_mut()
+            .find(|a| matches!(a, Attribute::Direction(_)))
+        {
+            Some(a) => *a = attribute,
+            None => self.attributes.push(attribute),
+        }
+    }
+
+    /// Direction attribute of media. `None` if it is not set at media level
+    pub fn direction(&self) -> Option<Direction> {
+        self.attributes.iter().find_map(|a| match a {
+            Attribute::Direction(direction) => Some(*direction),
+            _ => None,
+        })
+    }
+}
+
+impl<'a> fmt::Display for MediaDescription<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "m={} {}", self.media, self.port)?;
+        if let Some(num_ports) = self.num_ports {
+            write!(f, "/{}", num_ports)?;
+        }
+        write!(f, " {}", self.protocol)?;
+        for format in self.formats.iter() {
+            write!(f, " {}", format)?;
+        }
+        f.write_str("\r\n")?;
+        if let Some(information) = self.information {
+            write!(f, "i={}\r\n", information)?;
+        }
+        for connection in self.connections.iter() {
+            write!(f, "c={}\r\n", connection)?;
+        }
+        for bandwidth in self.bandwidths.iter() {
+            write!(f, "b={}\r\n", bandwidth)?;
+        }
+        if let Some(key) = self.key {
+            write!(f, "k={}\r\n", key)?;
+        }
+        for attribute in self.attributes.iter() {
+            write!(f, "a={}\r\n", attribute)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,530 @@
+//! Session Description Protocol body
+//! ([rfc8866](https://tools.ietf.org/html/rfc8866))
+//! and offer/answer negotiation, see [`OfferAnswer`].
+//!
+//! ```rust
+//! use sipmsg::sdp::{Attribute, Direction, SessionDescription};
+//!
+//! let body = "v=0\r\n\
+//!     o=alice 2890844526 2890844526 IN IP4 atlanta.com\r\n\
+//!     s=-\r\n\
+//!     c=IN IP4 192.0.2.101\r\n\
+//!     t=0 0\r\n\
+//!     m=audio 49172 RTP/AVP 0 101\r\n\
+//!     a=rtpmap:0 PCMU/8000\r\n\
+//!     a=rtpmap:101 telephone-event/8000\r\n\
+//!     a=fmtp:101 0-15\r\n\
+//!     a=sendonly\r\n\
+//!     a=x-unknown:value\r\n";
+//! let (_, sdp) = SessionDescription::parse(body.as_bytes()).unwrap();
+//! assert_eq!(sdp.origin.session_version, 2890844526);
+//! assert_eq!(sdp.connection.as_ref().unwrap().address, "192.0.2.101");
+//!
+//! let audio = &sdp.media[0];
+//! assert_eq!(audio.port, 49172);
+//! assert_eq!(audio.formats, ["0", "101"]);
+//! assert_eq!(audio.rtpmap(101).unwrap().encoding, "telephone-event");
+//! assert_eq!(audio.fmtp("101").unwrap().params, "0-15");
+//! assert_eq!(audio.direction(), Some(Direction::SendOnly));
+//! assert_eq!(audio.attributes[4], Attribute::Other("x-unknown", Some("value")));
+//!
+//! assert_eq!(sdp.to_string(), body);
+//! ```
+use crate::{SipHeaders, SipParseError, SipParseErrorKind, SipRFCHeader};
+use alloc::vec::Vec;
+use core::{fmt, str};
+use unicase::Ascii;
+
+mod attribute;
+pub use attribute::{Attribute, Candidate, Crypto, Direction, Fmtp, RtpMap};
+
+mod media;
+pub use media::MediaDescription;
+
+mod offer_answer;
+pub use offer_answer::OfferAnswer;
+
+/// Error of SDP line or its part
+fn sdp_error<'a>(code: u32, message: &'a str, element: &'a str) -> SipParseError<'a> {
+    SipParseError::new(code, Some(message)).with_kind(SipParseErrorKind::Body, element.as_bytes())
+}
+
+/// o=<username> <sess-id> <sess-version> <nettype> <addrtype> <unicast-address>
+#[derive(Clone, PartialEq, Debug)]
+pub struct Origin<'a> {
+    pub username: &'a str,
+    pub session_id: &'a str,
+    /// Incremented when session description is modified
+    pub session_version: u64,
+    pub net_type: &'a str,
+    pub addr_type: &'a str,
+    pub address: &'a str,
+}
+
+impl<'a> Origin<'a> {
+    fn parse(value: &'a str) -> Result<Origin<'a>, SipParseError<'a>> {
+        let invalid = || sdp_error(10, "Invalid origin line", value);
+        let mut fields = value.split(' ').filter(|s| !s.is_empty());
+        let mut field = || fields.next().ok_or_else(invalid);
+        Ok(Origin {
+            username: field()?,
+            session_id: field()?,
+            session_version: field()?.parse().map_err(|_| invalid())?,
+            net_type: field()?,
+            addr_type: field()?,
+            address: field()?,
+        })
+    }
+}
+
+impl<'a> fmt::Display for Origin<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            self.username,
+            self.session_id,
+            self.session_version,
+            self.net_type,
+            self.addr_type,
+            self.address
+        )
+    }
+}
+
+/// c=<nettype> <addrtype> <connection-address>
+#[derive(Clone, PartialEq, Debug)]
+pub struct Connection<'a> {
+    pub net_type: &'a str,
+    pub addr_type: &'a str,
+    pub address: &'a str,
+    /// TTL of IP4 multicast address
+    pub ttl: Option<u8>,
+    /// Number of multicast addresses
+    pub num_addresses: Option<u32>,
+}
+
+impl<'a> Connection<'a> {
+    fn parse(value: &'a str) -> Result<Connection<'a>, SipParseError<'a>> {
+        let invalid = || sdp_error(11, "Invalid connection line", value);
+        let mut fields = value.split(' ').filter(|s| !s.is_empty());
+        let net_type = fields.next().ok_or_else(invalid)?;
+        let addr_type = fields.next().ok_or_else(invalid)?;
+        let mut address = fields.next().ok_or_else(invalid)?.split('/');
+        let mut connection = Connection {
+            net_type,
+            addr_type,
+            address: address.next().ok_or_else(invalid)?,
+            ttl: None,
+            num_addresses: None,
+        };
+        if addr_type == "IP4" {
+            if let Some(ttl) = address.next() {
+                connection.ttl = Some(ttl.parse().map_err(|_| invalid())?);
+            }
+        }
+        if let Some(num_addresses) = address.next() {
+            connection.num_addresses = Some(num_addresses.parse().map_err(|_| invalid())?);
+        }
+        if fields.next().is_some() || address.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(connection)
+    }
+}
+
+impl<'a> fmt::Display for Connection<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.net_type, self.addr_type, self.address)?;
+        if let Some(ttl) = self.ttl {
+            write!(f, "/{}", ttl)?;
+        }
+        if let Some(num_addresses) = self.num_addresses {
+            write!(f, "/{}", num_addresses)?;
+        }
+        Ok(())
+    }
+}
+
+/// b=<bwtype>:<bandwidth>
+#[derive(Clone, PartialEq, Debug)]
+pub struct Bandwidth<'a> {
+    /// CT, AS, TIAS or other
+    pub bwtype: &'a str,
+    /// Kilobits per second for CT and AS
+    pub bandwidth: u64,
+}
+
+impl<'a> Bandwidth<'a> {
+    fn parse(value: &'a str) -> Result<Bandwidth<'a>, SipParseError<'a>> {
+        match value.split_once(':') {
+            Some((bwtype, bandwidth)) if !bwtype.is_empty() => match bandwidth.parse() {
+                Ok(bandwidth) => Ok(Bandwidth { bwtype, bandwidth }),
+                Err(_) => Err(sdp_error(12, "Invalid bandwidth line", value)),
+            },
+            _ => Err(sdp_error(12, "Invalid bandwidth line", value)),
+        }
+    }
+}
+
+impl<'a> fmt::Display for Bandwidth<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.bwtype, self.bandwidth)
+    }
+}
+
+/// t=<start-time> <stop-time> with following r= lines
+#[derive(Clone, PartialEq, Debug)]
+pub struct Timing<'a> {
+    /// NTP time, 0 is unbounded
+    pub start: u64,
+    pub stop: u64,
+    pub repeats: Vec<&'a str>,
+}
+
+impl<'a> Timing<'a> {
+    fn parse(value: &'a str) -> Result<Timing<'a>, SipParseError<'a>> {
+        let mut fields = value.split(' ').filter(|s| !s.is_empty());
+        match (
+            fields.next().and_then(|t| t.parse().ok()),
+            fields.next().and_then(|t| t.parse().ok()),
+            fields.next(),
+        ) {
+            (Some(start), Some(stop), None) => Ok(Timing {
+                start,
+                stop,
+                repeats: Vec::new(),
+            }),
+            _ => Err(sdp_error(13, "Invalid timing line", value)),
+        }
+    }
+}
+
+/// Session description with media descriptions
+#[derive(Clone, PartialEq, Debug)]
+pub struct SessionDescription<'a> {
+    /// Protocol version, always 0
+    pub version: u8,
+    pub origin: Origin<'a>,
+    pub session_name: &'a str,
+    pub information: Option<&'a str>,
+    pub uri: Option<&'a str>,
+    pub emails: Vec<&'a str>,
+    pub phones: Vec<&'a str>,
+    /// Session level connection data, it is used by media without `c=` line
+    pub connection: Option<Connection<'a>>,
+    pub bandwidths: Vec<Bandwidth<'a>>,
+    pub times: Vec<Timing<'a>>,
+    /// Time zone adjustments of `z=` line
+    pub time_zones: Option<&'a str>,
+    pub key: Option<&'a str>,
+    pub attributes: Vec<Attribute<'a>>,
+    pub media: Vec<MediaDescription<'a>>,
+}
+
+impl<'a> SessionDescription<'a> {
+    /// Parses the whole body. Unknown attributes are kept as [`Attribute::Other`],
+    /// unknown line types are rejected
+    /// ([rfc8866 section-5](https://tools.ietf.org/html/rfc8866#section-5))
+    pub fn parse(
+        input: &'a [u8],
+    ) -> nom::IResult<&'a [u8], SessionDescription<'a>, SipParseError<'a>> {
+        match SessionDescription::parse_lines(input) {
+            Ok(sdp) => Ok((&input[input.len()..], sdp)),
+            Err(err) => Err(nom::Err::Error(err)),
+        }
+    }
+
+    /// Session description of message body if Content-Type is `application/sdp`.
+    /// Returns `Ok(None)` for other bodies
+    pub fn from_body(
+        headers: &SipHeaders<'a>,
+        body: Option<&'a [u8]>,
+    ) -> Result<Option<SessionDescription<'a>>, SipParseError<'a>> {
+        let is_sdp = headers
+            .get_rfc_s(SipRFCHeader::ContentType)
+            .is_some_and(|hdr| Ascii::new(hdr.value.vstr.trim()) == "application/sdp");
+        match body {
+            Some(body) if is_sdp && !body.is_empty() => {
+                let (_, sdp) = SessionDescription::parse(body)?;
+                Ok(Some(sdp))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn parse_lines(input: &'a [u8]) -> Result<SessionDescription<'a>, SipParseError<'a>> {
+        let body = str::from_utf8(input).map_err(|err| {
+            SipParseError::new(1, Some("SDP is not valid UTF-8"))
+                .with_kind(SipParseErrorKind::Body, &input[err.valid_up_to()..])
+        })?;
+        let mut lines = body
+            .split('\n')
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .filter(|line| !line.is_empty());
+
+        match lines.next() {
+            Some("v=0") => {}
+            Some(line) => return Err(sdp_error(2, "Unsupported SDP version", line)),
+            None => return Err(sdp_error(2, "Empty SDP", body)),
+        }
+        let mut origin = None;
+        let mut session_name = None;
+        let mut sdp = SessionDescription {
+            version: 0,
+            // Replaced by mandatory o= line
+            origin: Origin {
+                username: "",
+                session_id: "",
+                session_version: 0,
+                net_type: "",
+                addr_type: "",
+                address: "",
+            },
+            session_name: "",
+            information: None,
+            uri: None,
+            emails: Vec::new(),
+            phones: Vec::new(),
+            connection: None,
+            bandwidths: Vec::new(),
+            times: Vec::new(),
+            time_zones: None,
+            key: None,
+            attributes: Vec::new(),
+            media: Vec::new(),
+        };
+
+        for line in lines {
+            let bytes = line.as_bytes();
+            if bytes.len() < 2 || bytes[1] != b'=' || !bytes[0].is_ascii_lowercase() {
+                return Err(sdp_error(3, "Invalid SDP line", line));
+            }
+            let value = &line[2..];
+            if bytes[0] == b'm' {
+                sdp.media.push(MediaDescription::parse_media_line(value)?);
+                continue;
+            }
+            if let Some(media) = sdp.media.last_mut() {
+                match bytes[0] {
+                    b'i' => media.information = Some(value),
+                    b'c' => media.connections.push(Connection::parse(value)?),
+                    b'b' => media.bandwidths.push(Bandwidth::parse(value)?),
+                    b'k' => media.key = Some(value),
+                    b'a' => media.attributes.push(Attribute::parse(value)?),
+                    _ => return Err(sdp_error(4, "Unexpected line in media description", line)),
+                }
+                continue;
+            }
+            match bytes[0] {
+                b'o' if origin.is_none() => origin = Some(Origin::parse(value)?),
+                b's' if session_name.is_none() => session_name = Some(value),
+                b'i' => sdp.information = Some(value),
+                b'u' => sdp.uri = Some(value),
+                b'e' => sdp.emails.push(value),
+                b'p' => sdp.phones.push(value),
+                b'c' => sdp.connection = Some(Connection::parse(value)?),
+                b'b' => sdp.bandwidths.push(Bandwidth::parse(value)?),
+                b't' => sdp.times.push(Timing::parse(value)?),
+                b'r' => match sdp.times.last_mut() {
+                    Some(timing) => timing.repeats.push(value),
+                    None => return Err(sdp_error(5, "Repeat line without timing", line)),
+                },
+                b'z' => sdp.time_zones = Some(value),
+                b'k' => sdp.key = Some(value),
+                b'a' => sdp.attributes.push(Attribute::parse(value)?),
+                _ => return Err(sdp_error(4, "Unexpected SDP line", line)),
+            }
+        }
+        match (origin, session_name) {
+            (Some(origin), Some(session_name)) => {
+                sdp.origin = origin;
+                sdp.session_name = session_name;
+                Ok(sdp)
+            }
+            _ => Err(sdp_error(6, "Origin and session name are mandatory", body)),
+        }
+    }
+
+    /// Session level direction attribute
+    pub fn direction(&self) -> Option<Direction> {
+        self.attributes.iter().find_map(|a| match a {
+            Attribute::Direction(direction) => Some(*direction),
+            _ => None,
+        })
+    }
+
+    /// Direction of media: media level attribute, session level attribute
+    /// or `sendrecv` by default
+    pub fn media_direction(&self, media: &MediaDescription) -> Direction {
+        media
+            .direction()
+            .or_else(|| self.direction())
+            .unwrap_or(Direction::SendRecv)
+    }
+
+    /// Connection data of media, media level lines override session level line
+    pub fn media_connection<'m>(
+        &'m self,
+        media: &'m MediaDescription<'a>,
+    ) -> Option<&'m Connection<'a>> {
+        media.connections.first().or(self.connection.as_ref())
+    }
+}
+
+impl<'a> fmt::Display for SessionDescription<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "v={}\r\n", self.version)?;
+        write!(f, "o={}\r\n", self.origin)?;
+        write!(f, "s={}\r\n", self.session_name)?;
+        if let Some(information) = self.information {
+            write!(f, "i={}\r\n", information)?;
+        }
+        if let Some(uri) = self.uri {
+            write!(f, "u={}\r\n", uri)?;
+        }
+        for email in self.emails.iter() {
+            write!(f, "e={}\r\n", email)?;
+        }
+        for phone in self.phones.iter() {
+            write!(f, "p={}\r\n", phone)?;
+        }
+        if let Some(connection) = &self.connection {
+            write!(f, "c={}\r\n", connection)?;
+        }
+        for bandwidth in self.bandwidths.iter() {
+            write!(f, "b={}\r\n", bandwidth)?;
+        }
+        for timing in self.times.iter() {
+            write!(f, "t={} {}\r\n", timing.start, timing.stop)?;
+            for repeat in timing.repeats.iter() {
+                write!(f, "r={}\r\n", repeat)?;
+            }
+        }
+        if let Some(time_zones) = self.time_zones {
+            write!(f, "z={}\r\n", time_zones)?;
+        }
+        if let Some(key) = self.key {
+            write!(f, "k={}\r\n", key)?;
+        }
+        for attribute in self.attributes.iter() {
+            write!(f, "a={}\r\n", attribute)?;
+        }
+        for media in self.media.iter() {
+            write!(f, "{}", media)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_sdp_rfc8866_example() {
+        let body = "v=0\r\n\
+            o=jdoe 3724394400 3724394405 IN IP4 198.51.100.1\r\n\
+            s=Call to John Smith\r\n\
+            i=SDP Offer #1\r\n\
+            u=http://www.jdoe.example.com/home.html\r\n\
+            e=Jane Doe <jane@jdoe.example.com>\r\n\
+            p=+1 617 555-6011\r\n\
+            c=IN IP4 198.51.100.1\r\n\
+            t=0 0\r\n\
+            m=audio 49170 RTP/AVP 0\r\n\
+            m=audio 49180 RTP/AVP 0\r\n\
+            m=video 51372 RTP/AVP 99\r\n\
+            c=IN IP6 2001:db8::2\r\n\
+            a=rtpmap:99 h263-1998/90000\r\n";
+        let (rest, sdp) = SessionDescription::parse(body.as_bytes()).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(sdp.origin.session_id, "3724394400");
+        assert_eq!(sdp.session_name, "Call to John Smith");
+        assert_eq!(sdp.emails, ["Jane Doe <jane@jdoe.example.com>"]);
+        assert_eq!(sdp.times[0].start, 0);
+        assert_eq!(sdp.media.len(), 3);
+        assert_eq!(sdp.media[2].connections[0].addr_type, "IP6");
+        assert_eq!(
+            sdp.media_connection(&sdp.media[0]).unwrap().address,
+            "198.51.100.1"
+        );
+        assert_eq!(
+            sdp.media_connection(&sdp.media[2]).unwrap().address,
+            "2001:db8::2"
+        );
+        assert_eq!(sdp.media_direction(&sdp.media[0]), Direction::SendRecv);
+        assert_eq!(sdp.to_string(), body);
+    }
+
+    #[test]
+    fn test_sdp_webrtc() {
+        let body = "v=0\n\
+            o=- 4611731400430051336 2 IN IP4 127.0.0.1\n\
+            s=-\n\
+            b=AS:256\n\
+            t=0 0\n\
+            r=604800 3600 0 90000\n\
+            a=group:BUNDLE 0\n\
+            a=recvonly\n\
+            m=audio 9/2 UDP/TLS/RTP/SAVPF 111\n\
+            c=IN IP4 224.2.1.1/127/3\n\
+            a=candidate:1 1 udp 2122260223 192.168.1.2 54321 typ host generation 0\n\
+            a=crypto:1 AES_CM_128_HMAC_SHA1_80 inline:d0RmdmcmVCspeEc3QGZiNWpVLFJhQX1cfHAwJSoj|2^31\n\
+            a=rtpmap:111 opus/48000/2\n\
+            a=fmtp:111 minptime=10;useinbandfec=1\n\
+            a=ptime:22.5\n\
+            a=rtpmap:x\n";
+        let (_, sdp) = SessionDescription::parse(body.as_bytes()).unwrap();
+        assert_eq!(sdp.bandwidths[0].bandwidth, 256);
+        assert_eq!(sdp.times[0].repeats, ["604800 3600 0 90000"]);
+        assert_eq!(
+            sdp.attributes[0],
+            Attribute::Other("group", Some("BUNDLE 0"))
+        );
+        let audio = &sdp.media[0];
+        assert_eq!(audio.num_ports, Some(2));
+        assert_eq!(audio.connections[0].ttl, Some(127));
+        assert_eq!(audio.connections[0].num_addresses, Some(3));
+        assert_eq!(sdp.media_direction(audio), Direction::RecvOnly);
+        assert_eq!(audio.attribute("ptime"), Some(&Attribute::Ptime(22.5)));
+        assert_eq!(audio.attributes[5], Attribute::Other("rtpmap", Some("x")));
+        assert!(matches!(audio.attributes[0], Attribute::Candidate(_)));
+        assert!(matches!(audio.attributes[1], Attribute::Crypto(_)));
+        assert_eq!(sdp.to_string(), body.replace('\n', "\r\n"));
+    }
+
+    #[test]
+    fn test_sdp_errors() {
+        for (body, code) in [
+            ("", 2),
+            ("v=1\r\n", 2),
+            ("v=0\r\ns=-\r\n", 6),
+            ("v=0\r\no=- 1 x IN IP4 h\r\ns=-\r\n", 10),
+            ("v=0\r\no=- 1 1 IN IP4 h\r\ns=-\r\nc=IN IP4 h/x\r\n", 11),
+            ("v=0\r\no=- 1 1 IN IP4 h\r\ns=-\r\nx=1\r\n", 4),
+            (
+                "v=0\r\no=- 1 1 IN IP4 h\r\ns=-\r\nm=audio 0 RTP/AVP 0\r\nt=0 0\r\n",
+                4,
+            ),
+            (
+                "v=0\r\no=- 1 1 IN IP4 h\r\ns=-\r\nm=audio x RTP/AVP 0\r\n",
+                30,
+            ),
+            ("v=0\r\no=- 1 1 IN IP4 h\r\ns=-\r\nr=1 1 0\r\n", 5),
+            ("v=0\r\no=- 1 1 IN IP4 h\r\ns=-\r\nbad\r\n", 3),
+        ]
+        .iter()
+        {
+            let err = match SessionDescription::parse(body.as_bytes()) {
+                Err(nom::Err::Error(err)) => err,
+                _ => panic!("error expected: {}", body),
+            };
+            match err {
+                SipParseError::Body(details) => assert_eq!(details.code, *code, "{}", body),
+                _ => panic!("{:?}", err),
+            }
+        }
+    }
+}
@@ -0,0 +1,446 @@
+//! Offer/answer model ([rfc3264](https://tools.ietf.org/html/rfc3264))
+use super::{Attribute, Crypto, Direction, Fmtp, MediaDescription, RtpMap, SessionDescription};
+use alloc::vec::Vec;
+
+/// Encoding of static RTP payload types
+/// ([rfc3551 section-6](https://tools.ietf.org/html/rfc3551#section-6))
+const STATIC_PAYLOAD_TYPES: [(u8, &str, u32); 14] = [
+    (0, "PCMU", 8000),
+    (3, "GSM", 8000),
+    (4, "G723", 8000),
+    (5, "DVI4", 8000),
+    (6, "DVI4", 16000),
+    (7, "LPC", 8000),
+    (8, "PCMA", 8000),
+    (9, "G722", 8000),
+    (13, "CN", 8000),
+    (15, "G728", 8000),
+    (18, "G729", 8000),
+    (26, "JPEG", 90000),
+    (31, "H261", 90000),
+    (34, "H263", 90000),
+];
+
+/// Encoding name, clock rate and channels of RTP format
+fn rtp_codec<'m>(media: &'m MediaDescription, format: &str) -> Option<(&'m str, u32, &'m str)> {
+    let payload_type: u8 = format.parse().ok()?;
+    match media.rtpmap(payload_type) {
+        Some(rtpmap) => Some((
+            rtpmap.encoding,
+            rtpmap.clock_rate,
+            rtpmap.encoding_params.unwrap_or("1"),
+        )),
+        None => STATIC_PAYLOAD_TYPES
+            .iter()
+            .find(|(pt, _, _)| *pt == payload_type)
+            .map(|(_, encoding, clock_rate)| (*encoding, *clock_rate, "1")),
+    }
+}
+
+/// Local format that matches offered format
+fn is_same_format(
+    local: &MediaDescription,
+    local_format: &str,
+    offered: &MediaDescription,
+    offered_format: &str,
+) -> bool {
+    if !offered.protocol.contains("RTP/") {
+        return local_format == offered_format;
+    }
+    match (
+        rtp_codec(local, local_format),
+        rtp_codec(offered, offered_format),
+    ) {
+        (Some(local), Some(offered)) => {
+            local.0.eq_ignore_ascii_case(offered.0) && local.1 == offered.1 && local.2 == offered.2
+        }
+        // Unknown static payload type
+        _ => local_format == offered_format,
+    }
+}
+
+/// Media line with port 0 that rejects offered stream
+fn rejected<'a>(offered: &MediaDescription<'a>) -> MediaDescription<'a> {
+    MediaDescription {
+        media: offered.media,
+        port: 0,
+        num_ports: None,
+        protocol: offered.protocol,
+        formats: offered.formats.clone(),
+        information: None,
+        connections: Vec::new(),
+        bandwidths: Vec::new(),
+        key: None,
+        attributes: Vec::new(),
+    }
+}
+
+/// Answer to offered media based on local capabilities of the same media type
+fn answer_media<'a>(
+    local_sdp: &SessionDescription<'a>,
+    local: &MediaDescription<'a>,
+    offer_sdp: &SessionDescription<'a>,
+    offered: &MediaDescription<'a>,
+) -> MediaDescription<'a> {
+    let mut attributes = Vec::new();
+    let mut formats = Vec::new();
+    // Formats in local order of preference with payload types of offer
+    for local_format in local.formats.iter() {
+        let offered_format = offered
+            .formats
+            .iter()
+            .find(|f| !formats.contains(*f) && is_same_format(local, local_format, offered, f));
+        let offered_format = match offered_format {
+            Some(offered_format) => *offered_format,
+            None => continue,
+        };
+        formats.push(offered_format);
+        let local_rtpmap = local_format.parse().ok().and_then(|pt| local.rtpmap(pt));
+        if let (Some(rtpmap), Ok(payload_type)) = (local_rtpmap, offered_format.parse()) {
+            attributes.push(Attribute::RtpMap(RtpMap {
+                payload_type,
+                ..rtpmap.clone()
+            }));
+        }
+        if let Some(fmtp) = local.fmtp(local_format) {
+            attributes.push(Attribute::Fmtp(Fmtp {
+                format: offered_format,
+                params: fmtp.params,
+            }));
+        }
+    }
+    if formats.is_empty() {
+        return rejected(offered);
+    }
+
+    // The first offered crypto attribute with suite supported locally,
+    // answer uses tag of offer ([rfc4568 section-7.1.2](https://tools.ietf.org/html/rfc4568#section-7.1.2))
+    let crypto = offered.attributes.iter().find_map(|offered| match offered {
+        Attribute::Crypto(offered) => local.attributes.iter().find_map(|local| match local {
+            Attribute::Crypto(local) if local.suite == offered.suite => Some(Crypto {
+                tag: offered.tag,
+                ..local.clone()
+            }),
+            _ => None,
+        }),
+        _ => None,
+    });
+    match crypto {
+        Some(crypto) => attributes.push(Attribute::Crypto(crypto)),
+        None if offered.protocol.contains("SAVP") => return rejected(offered),
+        None => {}
+    }
+
+    attributes.extend(
+        local
+            .attributes
+            .iter()
+            .filter(|a| {
+                !matches!(
+                    a,
+                    Attribute::RtpMap(_)
+                        | Attribute::Fmtp(_)
+                        | Attribute::Direction(_)
+                        | Attribute::Crypto(_)
+                )
+            })
+            .cloned(),
+    );
+    let direction = offer_sdp
+        .media_direction(offered)
+        .reverse()
+        .intersect(local_sdp.media_direction(local));
+    attributes.push(Attribute::Direction(direction));
+
+    MediaDescription {
+        media: offered.media,
+        port: local.port,
+        num_ports: None,
+        protocol: offered.protocol,
+        formats,
+        information: local.information,
+        connections: local.connections.clone(),
+        bandwidths: local.bandwidths.clone(),
+        key: local.key,
+        attributes,
+    }
+}
+
+/// Answer with the same media lines as offer
+fn answer<'a>(
+    local: &SessionDescription<'a>,
+    offer: &SessionDescription<'a>,
+) -> SessionDescription<'a> {
+    let mut used = Vec::with_capacity(local.media.len());
+    let media = offer
+        .media
+        .iter()
+        .map(|offered| {
+            let capabilities = local.media.iter().enumerate().find(|(idx, local)| {
+                !used.contains(idx)
+                    && !local.is_rejected()
+                    && local.media == offered.media
+                    && local.protocol == offered.protocol
+            });
+            match capabilities {
+                Some((idx, capabilities)) if !offered.is_rejected() => {
+                    used.push(idx);
+                    answer_media(local, capabilities, offer, offered)
+                }
+                _ => rejected(offered),
+            }
+        })
+        .collect();
+    SessionDescription {
+        times: offer.times.clone(),
+        // Direction is set for each media
+        attributes: local
+            .attributes
+            .iter()
+            .filter(|a| !matches!(a, Attribute::Direction(_)))
+            .cloned()
+            .collect(),
+        media,
+        ..local.clone()
+    }
+}
+
+/// Creates offers and answers of one session from local capabilities
+/// and keeps version of session description.
+///
+/// ```rust
+/// use sipmsg::sdp::{Direction, OfferAnswer, SessionDescription};
+///
+/// let (_, local) = SessionDescription::parse(
+///     b"v=0\r\no=bob 10 10 IN IP4 192.0.2.2\r\ns=-\r\nc=IN IP4 192.0.2.2\r\nt=0 0\r\n\
+///     m=audio 6000 RTP/AVP 8 0\r\n",
+/// )
+/// .unwrap();
+/// let (_, offer) = SessionDescription::parse(
+///     b"v=0\r\no=alice 1 1 IN IP4 192.0.2.1\r\ns=-\r\nc=IN IP4 192.0.2.1\r\nt=0 0\r\n\
+///     m=audio 5000 RTP/AVP 0 97\r\na=rtpmap:97 iLBC/8000\r\na=sendonly\r\n\
+///     m=video 5002 RTP/AVP 31\r\n",
+/// )
+/// .unwrap();
+///
+/// let mut session = OfferAnswer::new(local);
+/// let answer = session.answer(&offer);
+/// assert_eq!(answer.media[0].formats, ["0"]);
+/// assert_eq!(answer.media[0].direction(), Some(Direction::RecvOnly));
+/// // Video is rejected
+/// assert_eq!(answer.media[1].port, 0);
+///
+/// // Hold: version is incremented because description is changed
+/// session.set_hold(true);
+/// let answer = session.answer(&offer);
+/// assert_eq!(answer.origin.session_version, 11);
+/// assert_eq!(answer.media[0].direction(), Some(Direction::Inactive));
+/// ```
+#[derive(Debug)]
+pub struct OfferAnswer<'a> {
+    local: SessionDescription<'a>,
+    /// Negotiated session without hold
+    session: Option<SessionDescription<'a>>,
+    /// The last sent offer or answer
+    sent: Option<SessionDescription<'a>>,
+    hold: bool,
+}
+
+impl<'a> OfferAnswer<'a> {
+    /// `local` lists supported media and formats in order of preference.
+    /// Its origin is used for all created descriptions
+    pub fn new(local: SessionDescription<'a>) -> OfferAnswer<'a> {
+        OfferAnswer {
+            local,
+            session: None,
+            sent: None,
+            hold: false,
+        }
+    }
+
+    /// Puts media on hold or resumes it with the next offer or answer
+    /// ([rfc3264 section-8.4](https://tools.ietf.org/html/rfc3264#section-8.4)).
+    /// Media on hold is not received: sendrecv becomes sendonly, recvonly becomes inactive
+    pub fn set_hold(&mut self, hold: bool) {
+        self.hold = hold;
+    }
+
+    pub fn is_hold(&self) -> bool {
+        self.hold
+    }
+
+    /// The last sent offer or answer
+    pub fn local_description(&self) -> Option<&SessionDescription<'a>> {
+        self.sent.as_ref()
+    }
+
+    /// Initial offer from local capabilities or re-offer of negotiated session
+    pub fn offer(&mut self) -> SessionDescription<'a> {
+        let session = self.session.as_ref().unwrap_or(&self.local).clone();
+        self.send(session)
+    }
+
+    /// Answer to offer or re-offer of remote side.
+    /// Each offered media line is answered in the same order: media without matching
+    /// local media or common formats is rejected with port 0, dynamic payload types
+    /// are mapped to payload types of offer and direction is reversed
+    pub fn answer(&mut self, offer: &SessionDescription<'a>) -> SessionDescription<'a> {
+        let session = answer(&self.local, offer);
+        self.session = Some(session.clone());
+        self.send(session)
+    }
+
+    /// Applies hold and increments session version if description is changed
+    fn send(&mut self, mut sdp: SessionDescription<'a>) -> SessionDescription<'a> {
+        if self.hold {
+            for idx in 0..sdp.media.len() {
+                if !sdp.media[idx].is_rejected() {
+                    let direction = sdp.media_direction(&sdp.media[idx]);
+                    sdp.media[idx]
+                        .set_direction(Direction::from_flags(direction.can_send(), false));
+                }
+            }
+        }
+        if let Some(sent) = &self.sent {
+            sdp.origin.session_version = sent.origin.session_version;
+            if sdp != *sent {
+                sdp.origin.session_version = sent.origin.session_version.wrapping_add(1);
+            }
+        }
+        self.sent = Some(sdp.clone());
+        sdp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    const LOCAL: &str = "v=0\r\n\
+        o=bob 2808844564 2808844564 IN IP4 host.biloxi.example.com\r\n\
+        s=-\r\n\
+        c=IN IP4 192.0.2.2\r\n\
+        t=0 0\r\n\
+        m=audio 49172 RTP/AVP 8 109 100\r\n\
+        a=rtpmap:109 OPUS/48000/2\r\n\
+        a=rtpmap:100 telephone-event/8000\r\n\
+        a=fmtp:100 0-16\r\n\
+        a=ptime:20\r\n\
+        m=video 49174 RTP/AVP 97\r\n\
+        a=rtpmap:97 VP8/90000\r\n";
+
+    const OFFER: &str = "v=0\r\n\
+        o=alice 2890844526 2890844526 IN IP4 host.atlanta.example.com\r\n\
+        s=-\r\n\
+        c=IN IP4 192.0.2.1\r\n\
+        t=3034423619 0\r\n\
+        m=audio 49170 RTP/AVP 0 111 101\r\n\
+        a=rtpmap:111 opus/48000/2\r\n\
+        a=rtpmap:101 telephone-event/8000\r\n\
+        a=fmtp:101 0-15\r\n\
+        m=video 51372 RTP/AVP 96\r\n\
+        a=rtpmap:96 H264/90000\r\n\
+        m=application 5000 UDP/BFCP *\r\n";
+
+    fn parse(body: &str) -> SessionDescription<'_> {
+        SessionDescription::parse(body.as_bytes()).unwrap().1
+    }
+
+    #[test]
+    fn test_sdp_answer() {
+        let mut session = OfferAnswer::new(parse(LOCAL));
+        let answer = session.answer(&parse(OFFER));
+        assert_eq!(
+            answer.to_string(),
+            "v=0\r\n\
+            o=bob 2808844564 2808844564 IN IP4 host.biloxi.example.com\r\n\
+            s=-\r\n\
+            c=IN IP4 192.0.2.2\r\n\
+            t=3034423619 0\r\n\
+            m=audio 49172 RTP/AVP 111 101\r\n\
+            a=rtpmap:111 OPUS/48000/2\r\n\
+            a=rtpmap:101 telephone-event/8000\r\n\
+            a=fmtp:101 0-16\r\n\
+            a=ptime:20\r\n\
+            a=sendrecv\r\n\
+            m=video 0 RTP/AVP 96\r\n\
+            m=application 0 UDP/BFCP *\r\n"
+        );
+
+        // Remote side puts call on hold, the same description is answered again
+        let offer = OFFER.replace("a=fmtp:101 0-15\r\n", "a=fmtp:101 0-15\r\na=sendonly\r\n");
+        let answer = session.answer(&parse(&offer));
+        assert_eq!(answer.origin.session_version, 2808844565);
+        assert_eq!(answer.media[0].direction(), Some(Direction::RecvOnly));
+        let answer = session.answer(&parse(&offer));
+        assert_eq!(answer.origin.session_version, 2808844565);
+
+        // Local hold of media that is received only
+        session.set_hold(true);
+        let answer = session.answer(&parse(&offer));
+        assert_eq!(answer.origin.session_version, 2808844566);
+        assert_eq!(answer.media[0].direction(), Some(Direction::Inactive));
+        assert!(answer.media[1].direction().is_none());
+
+        // Re-offer keeps negotiated media
+        session.set_hold(false);
+        let offer = session.offer();
+        assert_eq!(offer.origin.session_version, 2808844567);
+        assert_eq!(offer.media.len(), 3);
+        assert_eq!(offer.media[0].direction(), Some(Direction::RecvOnly));
+        assert_eq!(session.local_description(), Some(&offer));
+    }
+
+    #[test]
+    fn test_sdp_offer_hold_resume() {
+        let mut session = OfferAnswer::new(parse(LOCAL));
+        let offer = session.offer();
+        assert_eq!(offer.to_string(), LOCAL);
+        assert_eq!(session.offer().origin.session_version, 2808844564);
+
+        session.set_hold(true);
+        let offer = session.offer();
+        assert_eq!(offer.origin.session_version, 2808844565);
+        assert_eq!(offer.media[0].direction(), Some(Direction::SendOnly));
+        assert_eq!(offer.media[1].direction(), Some(Direction::SendOnly));
+
+        session.set_hold(false);
+        let offer = session.offer();
+        assert_eq!(offer.origin.session_version, 2808844566);
+        assert_eq!(
+            offer.to_string(),
+            LOCAL.replace("2808844564 IN", "2808844566 IN")
+        );
+    }
+
+    #[test]
+    fn test_sdp_answer_crypto() {
+        let local = "v=0\r\no=- 1 1 IN IP4 h\r\ns=-\r\nt=0 0\r\n\
+            m=audio 5000 RTP/SAVP 0\r\n\
+            a=crypto:1 AES_CM_128_HMAC_SHA1_32 inline:NzB4d1BINUAvLEw6UzF3WSJ+PSdFcGdUJShpX1Zj\r\n\
+            a=recvonly\r\n";
+        let offer = "v=0\r\no=- 2 2 IN IP4 h\r\ns=-\r\nt=0 0\r\n\
+            m=audio 6000 RTP/SAVP 0\r\n\
+            a=crypto:1 AES_CM_128_HMAC_SHA1_80 inline:PS1uQCVeeCFCanVmcjkpPywjNWhcYD0mXXtxaVBR\r\n\
+            a=crypto:2 AES_CM_128_HMAC_SHA1_32 inline:d0RmdmcmVCspeEc3QGZiNWpVLFJhQX1cfHAwJSoj\r\n";
+        let mut session = OfferAnswer::new(parse(local));
+        let answer = session.answer(&parse(offer));
+        assert_eq!(
+            answer.media[0].attributes,
+            [
+                Attribute::Crypto(Crypto {
+                    tag: 2,
+                    suite: "AES_CM_128_HMAC_SHA1_32",
+                    key_params: "inline:NzB4d1BINUAvLEw6UzF3WSJ+PSdFcGdUJShpX1Zj",
+                    session_params: Vec::new(),
+                }),
+                Attribute::Direction(Direction::RecvOnly),
+            ]
+        );
+
+        let offer = offer.replace("a=crypto:2", "a=crypto-x:2");
+        let answer = session.answer(&parse(&offer));
+        assert!(answer.media[0].is_rejected());
+    }
+}
@@ -1,126 +1,314 @@
-use crate::{SipHeader, SipHeaders, SipMessage, SipRFCHeader, SipRequest, SipResponse};
+use crate::{SipHeaders, SipMessage, SipRFCHeader, SipRequest, SipResponse};
+use alloc::{borrow::Cow, format, vec::Vec};
+use core::{fmt, str};
 
-struct SipMsgSerializer {
-    internal_buffer: [u8; 5000],
+#[derive(Debug)]
+pub enum SerializeError {
+    /// Serialized message is larger than the limit set by
+    /// [`with_limit`](SipMsgSerializer::with_limit). Contains size of the message.
+    LimitExceeded(usize),
+    /// Message contains bytes that are not valid utf-8 and can't be written to `core::fmt::Write`
+    NotUtf8,
+    /// Error returned by `core::fmt::Write` sink
+    Fmt(fmt::Error),
+    /// Error returned by `std::io::Write` sink
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
 }
 
-/*
-https://tools.ietf.org/html/rfc3261#section-7.3.1
-The relative order of header fields with different field names is not
-   significant.  However, it is RECOMMENDED that header fields which are
-   needed for proxy processing (Via, Route, Record-Route, Proxy-Require,
-   Max-Forwards, and Proxy-Authorization, for example)
-*/
-
-// First element - highest priority
-static PRIORITY_HEADERS: &'static [SipRFCHeader] = &[
-    SipRFCHeader::Via,
-    SipRFCHeader::Route,
-    SipRFCHeader::RecordRoute,
-    SipRFCHeader::MaxForwards,
-    SipRFCHeader::ProxyRequire,
-    SipRFCHeader::ProxyAuthorization,
-];
-
-/// The default maximum SIP message size on Cisco UCM Release 8.6.1
-/// and earlier versions is 5000 bytes. The default on Cisco UCM Release 8.6.2
-/// and later versions is 11000 bytes.
-const MAX_SIP_MESSAGE_SIZE: usize = 5000;
+impl fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SerializeError::LimitExceeded(size) => {
+                write!(f, "message size {} exceeds the limit", size)
+            }
+            SerializeError::NotUtf8 => f.write_str("message is not valid utf-8"),
+            SerializeError::Fmt(err) => write!(f, "{}", err),
+            #[cfg(feature = "std")]
+            SerializeError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SerializeError {}
+
+/// Destination of serialized bytes
+trait Sink {
+    fn put(&mut self, data: &[u8]) -> Result<(), SerializeError>;
+}
+
+impl Sink for Vec<u8> {
+    fn put(&mut self, data: &[u8]) -> Result<(), SerializeError> {
+        self.extend_from_slice(data);
+        Ok(())
+    }
+}
+
+/// Counts size of message without writing it
+struct SizeCounter(usize);
+
+impl Sink for SizeCounter {
+    fn put(&mut self, data: &[u8]) -> Result<(), SerializeError> {
+        self.0 += data.len();
+        Ok(())
+    }
+}
+
+/// Checks that message can be written to `core::fmt::Write` without writing it
+struct Utf8Checker;
+
+impl Sink for Utf8Checker {
+    fn put(&mut self, data: &[u8]) -> Result<(), SerializeError> {
+        str::from_utf8(data).map_err(|_| SerializeError::NotUtf8)?;
+        Ok(())
+    }
+}
+
+struct FmtSink<'w, W: fmt::Write>(&'w mut W);
+
+impl<'w, W: fmt::Write> Sink for FmtSink<'w, W> {
+    fn put(&mut self, data: &[u8]) -> Result<(), SerializeError> {
+        let s = str::from_utf8(data).map_err(|_| SerializeError::NotUtf8)?;
+        self.0.write_str(s).map_err(SerializeError::Fmt)
+    }
+}
+
+#[cfg(feature = "std")]
+struct IoSink<'w, W: std::io::Write>(&'w mut W);
+
+#[cfg(feature = "std")]
+impl<'w, W: std::io::Write> Sink for IoSink<'w, W> {
+    fn put(&mut self, data: &[u8]) -> Result<(), SerializeError> {
+        self.0.write_all(data).map_err(SerializeError::Io)
+    }
+}
+
+/// Serializer of SIP messages to wire format.
+///
+/// By default size of message is not limited.
+/// Use [`with_limit`](SipMsgSerializer::with_limit) to reject messages larger than
+/// the peer accepts (for example, Cisco UCM drops SIP messages larger than 5000 bytes by default).
+/// Size is checked before anything is written to the sink.
+///
+/// ```rust
+/// use sipmsg::{SipMessage, SipMsgSerializer, SipSerializeError};
+///
+/// let (_, msg) = SipMessage::parse(b"SIP/2.0 200 OK\r\n\
+///     Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+///     Content-Length: 0\r\n\r\n").unwrap();
+///
+/// let mut serializer = SipMsgSerializer::new();
+/// let bytes = serializer.serialize_msg(&msg).unwrap();
+/// assert!(bytes.starts_with(b"SIP/2.0 200 OK\r\n"));
+///
+/// let mut s = String::new();
+/// serializer.write_msg(&msg, &mut s).unwrap();
+/// assert!(s.ends_with("Content-Length: 0\r\n\r\n"));
+///
+/// let mut serializer = SipMsgSerializer::with_limit(32);
+/// assert!(matches!(
+///     serializer.serialize_msg(&msg),
+///     Err(SipSerializeError::LimitExceeded(_))
+/// ));
+/// ```
+pub struct SipMsgSerializer {
+    internal_buffer: Vec<u8>,
+    limit: Option<usize>,
+}
 
 impl SipMsgSerializer {
     pub fn new() -> SipMsgSerializer {
-        let ib: [u8; MAX_SIP_MESSAGE_SIZE] = [0; MAX_SIP_MESSAGE_SIZE];
         SipMsgSerializer {
-            internal_buffer: ib,
+            internal_buffer: Vec::new(),
+            limit: None,
         }
     }
 
-    pub fn serialize_msg(&mut self, msg: &SipMessage) -> &[u8] {
+    /// Creates serializer that rejects messages larger than `limit` bytes
+    pub fn with_limit(limit: usize) -> SipMsgSerializer {
+        SipMsgSerializer {
+            internal_buffer: Vec::new(),
+            limit: Some(limit),
+        }
+    }
+
+    /// Serializes message into internal buffer that is reused by next calls
+    pub fn serialize_msg(&mut self, msg: &SipMessage) -> Result<&[u8], SerializeError> {
         match msg {
-            SipMessage::Request(r) => return self.serialize_req(r),
-            SipMessage::Response(r) => return self.serialize_resp(r),
-        };
+            SipMessage::Request(r) => self.serialize_req(r),
+            SipMessage::Response(r) => self.serialize_resp(r),
+        }
     }
 
-    pub fn serialize_req(&mut self, req: &SipRequest) -> &[u8] {
-        let buf_offset = self.append_data_to_ib(0, &req.rl.raw);
-        self.serialize(buf_offset, &req.headers, req.body)
+    pub fn serialize_req(&mut self, req: &SipRequest) -> Result<&[u8], SerializeError> {
+        let mut buffer = core::mem::take(&mut self.internal_buffer);
+        buffer.clear();
+        let res = self.append_req(req, &mut buffer);
+        self.internal_buffer = buffer;
+        res?;
+        Ok(&self.internal_buffer)
     }
 
-    pub fn serialize_resp(&mut self, resp: &SipResponse) -> &[u8] {
-        let buf_offset = self.append_data_to_ib(0, &resp.sl.raw);
-        self.serialize(buf_offset, &resp.headers, resp.body)
+    pub fn serialize_resp(&mut self, resp: &SipResponse) -> Result<&[u8], SerializeError> {
+        let mut buffer = core::mem::take(&mut self.internal_buffer);
+        buffer.clear();
+        let res = self.append_resp(resp, &mut buffer);
+        self.internal_buffer = buffer;
+        res?;
+        Ok(&self.internal_buffer)
     }
 
-    fn append_data_to_ib(&mut self, offset: usize, src: &[u8]) -> usize /* count written data */ {
-        let new_offset = offset + src.len();
-        if new_offset > MAX_SIP_MESSAGE_SIZE {
-            panic!("Internal buffer overflow. Sip message is too long");
+    /// Appends message to the end of `buf`
+    pub fn append_msg(&self, msg: &SipMessage, buf: &mut Vec<u8>) -> Result<(), SerializeError> {
+        match msg {
+            SipMessage::Request(r) => self.append_req(r, buf),
+            SipMessage::Response(r) => self.append_resp(r, buf),
         }
-        &self.internal_buffer[offset..new_offset].copy_from_slice(src);
-        return new_offset;
     }
-    /// Serialize headers and body
-    fn serialize(&mut self, buf_offset: usize, headers: &SipHeaders, body: Option<&[u8]>) -> &[u8] {
-        let mut new_offset = buf_offset;
-        new_offset = self.serialize_headers(&headers, new_offset);
-        if body != None {
-            new_offset = self.append_data_to_ib(new_offset, body.unwrap());
-        }
-        &self.internal_buffer[..new_offset]
+
+    pub fn append_req(&self, req: &SipRequest, buf: &mut Vec<u8>) -> Result<(), SerializeError> {
+        self.write_to_sink(&request_line(req), &req.headers, req.body, buf)
+    }
+
+    pub fn append_resp(&self, resp: &SipResponse, buf: &mut Vec<u8>) -> Result<(), SerializeError> {
+        self.write_to_sink(&status_line(resp), &resp.headers, resp.body, buf)
+    }
+
+    /// Writes message to `core::fmt::Write` sink.
+    /// Returns [`SerializeError::NotUtf8`] if the body is not valid utf-8,
+    /// nothing is written in this case.
+    pub fn write_msg<W: fmt::Write>(
+        &self,
+        msg: &SipMessage,
+        out: &mut W,
+    ) -> Result<(), SerializeError> {
+        self.write_msg_to_sink(msg, &mut Utf8Checker)?;
+        self.write_msg_to_sink(msg, &mut FmtSink(out))
     }
 
-    fn serialize_header(&mut self, hdr: &SipHeader, buf_offset: usize) -> usize {
-        let mut new_offset = self.append_data_to_ib(buf_offset, hdr.name.as_ref().as_bytes());
-        new_offset = self.append_data_to_ib(new_offset, b": ");
-        self.append_data_to_ib(new_offset, hdr.raw_value_param)
+    /// Writes message to `std::io::Write` sink
+    #[cfg(feature = "std")]
+    pub fn write_msg_io<W: std::io::Write>(
+        &self,
+        msg: &SipMessage,
+        out: &mut W,
+    ) -> Result<(), SerializeError> {
+        self.write_msg_to_sink(msg, &mut IoSink(out))
     }
 
-    fn serialize_headers(&mut self, sip_headers: &SipHeaders, buf_offset: usize) -> usize {
-        let mut new_offset = buf_offset;
-        // STEP 1
-        // Serialize RFC priority headers
-        for hdr in PRIORITY_HEADERS {
-            let hdrs = sip_headers.get_rfc(*hdr);
-            if hdrs == None {
-                continue;
+    fn write_msg_to_sink<S: Sink>(
+        &self,
+        msg: &SipMessage,
+        sink: &mut S,
+    ) -> Result<(), SerializeError> {
+        match msg {
+            SipMessage::Request(r) => {
+                self.write_to_sink(&request_line(r), &r.headers, r.body, sink)
             }
-            for hdr in hdrs.unwrap() {
-                new_offset = self.serialize_header(hdr, new_offset);
-                new_offset = self.append_data_to_ib(new_offset, b"\r\n");
+            SipMessage::Response(r) => {
+                self.write_to_sink(&status_line(r), &r.headers, r.body, sink)
             }
         }
-        // STEP 2
-        // Serialize other RFC headers
-        let other_rfs_headers_iter = sip_headers
-            .get_rfc_headers_keys()
-            .filter(|x| !PRIORITY_HEADERS.contains(x));
-        for hdrs_iter in other_rfs_headers_iter {
-            let hdrs = sip_headers.get_rfc(*hdrs_iter);
-            for hdr in hdrs.unwrap() {
-                new_offset = self.serialize_header(hdr, new_offset);
-                new_offset = self.append_data_to_ib(new_offset, b"\r\n");
+    }
+
+    /// Checks size of message and writes it to the sink
+    fn write_to_sink<S: Sink>(
+        &self,
+        start_line: &[u8],
+        headers: &SipHeaders,
+        body: Option<&[u8]>,
+        sink: &mut S,
+    ) -> Result<(), SerializeError> {
+        if let Some(limit) = self.limit {
+            let mut counter = SizeCounter(0);
+            SipMsgSerializer::serialize(start_line, headers, body, &mut counter)?;
+            if counter.0 > limit {
+                return Err(SerializeError::LimitExceeded(counter.0));
             }
         }
-        // STEP 3
-        // Serialize extention headers
-        let ext_headers_iter = sip_headers.get_ext_headers_keys();
-        match ext_headers_iter {
-            Some(ext_hdrs) => {
-                for header_name in ext_hdrs {
-                    // One header name can contain multiple header value
-                    let hdrs = sip_headers.get_ext(header_name).unwrap();
-                    for hdr in hdrs {
-                        new_offset = self.serialize_header(hdr, new_offset);
-                        new_offset = self.append_data_to_ib(new_offset, b"\r\n");
+        SipMsgSerializer::serialize(start_line, headers, body, sink)
+    }
+
+    /// Serialize start line, headers and body
+    fn serialize<S: Sink>(
+        start_line: &[u8],
+        headers: &SipHeaders,
+        body: Option<&[u8]>,
+        sink: &mut S,
+    ) -> Result<(), SerializeError> {
+        sink.put(start_line)?;
+        SipMsgSerializer::serialize_headers(headers, body.map_or(0, |b| b.len()), sink)?;
+        if let Some(body) = body {
+            sink.put(body)?;
+        }
+        Ok(())
+    }
+
+    /// Unmodified header lines are written in original order and form.
+    /// Modified lines are rebuilt from their values.
+    /// Content-Length is rewritten if it doesn't match the body.
+    fn serialize_headers<S: Sink>(
+        sip_headers: &SipHeaders,
+        body_len: usize,
+        sink: &mut S,
+    ) -> Result<(), SerializeError> {
+        for (line, values) in sip_headers.lines_with_values() {
+            if line.rfc_type == Some(SipRFCHeader::ContentLength)
+                && values.first().map(|v| v.value.vstr.parse::<usize>()) != Some(Ok(body_len))
+            {
+                let content_length = format!("{}: {}", line.name, body_len);
+                sink.put(content_length.as_bytes())?;
+            } else if let Some(raw) = line.raw {
+                sink.put(raw)?;
+            } else {
+                sink.put(line.name.as_bytes())?;
+                sink.put(b": ")?;
+                for (idx, hdr) in values.iter().enumerate() {
+                    if idx != 0 {
+                        sink.put(b", ")?;
                     }
+                    sink.put(hdr.raw_value_param)?;
                 }
             }
-            None => { /* There is no ext. headers. Do nothing */ }
+            sink.put(b"\r\n")?;
         }
-
         // Mark and headers by double "\r\n\r\n"
-        self.append_data_to_ib(new_offset, b"\r\n")
+        sink.put(b"\r\n")
+    }
+}
+
+/// Original request line or the new one if request line was modified
+fn request_line<'r>(req: &'r SipRequest) -> Cow<'r, [u8]> {
+    if req.rl.raw.is_empty() || req.rl.is_modified() {
+        let rl = format!(
+            "{} {} {}\r\n",
+            req.rl.method, req.rl.uri, req.rl.sip_version
+        );
+        Cow::Owned(rl.into_bytes())
+    } else {
+        Cow::Borrowed(req.rl.raw)
+    }
+}
+
+/// Original status line or the new one if status line was modified
+fn status_line<'r>(resp: &'r SipResponse) -> Cow<'r, [u8]> {
+    if resp.sl.raw.is_empty() || resp.sl.is_modified() {
+        let sl = format!(
+            "{} {} {}\r\n",
+            resp.sl.sip_version,
+            resp.sl.status_code.code(),
+            resp.sl.reason_phrase
+        );
+        Cow::Owned(sl.into_bytes())
+    } else {
+        Cow::Borrowed(resp.sl.raw)
+    }
+}
+
+impl Default for SipMsgSerializer {
+    fn default() -> SipMsgSerializer {
+        SipMsgSerializer::new()
     }
 }
 
@@ -140,7 +328,7 @@ mod tests {
             .as_bytes();
         let (_, resp) = SipResponse::parse(resp_msg_buf).unwrap();
         let mut s = SipMsgSerializer::new();
-        let serialized_buf = s.serialize_resp(&resp);
+        let serialized_buf = s.serialize_resp(&resp).unwrap();
 
         let (_, msg2) = SipMessage::parse(serialized_buf).unwrap();
         let new_resp = msg2.response().unwrap();
@@ -218,7 +406,7 @@ mod tests {
 
         let (_, msg) = SipMessage::parse(invite_msg_buf).unwrap();
         let mut s = SipMsgSerializer::new();
-        let serialized_buf = s.serialize_msg(&msg);
+        let serialized_buf = s.serialize_msg(&msg).unwrap();
         let (_, msg2) = SipMessage::parse(serialized_buf).unwrap();
         let new_req = msg2.request().unwrap();
         assert_eq!(
@@ -349,4 +537,97 @@ mod tests {
 
         assert_eq!(new_req.body.unwrap(), b"body");
     }
+
+    #[test]
+    fn test_serializer_round_trip() {
+        let invite_msg_buf = b"INVITE sip:bob@biloxi.example.com SIP/2.0\r\n\
+        v: SIP/2.0/TCP pc33.atlanta.example.com;branch=z9hG4bKnashds8\r\n\
+        To:Bob <sip:bob@biloxi.example.com>\r\n\
+        f : Alice <sip:alice@atlanta.example.com>;tag=1928301774\r\n\
+        Identity: \"ZYNBbHC00VMZr2kZt6VmCvPonWJMGvQTBDqghoWeLxJfzB2a1pxAr3VgrB0SsSAa\"\r\n\
+        i: a84b4c76e66710\r\n\
+        CSeq: 314159 INVITE\r\n\
+        X-Custom:  value\r\n\
+        Max-Forwards: 70\r\n\
+        Supported: replaces, 100rel\r\n\
+        m: <sip:alice@pc33.atlanta.example.com>\r\n\
+        c: text/plain\r\n\
+        l: 4\r\n\r\nbody";
+
+        let (_, msg) = SipMessage::parse(invite_msg_buf).unwrap();
+        let mut s = SipMsgSerializer::new();
+        assert_eq!(s.serialize_msg(&msg).unwrap(), &invite_msg_buf[..]);
+    }
+
+    const OPTIONS: &[u8] = b"OPTIONS sip:carol@chicago.com SIP/2.0\r\n\
+        Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKhjhs8ass877\r\n\
+        Max-Forwards: 70\r\n\
+        Call-ID: a84b4c76e66710\r\n\
+        CSeq: 63104 OPTIONS\r\n";
+
+    #[test]
+    fn test_serializer_limit() {
+        let mut large_msg = OPTIONS.to_vec();
+        for i in 0..500 {
+            large_msg.extend_from_slice(alloc::format!("X-Header-{}: value\r\n", i).as_bytes());
+        }
+        large_msg.extend_from_slice(b"Content-Length: 0\r\n\r\n");
+        assert!(large_msg.len() > 5000);
+        let (_, msg) = SipMessage::parse(&large_msg).unwrap();
+
+        let mut s = SipMsgSerializer::new();
+        assert_eq!(s.serialize_msg(&msg).unwrap().len(), large_msg.len());
+
+        let s = SipMsgSerializer::with_limit(5000);
+        let mut buf = b"prefix".to_vec();
+        match s.append_msg(&msg, &mut buf) {
+            Err(SerializeError::LimitExceeded(size)) => assert_eq!(size, large_msg.len()),
+            _ => panic!(),
+        }
+        assert_eq!(buf, b"prefix");
+
+        let s = SipMsgSerializer::with_limit(large_msg.len());
+        assert!(s.append_msg(&msg, &mut buf).is_ok());
+        assert_eq!(buf.len(), large_msg.len() + 6);
+    }
+
+    #[test]
+    fn test_serializer_fmt_write() {
+        let mut buf = OPTIONS.to_vec();
+        buf.extend_from_slice(b"Content-Length: 4\r\n\r\nbody");
+        let (_, msg) = SipMessage::parse(&buf).unwrap();
+        let s = SipMsgSerializer::new();
+        let mut out = alloc::string::String::new();
+        s.write_msg(&msg, &mut out).unwrap();
+        assert!(out.starts_with("OPTIONS sip:carol@chicago.com SIP/2.0\r\n"));
+        assert!(out.ends_with("\r\n\r\nbody"));
+
+        let mut buf = OPTIONS.to_vec();
+        buf.extend_from_slice(b"Content-Length: 2\r\n\r\n\xff\xfe");
+        let (_, msg) = SipMessage::parse(&buf).unwrap();
+        let mut out = alloc::string::String::new();
+        match s.write_msg(&msg, &mut out) {
+            Err(SerializeError::NotUtf8) => {}
+            _ => panic!(),
+        }
+        assert!(out.is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_serializer_io_write() {
+        let mut buf = OPTIONS.to_vec();
+        buf.extend_from_slice(b"Content-Length: 0\r\n\r\n");
+        let (_, msg) = SipMessage::parse(&buf).unwrap();
+        let mut cursor = std::io::Cursor::new([0u8; 32]);
+        match SipMsgSerializer::new().write_msg_io(&msg, &mut cursor) {
+            Err(SerializeError::Io(_)) => {}
+            _ => panic!(),
+        }
+        let mut out = std::vec::Vec::new();
+        SipMsgSerializer::new()
+            .write_msg_io(&msg, &mut out)
+            .unwrap();
+        assert_eq!(out.len(), buf.len());
+    }
 }
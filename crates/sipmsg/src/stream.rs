@@ -0,0 +1,315 @@
+use crate::{common::errorparse::SipParseError, SipMessage, SipRFCHeader};
+use alloc::vec::Vec;
+use nom::Needed;
+
+const CRLF: &[u8] = b"\r\n";
+const HEADERS_END: &[u8] = b"\r\n\r\n";
+
+/// Decoder of SIP messages received over stream-oriented transports (TCP, TLS).
+///
+/// Bytes are appended with [`feed`](StreamDecoder::feed) as they arrive and complete
+/// messages are taken one after another with [`next_message`](StreamDecoder::next_message).
+/// Message boundaries are detected by the Content-Length header
+/// which is mandatory for stream transports ([rfc3261 section-18.3](https://tools.ietf.org/html/rfc3261#section-18.3)).
+/// CRLF keep-alives ([rfc5626 section-3.5.1](https://tools.ietf.org/html/rfc5626#section-3.5.1))
+/// between messages are skipped.
+/// Message that is larger than [`max_message_size`](StreamDecoder::max_message_size)
+/// is an error, so a peer can't make the buffer grow forever.
+///
+/// ```rust
+/// use sipmsg::{SipMethod, SipStreamDecoder};
+///
+/// let mut decoder = SipStreamDecoder::new();
+/// decoder.feed(b"\r\n\r\nOPTIONS sip:user@example.com SIP/2.0\r\n\
+///                Via: SIP/2.0/TCP pc33.atlanta.com;branch=z9hG4bKhjhs8ass877\r\n");
+/// // Headers are not finished yet
+/// assert!(matches!(decoder.next_message(), Err(nom::Err::Incomplete(_))));
+///
+/// decoder.feed(b"Content-Length: 4\r\n\r\nbody");
+/// let msg = decoder.next_message().unwrap();
+/// assert_eq!(msg.request().unwrap().rl.method, SipMethod::OPTIONS);
+/// assert_eq!(msg.request().unwrap().body.unwrap(), b"body");
+/// ```
+pub struct StreamDecoder {
+    buffer: Vec<u8>,
+    /// Count of bytes at the beginning of the buffer that were already decoded
+    /// and must be dropped before the next decoding.
+    consumed: usize,
+    /// Count of bytes of the pending message that were searched for the end of headers
+    scanned: usize,
+    /// Length of the pending message including body, known once its headers were parsed.
+    /// Headers are not parsed again while the rest of body is received.
+    message_len: Option<usize>,
+    max_message_size: usize,
+}
+
+impl StreamDecoder {
+    /// Default limit of message size, see [`max_message_size`](StreamDecoder::max_message_size)
+    pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 64 * 1024;
+
+    pub fn new() -> StreamDecoder {
+        StreamDecoder {
+            buffer: Vec::new(),
+            consumed: 0,
+            scanned: 0,
+            message_len: None,
+            max_message_size: StreamDecoder::DEFAULT_MAX_MESSAGE_SIZE,
+        }
+    }
+
+    /// Limit of message size including headers and body.
+    /// Default is [`DEFAULT_MAX_MESSAGE_SIZE`](StreamDecoder::DEFAULT_MAX_MESSAGE_SIZE)
+    pub fn max_message_size(mut self, size: usize) -> StreamDecoder {
+        self.max_message_size = size;
+        self
+    }
+
+    /// Appends received bytes to the internal buffer
+    pub fn feed(&mut self, data: &[u8]) {
+        self.drop_consumed();
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Count of received bytes that are not decoded yet
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len() - self.consumed
+    }
+
+    fn drop_consumed(&mut self) {
+        if self.consumed != 0 {
+            self.buffer.drain(..self.consumed);
+            self.consumed = 0;
+        }
+    }
+
+    /// Returns the next complete message from the stream.
+    ///
+    /// `nom::Err::Incomplete` is returned when more data is needed to decode the message.
+    /// Any other error means that the stream is broken and the connection should be closed.
+    pub fn next_message(&mut self) -> Result<SipMessage<'_>, nom::Err<SipParseError<'_>>> {
+        self.drop_consumed();
+
+        let mut start = 0;
+        while self.buffer[start..].starts_with(CRLF) {
+            start += 2;
+        }
+        // keep-alives are consumed even if message is not complete
+        self.consumed = start;
+
+        let input = &self.buffer[start..];
+        if input.is_empty() || input == b"\r" {
+            return Err(nom::Err::Incomplete(Needed::Unknown));
+        }
+        if let Some(message_len) = self.message_len {
+            if input.len() < message_len {
+                return Err(nom::Err::Incomplete(Needed::new(message_len - input.len())));
+            }
+        }
+        // Bytes that were searched before are not searched again,
+        // except the tail that may be a part of split CRLFCRLF
+        let scan_from = self.scanned.saturating_sub(HEADERS_END.len() - 1);
+        let headers_end = input[scan_from..]
+            .windows(HEADERS_END.len())
+            .position(|w| w == HEADERS_END)
+            .map(|pos| scan_from + pos + HEADERS_END.len());
+        let too_large = || sip_parse_error!(2, "Message exceeds maximum size");
+        match headers_end {
+            Some(headers_end) if headers_end > self.max_message_size => return too_large(),
+            Some(headers_end) => self.scanned = headers_end - HEADERS_END.len(),
+            None if input.len() > self.max_message_size => return too_large(),
+            None => {
+                self.scanned = input.len();
+                return Err(nom::Err::Incomplete(Needed::Unknown));
+            }
+        }
+
+        let (rest, msg) = match SipMessage::parse(input) {
+            Err(nom::Err::Incomplete(Needed::Size(size))) => {
+                let message_len = input.len().saturating_add(size.get());
+                if message_len > self.max_message_size {
+                    return too_large();
+                }
+                self.message_len = Some(message_len);
+                return Err(nom::Err::Incomplete(Needed::Size(size)));
+            }
+            result => result?,
+        };
+        if msg.headers().get_rfc(SipRFCHeader::ContentLength).is_none() {
+            return sip_parse_error!(
+                1,
+                "Content-Length header is mandatory for stream-oriented transports"
+            );
+        }
+        self.consumed = start + input.len() - rest.len();
+        self.scanned = 0;
+        self.message_len = None;
+        Ok(msg)
+    }
+}
+
+impl Default for StreamDecoder {
+    fn default() -> StreamDecoder {
+        StreamDecoder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SipMethod, SipParseErrorKind, SipResponseStatusCode};
+    use alloc::string::String;
+
+    const INVITE: &[u8] = b"INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+        Via: SIP/2.0/TCP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+        Max-Forwards: 70\r\n\
+        Call-ID: a84b4c76e66710\r\n\
+        CSeq: 314159 INVITE\r\n\
+        Content-Length: 10\r\n\r\n\
+        body_stuff";
+
+    const RINGING: &[u8] = b"SIP/2.0 180 Ringing\r\n\
+        Via: SIP/2.0/TCP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+        CSeq: 314159 INVITE\r\n\
+        l: 0\r\n\r\n";
+
+    #[test]
+    fn decode_byte_by_byte() {
+        let mut decoder = StreamDecoder::new();
+        let mut stream = Vec::new();
+        stream.extend_from_slice(INVITE);
+        stream.extend_from_slice(b"\r\n\r\n");
+        stream.extend_from_slice(RINGING);
+        stream.extend_from_slice(b"\r\n");
+
+        let mut methods = Vec::new();
+        let mut status_codes = Vec::new();
+        for b in stream.iter() {
+            decoder.feed(&[*b]);
+            match decoder.next_message() {
+                Ok(SipMessage::Request(req)) => {
+                    assert_eq!(req.body.unwrap(), b"body_stuff");
+                    methods.push(String::from(req.rl.method.as_str()));
+                }
+                Ok(SipMessage::Response(resp)) => status_codes.push(resp.sl.status_code),
+                Err(nom::Err::Incomplete(_)) => {}
+                Err(_) => panic!(),
+            }
+        }
+        assert_eq!(methods, [SipMethod::INVITE.as_str()]);
+        assert_eq!(status_codes, [SipResponseStatusCode::Ringing]);
+        assert_eq!(decoder.buffered_len(), 0);
+    }
+
+    #[test]
+    fn decode_several_messages_in_one_chunk() {
+        let mut decoder = StreamDecoder::new();
+        let mut stream = Vec::new();
+        stream.extend_from_slice(RINGING);
+        stream.extend_from_slice(INVITE);
+        stream.extend_from_slice(&INVITE[..20]);
+        decoder.feed(&stream);
+
+        assert!(decoder.next_message().unwrap().response().is_some());
+        assert!(decoder.next_message().unwrap().request().is_some());
+        match decoder.next_message() {
+            Err(nom::Err::Incomplete(_)) => {}
+            _ => panic!(),
+        }
+        assert_eq!(decoder.buffered_len(), 20);
+    }
+
+    #[test]
+    fn decode_body_incomplete() {
+        let mut decoder = StreamDecoder::new();
+        decoder.feed(&INVITE[..INVITE.len() - 4]);
+        match decoder.next_message() {
+            Err(nom::Err::Incomplete(Needed::Size(size))) => assert_eq!(size.get(), 4),
+            _ => panic!(),
+        }
+        decoder.feed(&INVITE[INVITE.len() - 4..]);
+        assert!(decoder.next_message().is_ok());
+    }
+
+    #[test]
+    fn decode_body_byte_by_byte() {
+        let mut decoder = StreamDecoder::new();
+        let body_start = INVITE.len() - 10;
+        decoder.feed(&INVITE[..body_start]);
+        assert!(matches!(
+            decoder.next_message(),
+            Err(nom::Err::Incomplete(_))
+        ));
+        // Headers are parsed once, then only the length of body is checked
+        assert_eq!(decoder.message_len, Some(INVITE.len()));
+        for (i, b) in INVITE[body_start..INVITE.len() - 1].iter().enumerate() {
+            decoder.feed(&[*b]);
+            match decoder.next_message() {
+                Err(nom::Err::Incomplete(Needed::Size(size))) => assert_eq!(size.get(), 9 - i),
+                _ => panic!(),
+            }
+        }
+        decoder.feed(&INVITE[INVITE.len() - 1..]);
+        decoder.feed(RINGING);
+        assert_eq!(
+            decoder
+                .next_message()
+                .unwrap()
+                .request()
+                .unwrap()
+                .body
+                .unwrap(),
+            b"body_stuff"
+        );
+        assert_eq!(decoder.message_len, None);
+        assert!(decoder.next_message().unwrap().response().is_some());
+    }
+
+    #[test]
+    fn decode_errors() {
+        let mut decoder = StreamDecoder::new();
+        decoder.feed(
+            b"INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+            Via: SIP/2.0/TCP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\r\n",
+        );
+        match decoder.next_message() {
+            Err(nom::Err::Error(_)) => {}
+            _ => panic!(),
+        }
+
+        let mut decoder = StreamDecoder::new();
+        decoder.feed(b"garbage\r\n\r\n");
+        match decoder.next_message() {
+            Err(nom::Err::Error(_)) => {}
+            _ => panic!(),
+        }
+
+        let mut decoder = StreamDecoder::new();
+        decoder.feed(b"OPTIONS sip:alice@b.com SIP/256.0\r\nl: 0\r\n\r\n");
+        match decoder.next_message() {
+            Err(nom::Err::Error(e)) => assert_eq!(e.kind(), SipParseErrorKind::StartLine),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn decode_max_message_size() {
+        // Headers never end
+        let mut decoder = StreamDecoder::new().max_message_size(64);
+        decoder.feed(b"OPTIONS sip:alice@b.com SIP/2.0\r\n");
+        assert!(matches!(
+            decoder.next_message(),
+            Err(nom::Err::Incomplete(_))
+        ));
+        decoder.feed(b"Subject: aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+        assert!(matches!(decoder.next_message(), Err(nom::Err::Error(_))));
+
+        // Body is too large
+        let mut decoder = StreamDecoder::new().max_message_size(INVITE.len() - 1);
+        decoder.feed(&INVITE[..INVITE.len() - 10]);
+        assert!(matches!(decoder.next_message(), Err(nom::Err::Error(_))));
+
+        let mut decoder = StreamDecoder::new().max_message_size(INVITE.len());
+        decoder.feed(INVITE);
+        assert!(decoder.next_message().is_ok());
+    }
+}
@@ -2,8 +2,10 @@ use crate::common::{
     bnfcore::*,
     errorparse::SipParseError,
     nom_wrappers::{from_utf8_nom, take_while_with_escaped},
+    percent::decode,
 };
-use core::str;
+use alloc::borrow::Cow;
+use core::{fmt, str};
 
 /// userinfo =  ( user / telephone-subscriber ) [ ":" password ] "@"
 /// user     =  1*( unreserved / escaped / user-unreserved )
@@ -20,6 +22,16 @@ fn is_userinfo_char(c: u8) -> bool {
 }
 
 impl<'a> UserInfo<'a> {
+    /// User with escaped characters decoded
+    pub fn decoded_value(&self) -> Cow<'a, str> {
+        decode(self.value)
+    }
+
+    /// Password with escaped characters decoded
+    pub fn decoded_password(&self) -> Option<Cow<'a, str>> {
+        self.password.map(decode)
+    }
+
     fn take_user(input: &'a [u8]) -> nom::IResult<&'a [u8], &'a [u8], SipParseError> {
         take_while_with_escaped(input, is_userinfo_char)
     }
@@ -33,7 +45,7 @@ impl<'a> UserInfo<'a> {
             return sip_parse_error!(1);
         }
 
-        if !is_userinfo_char(input[0]) {
+        if !is_userinfo_char(input[0]) && !is_escaped(input) {
             return sip_parse_error!(2);
         }
 
@@ -61,6 +73,16 @@ impl<'a> UserInfo<'a> {
     }
 }
 
+impl<'a> fmt::Display for UserInfo<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.value)?;
+        match self.password {
+            Some(password) => write!(f, ":{}", password),
+            None => Ok(()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,187 @@
+use crate::{
+    SipHeaderTagType, SipHeaders, SipMessage, SipRFCHeader, SipRequest, SipResponse,
+    SipResponseStatusCode, SipVersion, Uri,
+};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Headers that must be present in every request
+/// ([rfc3261 section-8.1.1](https://tools.ietf.org/html/rfc3261#section-8.1.1))
+static REQUEST_MANDATORY_HEADERS: &[SipRFCHeader] = &[
+    SipRFCHeader::To,
+    SipRFCHeader::From,
+    SipRFCHeader::CSeq,
+    SipRFCHeader::CallID,
+    SipRFCHeader::MaxForwards,
+    SipRFCHeader::Via,
+];
+
+/// Headers that must be present in every response
+/// ([rfc3261 section-8.2.6.2](https://tools.ietf.org/html/rfc3261#section-8.2.6.2))
+static RESPONSE_MANDATORY_HEADERS: &[SipRFCHeader] = &[
+    SipRFCHeader::Via,
+    SipRFCHeader::From,
+    SipRFCHeader::To,
+    SipRFCHeader::CallID,
+    SipRFCHeader::CSeq,
+];
+
+/// Headers that can't be combined as comma-separated list
+/// and must be present only one time
+/// ([rfc3261 section-7.3.1](https://tools.ietf.org/html/rfc3261#section-7.3.1))
+static SINGLE_VALUE_HEADERS: &[SipRFCHeader] = &[
+    SipRFCHeader::To,
+    SipRFCHeader::From,
+    SipRFCHeader::CSeq,
+    SipRFCHeader::CallID,
+    SipRFCHeader::MaxForwards,
+    SipRFCHeader::ContentLength,
+    SipRFCHeader::ContentType,
+    SipRFCHeader::Expires,
+];
+
+/// Violation of rfc3261 found by `validate` of request or response
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Violation<'a> {
+    /// SIP version other than 2.0
+    UnsupportedVersion(SipVersion),
+    /// Request-URI scheme is not sip, sips or tel. Contains the scheme
+    UnsupportedUriScheme(&'a str),
+    /// Header failed to parse in lenient mode. Contains header name
+    InvalidHeader(&'a str),
+    /// Mandatory header is absent
+    MissingHeader(SipRFCHeader),
+    /// Header that must be present only one time is repeated
+    DuplicateHeader(SipRFCHeader),
+    /// Method in CSeq header doesn't match method of request line
+    CSeqMethodMismatch,
+    /// Value of Content-Length header doesn't match length of body.
+    /// Parsing frames the body by Content-Length, so this only fires for
+    /// messages whose body was replaced after parsing or that were built
+    ContentLengthMismatch { header: u64, body: u64 },
+}
+
+impl<'a> Violation<'a> {
+    /// Status code of response that UAS should reply with
+    /// ([rfc3261 section-8.2](https://tools.ietf.org/html/rfc3261#section-8.2))
+    pub fn status_code(&self) -> SipResponseStatusCode {
+        match self {
+            Violation::UnsupportedVersion(_) => SipResponseStatusCode::VersionNotSupported,
+            Violation::UnsupportedUriScheme(_) => SipResponseStatusCode::UnsupportedUriScheme,
+            _ => SipResponseStatusCode::BadRequest,
+        }
+    }
+}
+
+impl<'a> fmt::Display for Violation<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::UnsupportedVersion(version) => {
+                write!(f, "unsupported version {}", version)
+            }
+            Violation::UnsupportedUriScheme(scheme) => {
+                write!(f, "unsupported URI scheme {}", scheme)
+            }
+            Violation::InvalidHeader(name) => write!(f, "invalid header {}", name),
+            Violation::MissingHeader(hdr) => write!(f, "missing header {}", hdr.as_str()),
+            Violation::DuplicateHeader(hdr) => write!(f, "duplicate header {}", hdr.as_str()),
+            Violation::CSeqMethodMismatch => {
+                f.write_str("CSeq method doesn't match request method")
+            }
+            Violation::ContentLengthMismatch { header, body } => write!(
+                f,
+                "Content-Length {} doesn't match body length {}",
+                header, body
+            ),
+        }
+    }
+}
+
+/// Validates request.
+/// Violations are ordered by importance, the first one defines status code of reply.
+pub(crate) fn validate_request<'a>(req: &SipRequest<'a>) -> Vec<Violation<'a>> {
+    let mut violations = Vec::new();
+    validate_common(
+        req.rl.sip_version,
+        Some(&req.rl.uri),
+        &req.headers,
+        req.body,
+        REQUEST_MANDATORY_HEADERS,
+        &mut violations,
+    );
+    let cseq_method = req
+        .headers
+        .get_rfc_s(SipRFCHeader::CSeq)
+        .and_then(|hdr| hdr.value.tags())
+        .and_then(|tags| tags.get(&SipHeaderTagType::Method));
+    if let Some(cseq_method) = cseq_method {
+        if *cseq_method != req.rl.method.as_str().as_bytes() {
+            violations.push(Violation::CSeqMethodMismatch);
+        }
+    }
+    violations
+}
+
+/// Validates response
+pub(crate) fn validate_response<'a>(resp: &SipResponse<'a>) -> Vec<Violation<'a>> {
+    let mut violations = Vec::new();
+    validate_common(
+        resp.sl.sip_version,
+        None,
+        &resp.headers,
+        resp.body,
+        RESPONSE_MANDATORY_HEADERS,
+        &mut violations,
+    );
+    violations
+}
+
+pub(crate) fn validate_message<'a>(msg: &SipMessage<'a>) -> Vec<Violation<'a>> {
+    match msg {
+        SipMessage::Request(r) => validate_request(r),
+        SipMessage::Response(r) => validate_response(r),
+    }
+}
+
+fn validate_common<'a>(
+    version: SipVersion,
+    request_uri: Option<&Uri<'a>>,
+    headers: &SipHeaders<'a>,
+    body: Option<&[u8]>,
+    mandatory_headers: &[SipRFCHeader],
+    violations: &mut Vec<Violation<'a>>,
+) {
+    if version != SipVersion(2, 0) {
+        violations.push(Violation::UnsupportedVersion(version));
+    }
+    if let Some(Uri::Absolute(uri)) = request_uri {
+        violations.push(Violation::UnsupportedUriScheme(uri.scheme));
+    }
+    for hdr in headers.invalid_headers() {
+        violations.push(Violation::InvalidHeader(hdr.name.into_inner()));
+    }
+    for hdr in mandatory_headers {
+        // invalid header is already reported
+        let is_invalid = headers
+            .invalid_headers()
+            .iter()
+            .any(|h| h.rfc_type == Some(*hdr));
+        if headers.get_rfc(*hdr).is_none() && !is_invalid {
+            violations.push(Violation::MissingHeader(*hdr));
+        }
+    }
+    for hdr in SINGLE_VALUE_HEADERS {
+        if matches!(headers.get_rfc(*hdr), Some(hdrs) if hdrs.len() > 1) {
+            violations.push(Violation::DuplicateHeader(*hdr));
+        }
+    }
+    if let Ok(Some(content_length)) = headers.content_length() {
+        let body_len = body.map_or(0, |b| b.len() as u64);
+        if content_length != body_len {
+            violations.push(Violation::ContentLengthMismatch {
+                header: content_length,
+                body: body_len,
+            });
+        }
+    }
+}
@@ -314,3 +314,15 @@ fn content_type_header() {
     assert_eq!(hdrs[0].value.vstr, "application/sdp");
     assert_eq!(input.len(), 2)
 }
+
+#[test]
+fn header_from_line() {
+    let hdr = SipHeader::from_line(b"Max-Forwards: 69\r\n").unwrap();
+    assert_eq!(hdr.name, "Max-Forwards");
+    assert_eq!(hdr.value.vstr, "69");
+
+    let err = SipHeader::from_line(b"Route: <sip:a.com;lr>, <sip:b.com;lr>\r\n").unwrap_err();
+    assert_eq!(err.kind(), SipParseErrorKind::HeaderValue);
+    assert!(SipHeader::from_line(b"Max-Forwards: 69").is_err());
+    assert!(SipHeader::from_line(b"Max-Forwards: 69\r\nCSeq: 1 INFO\r\n").is_err());
+}
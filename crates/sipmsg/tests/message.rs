@@ -18,7 +18,7 @@ Content-Type: application/sdp\r\n\
 Content-Length: 0\r\n\r\n".as_bytes();
     let (_, sip_msg) = SipMessage::parse(invite_msg_buf).unwrap();
     let sip_req = sip_msg.request().unwrap();
-    assert_eq!(sip_req.rl.uri.user_info().unwrap().value, "001234567890");
+    assert_eq!(sip_req.rl.uri.sip().unwrap().user_info().unwrap().value, "001234567890");
 }
 
 #[test]
@@ -43,6 +43,140 @@ fn get_message_type() {
     );
     assert_eq!(
         sipmsg::get_sip_message_type("NEWMETHOD sip:user@example.com SIP/2.0".as_bytes()),
+        SipMessageType::Request
+    );
+    assert_eq!(
+        sipmsg::get_sip_message_type("SIPX sip:user@example.com SIP/2.0".as_bytes()),
+        SipMessageType::Request
+    );
+    assert_eq!(
+        sipmsg::get_sip_message_type("NEW/METHOD sip:user@example.com SIP/2.0".as_bytes()),
+        SipMessageType::Unknown
+    );
+    assert_eq!(
+        sipmsg::get_sip_message_type(" INVITE sip:user@example.com SIP/2.0".as_bytes()),
         SipMessageType::Unknown
     );
 }
+
+#[test]
+fn parse_message_errors() {
+    let err = |buf: &'static [u8]| match SipMessage::parse(buf) {
+        Err(e) => SipParseError::from(e),
+        Ok(_) => panic!(),
+    };
+
+    let e = err(b"INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+                  Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+                  CSeq: INVITE\r\n\
+                  Content-Length: 0\r\n\r\n");
+    let details = match &e {
+        SipParseError::HeaderValue(details) => details,
+        _ => panic!("{:?}", e),
+    };
+    assert_eq!(details.header, Some("CSeq"));
+    assert_eq!(details.code, 0);
+    let position = details.position.unwrap();
+    assert_eq!((position.line, position.column), (3, 7));
+    assert_eq!(position.offset, 35 + 57 + 6);
+
+    let e = err(b"INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+                  To: <sip:bob@biloxi.com\r\n\r\n");
+    let details = e.details().unwrap();
+    assert_eq!(details.header, Some("To"));
+    assert_eq!(details.position.unwrap().line, 2);
+
+    let e = err(b"INVITE bob SIP/2.0\r\n\r\n");
+    assert_eq!(e.kind(), SipParseErrorKind::Uri);
+
+    let e = err(b"SIP/2.0 20 OK\r\n\r\n");
+    assert_eq!(e.kind(), SipParseErrorKind::StartLine);
+
+    let e = err(b"SIP/2.0 200 OK\r\nContent-Length: 10\r\n\r\nbody");
+    assert_eq!(e, SipParseError::Incomplete);
+    assert_eq!(e.to_string(), "incomplete message");
+
+    let e = err(b"SIP/2.0 200 OK\r\nContent-Length: 1\r\nContent-Length: 1\r\n\r\nb");
+    assert_eq!(
+        e.to_string(),
+        "invalid body at line 5, column 1: Content-Length header must be present only one time"
+    );
+    assert_eq!(e.kind(), SipParseErrorKind::Body);
+
+    let e = err(b"SIP/2.0 200 OK\r\nContent-Length: 99999999999999999999\r\n\r\n");
+    assert_eq!(e.kind(), SipParseErrorKind::Body);
+}
+
+#[test]
+fn parse_message_lenient() {
+    let buf = b"INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+        Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+        Max-Forwards: seventy\r\n\
+        To: Bob <sip:bob@biloxi.com>\r\n\
+        Call-ID: a84b4c76e66710\r\n\
+        Content-Length: 4\r\n\r\nbody";
+    assert!(SipMessage::parse(buf).is_err());
+
+    let (rest, msg) = SipMessage::parse_lenient(buf).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(msg.body().unwrap(), b"body");
+    let headers = msg.headers();
+    assert!(headers.has_invalid_headers());
+    assert!(headers.get_rfc(SipRFCHeader::MaxForwards).is_none());
+    assert!(headers.get_rfc_s(SipRFCHeader::To).is_some());
+
+    let invalid = &headers.invalid_headers()[0];
+    assert_eq!(invalid.name, "Max-Forwards");
+    assert_eq!(invalid.raw, b"Max-Forwards: seventy");
+    let details = invalid.error.details().unwrap();
+    assert_eq!(details.header, Some("Max-Forwards"));
+    assert_eq!(details.position.unwrap().line, 3);
+
+    // Invalid header is kept on serialization
+    let mut serializer = SipMsgSerializer::new();
+    assert_eq!(serializer.serialize_msg(&msg).unwrap(), &buf[..]);
+
+    let (_, msg) = SipMessage::parse_lenient(
+        b"SIP/2.0 200 OK\r\nVia: SIP/2.0/UDP 10.0.0.1\r\nCSeq: 1\r\n\r\n",
+    )
+    .unwrap();
+    assert_eq!(msg.headers().invalid_headers().len(), 1);
+
+    let e = SipMessage::parse_lenient(b"INVITE sip:bob@biloxi.com SIP/2.0\r\nVia: bad")
+        .err()
+        .unwrap();
+    assert!(matches!(e, nom::Err::Error(_)));
+}
+
+#[cfg(feature = "sdp")]
+#[test]
+fn parse_message_sdp() {
+    let buf = b"INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+        Content-Type: application/sdp\r\n\
+        Content-Length: 87\r\n\r\n\
+        v=0\r\n\
+        o=- 1 1 IN IP4 192.0.2.1\r\n\
+        s=-\r\n\
+        c=IN IP4 192.0.2.1\r\n\
+        t=0 0\r\n\
+        m=audio 5004 RTP/AVP 0\r\n";
+    let (_, msg) = SipMessage::parse(buf).unwrap();
+    let sdp = msg.sdp().unwrap().unwrap();
+    assert_eq!(sdp.media[0].port, 5004);
+
+    let (_, msg) = SipMessage::parse(
+        b"INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+        Content-Type: text/plain\r\n\
+        Content-Length: 3\r\n\r\nv=0",
+    )
+    .unwrap();
+    assert!(msg.sdp().unwrap().is_none());
+
+    let (_, msg) = SipMessage::parse(
+        b"INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+        Content-Type: application/SDP\r\n\
+        Content-Length: 3\r\n\r\nv=1",
+    )
+    .unwrap();
+    assert_eq!(msg.sdp().unwrap_err().kind(), SipParseErrorKind::Body);
+}
@@ -20,9 +20,9 @@ fn parse_request() {
 
     assert_eq!(parsed_req.rl.raw, "INVITE sip:bob@biloxi.com SIP/2.0\r\n".as_bytes());
     assert_eq!(parsed_req.rl.method, SipMethod::INVITE);
-    assert_eq!(parsed_req.rl.uri.scheme, SipRequestUriScheme::SIP);
-    assert_eq!(parsed_req.rl.uri.user_info().unwrap().value, "bob");
-    assert_eq!(parsed_req.rl.uri.hostport.host, "biloxi.com");
+    assert_eq!(parsed_req.rl.uri.sip().unwrap().scheme, SipRequestUriScheme::SIP);
+    assert_eq!(parsed_req.rl.uri.sip().unwrap().user_info().unwrap().value, "bob");
+    assert_eq!(parsed_req.rl.uri.sip().unwrap().hostport.host, "biloxi.com");
     assert_eq!(parsed_req.rl.sip_version, SipVersion(2, 0));
 
     assert_eq!(parsed_req.headers.len(), 9);
@@ -204,10 +204,10 @@ fn get_method_type() {
     let (_, rl) = res.unwrap();
 
     assert_eq!(rl.method, SipMethod::OPTIONS);
-    assert_eq!(rl.uri.scheme, SipRequestUriScheme::SIP);
+    assert_eq!(rl.uri.sip().unwrap().scheme, SipRequestUriScheme::SIP);
     assert_eq!(rl.sip_version, SipVersion(2, 0));
-    assert_eq!(rl.uri.user_info().unwrap().value, "user");
-    assert_eq!(rl.uri.hostport.host, "example.com");
+    assert_eq!(rl.uri.sip().unwrap().user_info().unwrap().value, "user");
+    assert_eq!(rl.uri.sip().unwrap().hostport.host, "example.com");
 
     let res = SipRequestLine::parse(
         "INVITE sips:vivekg@chair-dnrc.example.com;unknownparam SIP/2.0\r\n".as_bytes(),
@@ -215,26 +215,252 @@ fn get_method_type() {
     let (_, rl) = res.unwrap();
 
     assert_eq!(rl.method, SipMethod::INVITE);
-    assert_eq!(rl.uri.scheme, SipRequestUriScheme::SIPS);
+    assert_eq!(rl.uri.sip().unwrap().scheme, SipRequestUriScheme::SIPS);
     assert_eq!(rl.sip_version, SipVersion(2, 0));
-    assert_eq!(rl.uri.user_info().unwrap().value, "vivekg");
-    assert_eq!(rl.uri.hostport.host, "chair-dnrc.example.com");
-    assert_eq!(rl.uri.params().unwrap().get(&"unknownparam"), Some(&None));
+    assert_eq!(rl.uri.sip().unwrap().user_info().unwrap().value, "vivekg");
+    assert_eq!(rl.uri.sip().unwrap().hostport.host, "chair-dnrc.example.com");
+    assert_eq!(rl.uri.sip().unwrap().params().unwrap().get(&"unknownparam"), Some(&None));
 
     let res = SipRequestLine::parse("REGISTER sip:[2001:db8::10]:9999 SIP/3.1\r\n".as_bytes());
     let (_, rl) = res.unwrap();
 
     assert_eq!(rl.method, SipMethod::REGISTER);
-    assert_eq!(rl.uri.scheme, SipRequestUriScheme::SIP);
+    assert_eq!(rl.uri.sip().unwrap().scheme, SipRequestUriScheme::SIP);
     assert_eq!(rl.sip_version, SipVersion(3, 1));
-    assert_eq!(rl.uri.hostport.host, "2001:db8::10");
-    assert_eq!(rl.uri.hostport.port.unwrap(), 9999);
+    assert_eq!(rl.uri.sip().unwrap().hostport.host, "2001:db8::10");
+    assert_eq!(rl.uri.sip().unwrap().hostport.port.unwrap(), 9999);
 }
 
 #[test]
 fn get_method_type_fail() {
-    match SipRequestLine::parse("OPTI2ONS sip:user@example.com SIP/2.0\r\n".as_bytes()) {
+    match SipRequestLine::parse("OPTI\"ONS sip:user@example.com SIP/2.0\r\n".as_bytes()) {
         Ok((_, _)) => panic!(),
         Err(_e) => (),
     }
 }
+
+#[test]
+fn parse_request_extension_method() {
+    let msg_buf = "XPUSH-NOTIFY sip:user@example.com SIP/2.0\r\n\
+                   Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKhjhs8ass877\r\n\
+                   CSeq: 12 XPUSH-NOTIFY\r\n\
+                   Content-Length: 0\r\n\r\n"
+        .as_bytes();
+    let (_, msg) = SipMessage::parse(msg_buf).unwrap();
+    let req = msg.request().unwrap();
+    assert_eq!(req.rl.method, SipMethod::Extension("XPUSH-NOTIFY"));
+    assert_eq!(req.rl.method.as_str(), "XPUSH-NOTIFY");
+    let cseq = req.headers.get_rfc_s(SipRFCHeader::CSeq).unwrap();
+    assert_eq!(
+        cseq.value.tags().unwrap()[&SipHeaderTagType::Method],
+        b"XPUSH-NOTIFY"
+    );
+    assert_eq!(SipMethod::from_str("XPUSH-NOTIFY"), Some(req.rl.method));
+    assert_eq!(SipMethod::from_str("invite"), Some(SipMethod::Extension("invite")));
+    assert_eq!(SipMethod::from_str("IN VITE"), None);
+
+    let mut serializer = SipMsgSerializer::new();
+    assert_eq!(serializer.serialize_msg(&msg).unwrap(), msg_buf);
+}
+
+#[test]
+fn parse_request_content_length() {
+    let msgs_buf = "MESSAGE sip:user2@domain.com SIP/2.0\r\n\
+                    Via: SIP/2.0/TCP user1pc.domain.com;branch=z9hG4bK776sgdkse\r\n\
+                    Max-Forwards: 70\r\n\
+                    From: sip:user1@domain.com;tag=49583\r\n\
+                    To: sip:user2@domain.com\r\n\
+                    Call-ID: asd88asd77a@1.2.3.4\r\n\
+                    CSeq: 1 MESSAGE\r\n\
+                    Content-Type: text/plain\r\n\
+                    l: 18\r\n\r\n\
+                    Watson, come here.\
+                    OPTIONS sip:user@example.com SIP/2.0\r\n\
+                    Via: SIP/2.0/TCP pc33.atlanta.com;branch=z9hG4bKhjhs8ass877\r\n\
+                    Content-Length: 0\r\n\r\n"
+        .as_bytes();
+
+    let (rest, req) = SipRequest::parse(msgs_buf).unwrap();
+    assert_eq!(req.rl.method, SipMethod::MESSAGE);
+    assert_eq!(req.body.unwrap(), b"Watson, come here.");
+
+    let (rest, req) = SipRequest::parse(rest).unwrap();
+    assert_eq!(req.rl.method, SipMethod::OPTIONS);
+    assert_eq!(req.body.unwrap(), b"");
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn parse_request_truncated_body() {
+    let invite_msg_buf = "INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+                          Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKkjshdyff\r\n\
+                          Content-Length: 20\r\n\r\nbody_stuff"
+        .as_bytes();
+    match SipRequest::parse(invite_msg_buf) {
+        Ok((_, _)) => panic!(),
+        Err(_e) => (),
+    }
+
+    let invite_msg_buf = "INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+                          Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKkjshdyff\r\n\
+                          Content-Length: 4\r\n\r\nbody_stuff"
+        .as_bytes();
+    let (rest, req) = SipRequest::parse(invite_msg_buf).unwrap();
+    assert_eq!(req.body.unwrap(), b"body");
+    assert_eq!(rest, b"_stuff");
+}
+
+#[test]
+fn modify_request_as_proxy() {
+    let invite_msg_buf = b"INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+    Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+    Route: <sip:ss1.atlanta.com;lr>, <sip:ss2.biloxi.com;lr>\r\n\
+    Max-Forwards: 70\r\n\
+    To: Bob <sip:bob@biloxi.com>\r\n\
+    From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+    Proxy-Authorization: Digest username=\"alice\", realm=\"atlanta.com\"\r\n\
+    Call-ID: a84b4c76e66710\r\n\
+    CSeq: 314159 INVITE\r\n\
+    Content-Length: 4\r\n\r\nbody";
+    let (_, mut msg) = SipMessage::parse(invite_msg_buf).unwrap();
+
+    let via_line = b"Via: SIP/2.0/UDP ss1.atlanta.com;branch=z9hG4bK2d4790.1\r\n";
+    let rr_line = b"Record-Route: <sip:ss1.atlanta.com;lr>\r\n";
+    let max_forwards_line = b"Max-Forwards: 69\r\n";
+    let new_body = b"new body";
+    let hdr = |line| SipHeader::from_line(line).unwrap();
+
+    let headers = msg.headers_mut();
+    headers.prepend(hdr(via_line));
+    let route = headers.pop_rfc(SipRFCHeader::Route).unwrap();
+    assert_eq!(route.value.vstr, "<sip:ss1.atlanta.com;lr>");
+    let old = headers.replace(hdr(max_forwards_line)).unwrap();
+    assert_eq!(old[0].value.vstr, "70");
+    assert!(headers.remove_rfc(SipRFCHeader::ProxyAuthorization).is_some());
+    assert!(headers.remove_rfc(SipRFCHeader::ProxyAuthorization).is_none());
+    headers.insert(hdr(rr_line));
+    match &mut msg {
+        SipMessage::Request(req) => {
+            let (_, uri) = SipUri::parse(b"sip:bob@192.0.2.4").unwrap();
+            req.set_uri(uri);
+            req.set_body(new_body);
+        }
+        SipMessage::Response(_) => panic!(),
+    }
+
+    let mut serializer = SipMsgSerializer::new();
+    let serialized = serializer.serialize_msg(&msg).unwrap();
+    assert_eq!(
+        serialized,
+        &b"INVITE sip:bob@192.0.2.4 SIP/2.0\r\n\
+        Via: SIP/2.0/UDP ss1.atlanta.com;branch=z9hG4bK2d4790.1\r\n\
+        Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+        Route: <sip:ss2.biloxi.com;lr>\r\n\
+        Max-Forwards: 69\r\n\
+        To: Bob <sip:bob@biloxi.com>\r\n\
+        From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+        Call-ID: a84b4c76e66710\r\n\
+        CSeq: 314159 INVITE\r\n\
+        Content-Length: 8\r\n\
+        Record-Route: <sip:ss1.atlanta.com;lr>\r\n\r\nnew body"[..]
+    );
+    let (_, forwarded) = SipMessage::parse(serialized).unwrap();
+    assert!(forwarded.validate().is_empty());
+}
+
+#[test]
+fn modify_request_line_fields() {
+    let buf = b"OPTIONS sip:bob@biloxi.com SIP/2.0\r\n\
+    Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+    CSeq: 1 OPTIONS\r\n\
+    Content-Length: 0\r\n\r\n";
+    let (_, mut req) = SipRequest::parse(buf).unwrap();
+    // Fields are assigned directly, raw request line is stale
+    req.rl.method = SipMethod::INFO;
+    req.headers.replace(SipHeader::from_line(b"CSeq: 1 INFO\r\n").unwrap());
+    let mut serializer = SipMsgSerializer::new();
+    assert_eq!(
+        serializer.serialize_req(&req).unwrap(),
+        &b"INFO sip:bob@biloxi.com SIP/2.0\r\n\
+        Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+        CSeq: 1 INFO\r\n\
+        Content-Length: 0\r\n\r\n"[..]
+    );
+}
+
+#[test]
+fn parse_request_tel_uri() {
+    let buf = b"INVITE tel:+1-201-555-0123;phone-context=example.com SIP/2.0\r\n\
+        Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+        To: tel:+1-201-555-0123\r\n\
+        From: \"Alice\" <tel:7042;phone-context=atlanta.com;ext=12>;tag=1928301774\r\n\
+        P-Asserted-Identity: <tel:+1-404-555-1111>\r\n\
+        Contact: <sip:alice@pc33.atlanta.com>\r\n\
+        Call-ID: a84b4c76e66710\r\n\
+        CSeq: 314159 INVITE\r\n\
+        Content-Length: 0\r\n\r\n";
+    let (_, req) = SipRequest::parse(buf).unwrap();
+    let ruri = req.rl.uri.tel().unwrap();
+    assert_eq!(req.rl.uri.scheme(), "tel");
+    assert_eq!(ruri.number, "+1-201-555-0123");
+    assert_eq!(ruri.phone_context(), Some("example.com"));
+
+    let to = req.headers.get_rfc_s(SipRFCHeader::To).unwrap();
+    assert_eq!(
+        to.value.uri().unwrap().tel().unwrap().number,
+        "+1-201-555-0123"
+    );
+    assert!(to.value.sip_uri().is_none());
+
+    let from = req.headers.get_rfc_s(SipRFCHeader::From).unwrap();
+    let from_uri = from.value.uri().unwrap().tel().unwrap();
+    assert_eq!(from_uri.number, "7042");
+    assert_eq!(from_uri.extension(), Some("12"));
+    assert_eq!(from.params().unwrap().get("tag"), Some(&Some("1928301774")));
+
+    let pai = req
+        .headers
+        .get_rfc_s(SipRFCHeader::PAssertedIdentity)
+        .unwrap();
+    assert!(pai.value.uri().unwrap().tel().unwrap().is_global());
+
+    let contact = req.headers.get_rfc_s(SipRFCHeader::Contact).unwrap();
+    assert_eq!(contact.value.uri().unwrap().scheme(), "sip");
+
+    let mut serializer = SipMsgSerializer::new();
+    assert_eq!(serializer.serialize_req(&req).unwrap(), &buf[..]);
+}
+
+#[test]
+fn parse_request_absolute_uri() {
+    let buf = b"OPTIONS urn:service:sos SIP/2.0\r\n\
+        Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+        To: <urn:service:sos>\r\n\
+        From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+        Contact: \"Mr. Watson\" <mailto:watson@bell-telephone.com?subject=hi> ;q=0.1\r\n\
+        Call-Info: <http://wwww.example.com/alice/photo.jpg> ;purpose=icon\r\n\
+        Call-ID: a84b4c76e66710\r\n\
+        CSeq: 1 OPTIONS\r\n\r\n";
+    let (_, req) = SipRequest::parse(buf).unwrap();
+    let ruri = req.rl.uri.absolute().unwrap();
+    assert_eq!(ruri.scheme, "urn");
+    assert_eq!(ruri.hier_part, "service:sos");
+
+    let contact = req.headers.get_rfc_s(SipRFCHeader::Contact).unwrap();
+    let contact_uri = contact.value.uri().unwrap().absolute().unwrap();
+    assert_eq!(contact_uri.scheme, "mailto");
+    assert_eq!(contact_uri.hier_part, "watson@bell-telephone.com");
+    assert_eq!(contact_uri.query, Some("subject=hi"));
+
+    let call_info = req.headers.get_rfc_s(SipRFCHeader::CallInfo).unwrap();
+    assert_eq!(call_info.value.uri().unwrap().scheme(), "http");
+
+    // every URI of message can be handled uniformly
+    let schemes: Vec<&str> = req
+        .headers
+        .iter()
+        .filter_map(|h| h.value.uri())
+        .map(|uri| uri.scheme())
+        .collect();
+    assert_eq!(schemes, ["urn", "sip", "mailto", "http"]);
+}
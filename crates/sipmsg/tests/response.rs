@@ -13,6 +13,55 @@ fn status_code_from_bytes_str() {
     );
 }
 
+#[test]
+fn status_code_unregistered() {
+    let code = SipResponseStatusCode::from_str("499");
+    assert!(matches!(code, SipResponseStatusCode::Other(other) if other.code() == 499));
+    assert_eq!(code, SipResponseStatusCode::from_code(499).unwrap());
+    assert_eq!(code.code(), 499);
+    assert_eq!(code.reason_phrase(), "Bad Request");
+    assert_eq!(
+        SipResponseStatusCode::from_str("429"),
+        SipResponseStatusCode::ProvideReferrerIdentity
+    );
+    assert_eq!(SipResponseStatusCode::from_str("608").code(), 608);
+    // Registered codes are never `Other`
+    assert_eq!(
+        SipResponseStatusCode::from_code(200).unwrap(),
+        SipResponseStatusCode::OK
+    );
+    assert_eq!(
+        SipResponseStatusCode::from_str("700"),
+        SipResponseStatusCode::Unknown
+    );
+    assert_eq!(
+        SipResponseStatusCode::from_str("0x1"),
+        SipResponseStatusCode::Unknown
+    );
+    assert!(SipResponseStatusCode::from_code(99).is_err());
+    assert!(SipResponseStatusCode::from_code(700).is_err());
+    assert_eq!(
+        SipResponseStatusCode::from_code(580).unwrap(),
+        SipResponseStatusCode::PreconditionFailure
+    );
+}
+
+#[test]
+fn status_code_class() {
+    let trying = SipResponseStatusCode::Trying;
+    assert_eq!(trying.class(), Some(SipResponseStatusClass::Provisional));
+    assert!(trying.is_provisional() && !trying.is_final());
+    let ok = SipResponseStatusCode::from_str("204");
+    assert!(ok.is_success() && ok.is_final() && !ok.is_error());
+    let redirect = SipResponseStatusCode::from_code(399).unwrap();
+    assert!(redirect.is_redirection() && !redirect.is_error());
+    let failure = SipResponseStatusCode::from_code(699).unwrap();
+    assert_eq!(failure.class(), Some(SipResponseStatusClass::GlobalFailure));
+    assert!(failure.is_error() && failure.is_final());
+    assert_eq!(SipResponseStatusCode::Unknown.class(), None);
+    assert!(!SipResponseStatusCode::Unknown.is_final());
+}
+
 #[test]
 fn status_code_from_str() {
     assert_eq!(
@@ -34,6 +83,20 @@ fn status_line() {
     }
 }
 
+#[test]
+fn status_line_unregistered_code() {
+    let (_, status_line) = SipResponseStatusLine::parse(b"SIP/2.0 499 Custom Reason\r\n").unwrap();
+    assert_eq!(status_line.status_code.code(), 499);
+    assert!(matches!(
+        status_line.status_code,
+        SipResponseStatusCode::Other(_)
+    ));
+    assert_eq!(status_line.reason_phrase, "Custom Reason");
+    assert!(SipResponseStatusLine::parse(b"SIP/2.0 099 Too Low\r\n").is_err());
+    assert!(SipResponseStatusLine::parse(b"SIP/2.0 7xx Bad\r\n").is_err());
+    assert!(SipResponseStatusLine::parse(b"SIP/2.256 200 OK\r\n").is_err());
+}
+
 #[test]
 fn parse_response() {
     let response_msg = "SIP/2.0 401 Unauthorized\r\n\
@@ -188,3 +251,26 @@ fn parse_response() {
         Err(_e) => panic!(),
     }
 }
+
+#[test]
+fn parse_response_content_length() {
+    let response_msg = "SIP/2.0 200 OK\r\n\
+    Via: SIP/2.0/UDP server10.biloxi.com;branch=z9hG4bKnashds8\r\n\
+    Content-Type: text/plain\r\n\
+    Content-Length: 5\r\n\r\n\
+    helloSIP/2.0 180 Ringing\r\n";
+
+    let (rest, response) = SipResponse::parse(response_msg.as_bytes()).unwrap();
+    assert_eq!(response.sl.status_code, SipResponseStatusCode::OK);
+    assert_eq!(response.body.unwrap(), b"hello");
+    assert_eq!(rest, b"SIP/2.0 180 Ringing\r\n");
+
+    let response_msg = "SIP/2.0 200 OK\r\n\
+    Via: SIP/2.0/UDP server10.biloxi.com;branch=z9hG4bKnashds8\r\n\
+    l: 6\r\n\r\n\
+    hello";
+    match SipResponse::parse(response_msg.as_bytes()) {
+        Ok((_, _)) => panic!(),
+        Err(_e) => (),
+    }
+}
@@ -31,14 +31,14 @@ fn parse_request() {
           newvalue ;\r\n \
           secondparam ; q = 0.33\r\n\
     \r\n\
-    v=0\
-    o=mhandley 29739 7272939 IN IP4 192.0.2.3\
-    s=-\
-    c=IN IP4 192.0.2.4\
-    t=0 0\
-    m=audio 49217 RTP/AVP 0 12\
-    m=video 3227 RTP/AVP 31\
-    a=rtpmap:31 LPC"
+    v=0\r\n\
+    o=mhandley 29739 7272939 IN IP4 192.0.2.3\r\n\
+    s=-\r\n\
+    c=IN IP4 192.0.2.4\r\n\
+    t=0 0\r\n\
+    m=audio 49217 RTP/AVP 0 12\r\n\
+    m=video 3227 RTP/AVP 31\r\n\
+    a=rtpmap:31 LPC\r\n"
         .as_bytes();
 
     let res = SipRequest::parse(invite_msg_buf);
@@ -46,12 +46,27 @@ fn parse_request() {
     let request_line = &parsed_req.rl;
     let headers = &parsed_req.headers;
     assert_eq!(request_line.method, SipMethod::INVITE);
-    assert_eq!(request_line.uri.scheme, SipRequestUriScheme::SIP);
-    assert_eq!(request_line.uri.user_info().unwrap().value, "vivekg");
-    assert_eq!(request_line.uri.hostport.host, "chair-dnrc.example.com");
+    assert_eq!(
+        request_line.uri.sip().unwrap().scheme,
+        SipRequestUriScheme::SIP
+    );
+    assert_eq!(
+        request_line.uri.sip().unwrap().user_info().unwrap().value,
+        "vivekg"
+    );
+    assert_eq!(
+        request_line.uri.sip().unwrap().hostport.host,
+        "chair-dnrc.example.com"
+    );
     assert_eq!(request_line.sip_version, SipVersion(2, 0));
     assert_eq!(
-        request_line.uri.params().unwrap().get(&"unknownparam"),
+        request_line
+            .uri
+            .sip()
+            .unwrap()
+            .params()
+            .unwrap()
+            .get(&"unknownparam"),
         Some(&None)
     );
 
@@ -216,14 +231,14 @@ fn parse_request() {
     /*********************************************************/
     assert_eq!(
         parsed_req.body.unwrap(),
-        "v=0\
-    o=mhandley 29739 7272939 IN IP4 192.0.2.3\
-    s=-\
-    c=IN IP4 192.0.2.4\
-    t=0 0\
-    m=audio 49217 RTP/AVP 0 12\
-    m=video 3227 RTP/AVP 31\
-    a=rtpmap:31 LPC"
+        "v=0\r\n\
+    o=mhandley 29739 7272939 IN IP4 192.0.2.3\r\n\
+    s=-\r\n\
+    c=IN IP4 192.0.2.4\r\n\
+    t=0 0\r\n\
+    m=audio 49217 RTP/AVP 0 12\r\n\
+    m=video 3227 RTP/AVP 31\r\n\
+    a=rtpmap:31 LPC\r\n"
             .as_bytes()
     );
 }
@@ -0,0 +1,154 @@
+use sipmsg::*;
+
+#[test]
+fn validate_valid_messages() {
+    let (_, msg) = SipMessage::parse(
+        b"INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+        Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+        Max-Forwards: 70\r\n\
+        To: Bob <sip:bob@biloxi.com>\r\n\
+        From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+        Call-ID: a84b4c76e66710\r\n\
+        CSeq: 314159 INVITE\r\n\
+        Content-Length: 4\r\n\r\nbody",
+    )
+    .unwrap();
+    assert!(msg.validate().is_empty());
+
+    let (_, msg) = SipMessage::parse(
+        b"SIP/2.0 180 Ringing\r\n\
+        Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+        To: Bob <sip:bob@biloxi.com>;tag=a6c85cf\r\n\
+        From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+        Call-ID: a84b4c76e66710\r\n\
+        CSeq: 314159 INVITE\r\n\
+        Content-Length: 0\r\n\r\n",
+    )
+    .unwrap();
+    assert!(msg.validate().is_empty());
+}
+
+#[test]
+fn validate_request_violations() {
+    let (_, req) = SipRequest::parse(
+        b"INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+        Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+        To: Bob <sip:bob@biloxi.com>\r\n\
+        To: Carol <sip:carol@chicago.com>\r\n\
+        Call-ID: a84b4c76e66710\r\n\
+        CSeq: 314159 BYE\r\n\r\n",
+    )
+    .unwrap();
+    let violations = req.validate();
+    assert_eq!(
+        violations,
+        [
+            SipViolation::MissingHeader(SipRFCHeader::From),
+            SipViolation::MissingHeader(SipRFCHeader::MaxForwards),
+            SipViolation::DuplicateHeader(SipRFCHeader::To),
+            SipViolation::CSeqMethodMismatch,
+        ]
+    );
+    assert_eq!(
+        violations[0].status_code(),
+        SipResponseStatusCode::BadRequest
+    );
+    assert_eq!(violations[0].to_string(), "missing header From");
+}
+
+#[test]
+fn validate_version_and_invalid_headers() {
+    let (_, req) = SipRequest::parse_lenient(
+        b"OPTIONS sip:bob@biloxi.com SIP/3.0\r\n\
+        Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+        Max-Forwards: 70\r\n\
+        To: Bob <sip:bob@biloxi.com>\r\n\
+        From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+        Call-ID: a84b4c76e66710\r\n\
+        CSeq: OPTIONS\r\n\r\n",
+    )
+    .unwrap();
+    let violations = req.validate();
+    assert_eq!(
+        violations,
+        [
+            SipViolation::UnsupportedVersion(SipVersion(3, 0)),
+            SipViolation::InvalidHeader("CSeq"),
+        ]
+    );
+    assert_eq!(
+        violations[0].status_code(),
+        SipResponseStatusCode::VersionNotSupported
+    );
+}
+
+#[test]
+fn validate_content_length() {
+    let (_, mut resp) = SipResponse::parse(
+        b"SIP/2.0 200 OK\r\n\
+        Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+        To: Bob <sip:bob@biloxi.com>;tag=a6c85cf\r\n\
+        From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+        Call-ID: a84b4c76e66710\r\n\
+        CSeq: 314159 INVITE\r\n\
+        Content-Length: 0\r\n\r\n",
+    )
+    .unwrap();
+    resp.set_body(b"body");
+    assert_eq!(
+        resp.validate(),
+        [SipViolation::ContentLengthMismatch { header: 0, body: 4 }]
+    );
+
+    // Parsed body always matches Content-Length, the rest of input is returned
+    let (rest, mut req) = SipRequest::parse(
+        b"MESSAGE sip:bob@biloxi.com SIP/2.0\r\n\
+        Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+        Max-Forwards: 70\r\n\
+        To: <sip:bob@biloxi.com>\r\n\
+        From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+        Call-ID: a84b4c76e66710\r\n\
+        CSeq: 1 MESSAGE\r\n\
+        Content-Length: 5\r\n\r\nhello world",
+    )
+    .unwrap();
+    assert_eq!(rest, b" world");
+    assert!(req.validate().is_empty());
+    req.set_body(b"hello world");
+    let violations = req.validate();
+    assert_eq!(
+        violations,
+        [SipViolation::ContentLengthMismatch {
+            header: 5,
+            body: 11
+        }]
+    );
+    assert_eq!(
+        violations[0].to_string(),
+        "Content-Length 5 doesn't match body length 11"
+    );
+    assert_eq!(
+        violations[0].status_code(),
+        SipResponseStatusCode::BadRequest
+    );
+}
+
+#[test]
+fn validate_uri_scheme() {
+    let (_, req) = SipRequest::parse(
+        b"MESSAGE im:bob@biloxi.com SIP/2.0\r\n\
+        Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+        Max-Forwards: 70\r\n\
+        To: <im:bob@biloxi.com>\r\n\
+        From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+        Call-ID: a84b4c76e66710\r\n\
+        CSeq: 1 MESSAGE\r\n\r\n",
+    )
+    .unwrap();
+    let violations = req.validate();
+    assert_eq!(violations, [SipViolation::UnsupportedUriScheme("im")]);
+    assert_eq!(
+        violations[0].status_code(),
+        SipResponseStatusCode::UnsupportedUriScheme
+    );
+}